@@ -0,0 +1,238 @@
+use std::{path::Path, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use cluster_lend::{
+    utils::{BorrowRateCurve, CurvePoint},
+    AssetTier, LendingMarket, PythConfiguration, ReserveConfig, ReserveFees, ReserveStatus,
+    TokenInfo, WithdrawalCaps,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Loads a TOML or JSON file, picking the format from the file extension.
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&contents)
+            .with_context(|| format!("parsing {} as JSON", path.display())),
+        Some("toml") => {
+            toml::from_str(&contents).with_context(|| format!("parsing {} as TOML", path.display()))
+        }
+        _ => bail!("{}: expected a .toml or .json file", path.display()),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReserveStatusFile {
+    Active,
+    Obsolete,
+    Hidden,
+    ReduceOnly,
+}
+
+impl From<ReserveStatusFile> for ReserveStatus {
+    fn from(status: ReserveStatusFile) -> Self {
+        match status {
+            ReserveStatusFile::Active => ReserveStatus::Active,
+            ReserveStatusFile::Obsolete => ReserveStatus::Obsolete,
+            ReserveStatusFile::Hidden => ReserveStatus::Hidden,
+            ReserveStatusFile::ReduceOnly => ReserveStatus::ReduceOnly,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetTierFile {
+    Regular,
+    IsolatedCollateral,
+    IsolatedDebt,
+}
+
+impl From<AssetTierFile> for AssetTier {
+    fn from(tier: AssetTierFile) -> Self {
+        match tier {
+            AssetTierFile::Regular => AssetTier::Regular,
+            AssetTierFile::IsolatedCollateral => AssetTier::IsolatedCollateral,
+            AssetTierFile::IsolatedDebt => AssetTier::IsolatedDebt,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct WithdrawalCapFile {
+    pub capacity: i64,
+    pub interval_length_seconds: u64,
+}
+
+/// Human-editable description of a `ReserveConfig`.
+///
+/// Runtime state stored next to the config (withdrawal cap totals and interval starts) is
+/// not part of the file and is always taken from the chain.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ReserveConfigFile {
+    pub name: String,
+    pub status: ReserveStatusFile,
+    pub asset_tier: AssetTierFile,
+
+    pub loan_to_value_pct: u8,
+    pub liquidation_threshold_pct: u8,
+    pub min_liquidation_bonus_bps: u16,
+    pub max_liquidation_bonus_bps: u16,
+    pub bad_debt_liquidation_bonus_bps: u16,
+    pub protocol_take_rate_pct: u8,
+    pub protocol_liquidation_fee_pct: u8,
+    #[serde(default)]
+    pub insurance_fee_pct: u8,
+
+    pub deleveraging_margin_call_period_secs: u64,
+    pub deleveraging_threshold_slots_per_bps: u64,
+
+    pub borrow_fee_sf: u64,
+    pub flash_loan_fee_sf: u64,
+    pub borrow_factor_pct: u64,
+
+    pub deposit_limit: u64,
+    pub borrow_limit: u64,
+
+    /// `[utilization_rate_bps, borrow_rate_bps]` pairs, padded to 11 points on-chain.
+    pub borrow_rate_curve: Vec<[u32; 2]>,
+
+    pub pyth_price: String,
+    pub max_age_price_seconds: u64,
+    #[serde(default)]
+    pub max_twap_divergence_bps: u64,
+    #[serde(default)]
+    pub max_age_twap_seconds: u64,
+
+    #[serde(default)]
+    pub deposit_withdrawal_cap: WithdrawalCapFile,
+    #[serde(default)]
+    pub debt_withdrawal_cap: WithdrawalCapFile,
+}
+
+pub fn token_name(name: &str) -> Result<[u8; 32]> {
+    let bytes = name.as_bytes();
+    if bytes.len() > 32 {
+        bail!("token name {name:?} is longer than 32 bytes");
+    }
+    let mut value = [0; 32];
+    value[..bytes.len()].copy_from_slice(bytes);
+    Ok(value)
+}
+
+impl ReserveConfigFile {
+    /// Builds the on-chain config. Runtime fields of the withdrawal caps are copied from
+    /// `current` when given, so that applying the file does not reset them.
+    pub fn to_reserve_config(&self, current: Option<&ReserveConfig>) -> Result<ReserveConfig> {
+        let points = self
+            .borrow_rate_curve
+            .iter()
+            .map(|[utilization_rate_bps, borrow_rate_bps]| {
+                CurvePoint::new(*utilization_rate_bps, *borrow_rate_bps)
+            })
+            .collect::<Vec<_>>();
+        let borrow_rate_curve = BorrowRateCurve::from_points(&points)
+            .map_err(|e| anyhow!("invalid borrow rate curve: {e}"))?;
+
+        let pyth_price = Pubkey::from_str(&self.pyth_price)
+            .map_err(|e| anyhow!("invalid pyth_price {:?}: {e}", self.pyth_price))?;
+
+        let withdrawal_cap =
+            |file: &WithdrawalCapFile, current: Option<&WithdrawalCaps>| WithdrawalCaps {
+                config_capacity: file.capacity,
+                config_interval_length_seconds: file.interval_length_seconds,
+                current_total: current.map_or(0, |c| c.current_total),
+                last_interval_start_timestamp: current
+                    .map_or(0, |c| c.last_interval_start_timestamp),
+            };
+
+        Ok(ReserveConfig {
+            status: ReserveStatus::from(self.status).into(),
+            asset_tier: AssetTier::from(self.asset_tier).into(),
+            protocol_take_rate_pct: self.protocol_take_rate_pct,
+            protocol_liquidation_fee_pct: self.protocol_liquidation_fee_pct,
+            loan_to_value_pct: self.loan_to_value_pct,
+            liquidation_threshold_pct: self.liquidation_threshold_pct,
+            min_liquidation_bonus_bps: self.min_liquidation_bonus_bps,
+            max_liquidation_bonus_bps: self.max_liquidation_bonus_bps,
+            bad_debt_liquidation_bonus_bps: self.bad_debt_liquidation_bonus_bps,
+            insurance_fee_pct: self.insurance_fee_pct,
+            deleveraging_margin_call_period_secs: self.deleveraging_margin_call_period_secs,
+            deleveraging_threshold_slots_per_bps: self.deleveraging_threshold_slots_per_bps,
+            fees: ReserveFees {
+                borrow_fee_sf: self.borrow_fee_sf,
+                flash_loan_fee_sf: self.flash_loan_fee_sf,
+                padding: [0; 8],
+            },
+            borrow_rate_curve,
+            borrow_factor_pct: self.borrow_factor_pct,
+            deposit_limit: self.deposit_limit,
+            borrow_limit: self.borrow_limit,
+            token_info: TokenInfo {
+                name: token_name(&self.name)?,
+                max_twap_divergence_bps: self.max_twap_divergence_bps,
+                max_age_price_seconds: self.max_age_price_seconds,
+                max_age_twap_seconds: self.max_age_twap_seconds,
+                pyth_configuration: PythConfiguration { price: pyth_price },
+                _padding: [0; 20],
+            },
+            deposit_withdrawal_cap: withdrawal_cap(
+                &self.deposit_withdrawal_cap,
+                current.map(|c| &c.deposit_withdrawal_cap),
+            ),
+            debt_withdrawal_cap: withdrawal_cap(
+                &self.debt_withdrawal_cap,
+                current.map(|c| &c.debt_withdrawal_cap),
+            ),
+            ..current.copied().unwrap_or_default()
+        })
+    }
+}
+
+/// Market parameters managed by the CLI. Fields left out of the file are not touched.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct MarketConfigFile {
+    pub emergency_mode: Option<bool>,
+    pub autodeleverage_enabled: Option<bool>,
+    pub borrow_disabled: Option<bool>,
+    pub liquidation_max_debt_close_factor_pct: Option<u8>,
+    pub insolvency_risk_unhealthy_ltv_pct: Option<u8>,
+    pub price_refresh_trigger_to_max_age_pct: Option<u8>,
+    pub max_liquidatable_debt_market_value_at_once: Option<u64>,
+    pub global_unhealthy_borrow_value: Option<u64>,
+    pub global_allowed_borrow_value: Option<u64>,
+    pub min_full_liquidation_value_threshold: Option<u64>,
+    pub min_net_value_in_obligation_sf: Option<u128>,
+    pub config_timelock_secs: Option<u64>,
+    pub liquidation_bonus_ramp_slots: Option<u64>,
+}
+
+impl MarketConfigFile {
+    pub fn from_market(market: &LendingMarket) -> Self {
+        Self {
+            emergency_mode: Some(market.emergency_mode != 0),
+            autodeleverage_enabled: Some(market.autodeleverage_enabled != 0),
+            borrow_disabled: Some(market.borrow_disabled != 0),
+            liquidation_max_debt_close_factor_pct: Some(
+                market.liquidation_max_debt_close_factor_pct,
+            ),
+            insolvency_risk_unhealthy_ltv_pct: Some(market.insolvency_risk_unhealthy_ltv_pct),
+            price_refresh_trigger_to_max_age_pct: Some(market.price_refresh_trigger_to_max_age_pct),
+            max_liquidatable_debt_market_value_at_once: Some(
+                market.max_liquidatable_debt_market_value_at_once,
+            ),
+            global_unhealthy_borrow_value: Some(market.global_unhealthy_borrow_value),
+            global_allowed_borrow_value: Some(market.global_allowed_borrow_value),
+            min_full_liquidation_value_threshold: Some(market.min_full_liquidation_value_threshold),
+            min_net_value_in_obligation_sf: Some(market.min_net_value_in_obligation_sf),
+            config_timelock_secs: Some(market.config_timelock_secs),
+            liquidation_bonus_ramp_slots: Some(market.liquidation_bonus_ramp_slots),
+        }
+    }
+}
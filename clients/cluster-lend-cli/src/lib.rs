@@ -0,0 +1,8 @@
+//! Config-file driven administration of cluster-lend markets.
+//!
+//! [`config`] holds the TOML/JSON file formats, [`plan`] diffs them against on-chain state
+//! and builds the `update_market`/`update_reserve_mode` instructions. Both are independent of
+//! the RPC transport so they can be driven from `solana-program-test` as well as the binary.
+
+pub mod config;
+pub mod plan;
@@ -0,0 +1,642 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use cluster_lend::{
+    FeeShare, MarketRole, QueuedUpdateKind, ReserveConfigUpdate, ReservePauseFlags,
+};
+use cluster_lend_cli::{
+    config::{self, MarketConfigFile, ReserveConfigFile},
+    plan,
+};
+use cluster_lend_client::{accounts, instructions, pda};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "cluster-lend-cli",
+    about = "Manage cluster-lend markets and reserves"
+)]
+struct Cli {
+    /// RPC endpoint, e.g. http://127.0.0.1:8899 for a local validator.
+    #[arg(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Market owner keypair, also used to pay for transactions.
+    #[arg(long, short = 'k')]
+    keypair: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a new lending market owned by the keypair.
+    CreateMarket {
+        /// Quote currency symbol, e.g. USD.
+        #[arg(long, default_value = "USD")]
+        quote_currency: String,
+        /// Keypair for the new market account. A fresh one is generated when omitted.
+        #[arg(long)]
+        market_keypair: Option<PathBuf>,
+    },
+    /// Create the reserve registry of a market created before registries existed.
+    InitReserveRegistry {
+        #[arg(long)]
+        market: Pubkey,
+        /// Reserves already listed on the market, registered in the given order.
+        #[arg(long = "reserve")]
+        reserves: Vec<Pubkey>,
+    },
+    /// Propose a new market owner. Ownership moves once they run `accept-owner`.
+    ProposeOwner {
+        #[arg(long)]
+        market: Pubkey,
+        #[arg(long)]
+        new_owner: Pubkey,
+    },
+    /// Accept a pending ownership transfer, signed by the proposed owner's keypair.
+    AcceptOwner {
+        #[arg(long)]
+        market: Pubkey,
+    },
+    /// Cancel a pending ownership transfer.
+    CancelOwner {
+        #[arg(long)]
+        market: Pubkey,
+    },
+    /// Execute a queued config update or insurance withdrawal whose timelock has expired.
+    ExecuteQueued {
+        #[arg(long)]
+        queued_update: Pubkey,
+    },
+    /// Cancel a queued config update.
+    CancelQueued {
+        #[arg(long)]
+        queued_update: Pubkey,
+    },
+    /// Print every reserve registered on a market.
+    ListReserves {
+        #[arg(long)]
+        market: Pubkey,
+    },
+    /// List a new reserve for `mint` and configure it from a TOML/JSON file.
+    ListReserve {
+        #[arg(long)]
+        market: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        config: PathBuf,
+        /// Keypair for the new reserve account. A fresh one is generated when omitted.
+        #[arg(long)]
+        reserve_keypair: Option<PathBuf>,
+        /// Owner token account of `mint` funding the locked seed deposit.
+        #[arg(long)]
+        seed_source: Pubkey,
+    },
+    /// Replace the set of paused operations on a reserve. Without `--operation` every
+    /// operation is resumed.
+    PauseReserve {
+        #[arg(long)]
+        reserve: Pubkey,
+        /// deposit, redeem, borrow, repay, deposit_collateral, withdraw_collateral,
+        /// liquidation or flash_loan.
+        #[arg(long = "operation")]
+        operations: Vec<String>,
+    },
+    /// Close an emptied reserve and remove it from the market's registry.
+    CloseReserve {
+        #[arg(long)]
+        reserve: Pubkey,
+        /// Owner token account of the liquidity mint receiving the leftover fees.
+        #[arg(long)]
+        destination: Pubkey,
+    },
+    /// Move tokens sent straight to a reserve's vaults out of them.
+    SkimReserve {
+        #[arg(long)]
+        reserve: Pubkey,
+        /// Liquidity mint account receiving the excess. Defaults to the reserve fee vault.
+        #[arg(long)]
+        liquidity_destination: Option<Pubkey>,
+        /// Collateral mint account receiving stray cTokens.
+        #[arg(long)]
+        collateral_destination: Pubkey,
+    },
+    /// Create a reserve's insurance vault.
+    InitInsurance {
+        #[arg(long)]
+        reserve: Pubkey,
+    },
+    /// Top up a reserve's insurance vault from an owner token account.
+    DepositInsurance {
+        #[arg(long)]
+        reserve: Pubkey,
+        #[arg(long)]
+        source: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Withdraw from a reserve's insurance vault, capped at its balance. Queued on markets with a
+    /// config timelock.
+    WithdrawInsurance {
+        #[arg(long)]
+        reserve: Pubkey,
+        #[arg(long)]
+        destination: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Write off the debt an obligation without collateral owes a reserve.
+    SocializeLoss {
+        #[arg(long)]
+        obligation: Pubkey,
+        #[arg(long)]
+        reserve: Pubkey,
+    },
+    /// Create or replace the market's fee recipients.
+    SetFeeDistribution {
+        #[arg(long)]
+        market: Pubkey,
+        /// `<wallet>=<bps>`, repeated. Shares must add up to 10000.
+        #[arg(long = "recipient")]
+        recipients: Vec<String>,
+    },
+    /// Split a reserve's fee vault between the market's fee recipients.
+    DistributeFees {
+        #[arg(long)]
+        reserve: Pubkey,
+        /// Liquidity mint account of each recipient, in fee distribution order.
+        #[arg(long = "account")]
+        accounts: Vec<Pubkey>,
+    },
+    /// Print the differences between an on-chain reserve and a config file.
+    DiffReserve {
+        #[arg(long)]
+        reserve: Pubkey,
+        #[arg(long)]
+        config: PathBuf,
+    },
+    /// Apply a config file to an on-chain reserve.
+    ApplyReserve {
+        #[arg(long)]
+        reserve: Pubkey,
+        #[arg(long)]
+        config: PathBuf,
+    },
+    /// Print the differences between an on-chain market and a config file.
+    DiffMarket {
+        #[arg(long)]
+        market: Pubkey,
+        #[arg(long)]
+        config: PathBuf,
+    },
+    /// Apply a config file to an on-chain market.
+    ApplyMarket {
+        #[arg(long)]
+        market: Pubkey,
+        #[arg(long)]
+        config: PathBuf,
+    },
+}
+
+struct Admin {
+    rpc: RpcClient,
+    payer: Keypair,
+}
+
+impl Admin {
+    fn send(&self, ixs: &[Instruction], extra_signers: &[&Keypair]) -> Result<()> {
+        let mut signers = vec![&self.payer];
+        signers.extend_from_slice(extra_signers);
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.payer.pubkey()),
+            &signers,
+            blockhash,
+        );
+        let signature = self
+            .rpc
+            .send_and_confirm_transaction(&tx)
+            .context("sending transaction")?;
+        println!("{signature}");
+        Ok(())
+    }
+
+    /// Sends the instruction built by `build` for a fresh queued update account.
+    fn queue(&self, build: impl FnOnce(&Pubkey) -> Instruction) -> Result<()> {
+        let queued_update = Keypair::new();
+        self.send(&[build(&queued_update.pubkey())], &[&queued_update])?;
+        println!("queued: {}", queued_update.pubkey());
+        Ok(())
+    }
+
+    fn account_data(&self, address: &Pubkey) -> Result<Vec<u8>> {
+        self.rpc
+            .get_account_data(address)
+            .with_context(|| format!("fetching account {address}"))
+    }
+}
+
+fn keypair_or_new(path: Option<PathBuf>) -> Result<Keypair> {
+    match path {
+        Some(path) => read_keypair_file(&path)
+            .map_err(|e| anyhow::anyhow!("reading keypair {}: {e}", path.display())),
+        None => Ok(Keypair::new()),
+    }
+}
+
+fn parse_fee_share(value: &str) -> Result<FeeShare> {
+    let (recipient, share_bps) = value
+        .split_once('=')
+        .with_context(|| format!("expected <wallet>=<bps>, got {value:?}"))?;
+    Ok(FeeShare {
+        recipient: recipient
+            .parse()
+            .with_context(|| format!("invalid recipient {recipient:?}"))?,
+        share_bps: share_bps
+            .parse()
+            .with_context(|| format!("invalid share {share_bps:?}"))?,
+    })
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let payer = read_keypair_file(&cli.keypair)
+        .map_err(|e| anyhow::anyhow!("reading keypair {}: {e}", cli.keypair.display()))?;
+    let ctx = Admin {
+        rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        payer,
+    };
+    let owner = ctx.payer.pubkey();
+
+    match cli.command {
+        Command::CreateMarket {
+            quote_currency,
+            market_keypair,
+        } => {
+            let market = keypair_or_new(market_keypair)?;
+            let ix = instructions::initialize_market(
+                &owner,
+                &market.pubkey(),
+                config::token_name(&quote_currency)?,
+            );
+            let registry_ix =
+                instructions::initialize_reserve_registry(&owner, &market.pubkey(), &[]);
+            ctx.send(&[ix, registry_ix], &[&market])?;
+            println!("market: {}", market.pubkey());
+        }
+        Command::InitReserveRegistry { market, reserves } => {
+            let ix = instructions::initialize_reserve_registry(&owner, &market, &reserves);
+            ctx.send(&[ix], &[])?;
+        }
+        Command::ProposeOwner { market, new_owner } => {
+            let ix =
+                instructions::propose_market_role(&owner, &market, MarketRole::Owner, &new_owner);
+            ctx.send(&[ix], &[])?;
+        }
+        Command::AcceptOwner { market } => {
+            let ix = instructions::accept_market_role(&owner, &market, MarketRole::Owner);
+            ctx.send(&[ix], &[])?;
+        }
+        Command::CancelOwner { market } => {
+            let ix = instructions::cancel_market_role(&owner, &market, MarketRole::Owner);
+            ctx.send(&[ix], &[])?;
+        }
+        Command::ExecuteQueued { queued_update } => {
+            let queued_state = accounts::queued_update(&ctx.account_data(&queued_update)?)?;
+            let ix = match queued_state.update {
+                QueuedUpdateKind::InsuranceWithdrawal { reserve, .. } => {
+                    let reserve_state = accounts::reserve(&ctx.account_data(&reserve)?)?;
+                    instructions::execute_insurance_withdrawal(
+                        &queued_update,
+                        &queued_state,
+                        &reserve_state,
+                    )
+                }
+                QueuedUpdateKind::Market(_) | QueuedUpdateKind::Reserve { .. } => {
+                    let market_state =
+                        accounts::lending_market(&ctx.account_data(&queued_state.lending_market)?)?;
+                    instructions::execute_queued_update(
+                        &queued_update,
+                        &queued_state,
+                        instructions::market_reserve_registry(&market_state),
+                    )
+                }
+            };
+            ctx.send(&[ix], &[])?;
+        }
+        Command::CancelQueued { queued_update } => {
+            let queued_state = accounts::queued_update(&ctx.account_data(&queued_update)?)?;
+            let ix = instructions::cancel_queued_update(&owner, &queued_update, &queued_state);
+            ctx.send(&[ix], &[])?;
+        }
+        Command::ListReserves { market } => {
+            let registry =
+                accounts::reserve_registry(&ctx.account_data(&pda::reserve_registry(&market))?)?;
+            for entry in registry.reserves() {
+                println!(
+                    "{} mint={} status={:?} created_slot={}",
+                    entry.reserve,
+                    entry.mint,
+                    entry.status(),
+                    entry.creation_slot
+                );
+            }
+        }
+        Command::ListReserve {
+            market,
+            mint,
+            config,
+            reserve_keypair,
+            seed_source,
+        } => {
+            let file: ReserveConfigFile = config::load(&config)?;
+            let desired = file.to_reserve_config(None)?;
+            let reserve = keypair_or_new(reserve_keypair)?;
+
+            let mint_account = ctx
+                .rpc
+                .get_account(&mint)
+                .with_context(|| format!("fetching mint {mint}"))?;
+            let reserve_registry = instructions::market_reserve_registry(
+                &accounts::lending_market(&ctx.account_data(&market)?)?,
+            );
+            let init_ix = instructions::initialize_reserve(
+                &owner,
+                &market,
+                reserve_registry,
+                &reserve.pubkey(),
+                &mint,
+                &mint_account.owner,
+                &seed_source,
+            );
+            ctx.send(&[init_ix], &[&reserve])?;
+
+            let update_ix = instructions::update_reserve(
+                &owner,
+                &market,
+                reserve_registry,
+                &reserve.pubkey(),
+                &desired,
+            );
+            ctx.send(&[update_ix], &[])?;
+            println!("reserve: {}", reserve.pubkey());
+        }
+        Command::PauseReserve {
+            reserve,
+            operations,
+        } => {
+            let paused =
+                operations
+                    .iter()
+                    .try_fold(ReservePauseFlags::empty(), |paused, operation| {
+                        ReservePauseFlags::from_name(&operation.to_uppercase())
+                            .map(|flag| paused | flag)
+                            .with_context(|| format!("unknown operation {operation:?}"))
+                    })?;
+            let reserve_state = accounts::reserve(&ctx.account_data(&reserve)?)?;
+            let market = reserve_state.lending_market;
+            let update = ReserveConfigUpdate::PausedOperations(paused.bits());
+
+            let market_state = accounts::lending_market(&ctx.account_data(&market)?)?;
+            if market_state.has_config_timelock()
+                && !update.is_timelock_exempt(&reserve_state.config)
+            {
+                ctx.queue(|queued_update| {
+                    instructions::queue_reserve_update(
+                        &owner,
+                        &market,
+                        &reserve,
+                        queued_update,
+                        update,
+                    )
+                })?;
+            } else {
+                let ix = instructions::update_reserve_config(
+                    &owner,
+                    &market,
+                    instructions::market_reserve_registry(&market_state),
+                    &reserve,
+                    update,
+                );
+                ctx.send(&[ix], &[])?;
+            }
+        }
+        Command::CloseReserve {
+            reserve,
+            destination,
+        } => {
+            let reserve_state = accounts::reserve(&ctx.account_data(&reserve)?)?;
+            let market_state =
+                accounts::lending_market(&ctx.account_data(&reserve_state.lending_market)?)?;
+            let ix = instructions::close_reserve(
+                &owner,
+                &reserve,
+                &reserve_state,
+                instructions::market_reserve_registry(&market_state),
+                &destination,
+            );
+            ctx.send(&[ix], &[])?;
+        }
+        Command::SkimReserve {
+            reserve,
+            liquidity_destination,
+            collateral_destination,
+        } => {
+            let reserve_state = accounts::reserve(&ctx.account_data(&reserve)?)?;
+            let liquidity_destination =
+                liquidity_destination.unwrap_or(reserve_state.liquidity.fee_vault);
+            let ix = instructions::skim_reserve_excess(
+                &owner,
+                &reserve,
+                &reserve_state,
+                &liquidity_destination,
+                &collateral_destination,
+            );
+            ctx.send(&[ix], &[])?;
+        }
+        Command::InitInsurance { reserve } => {
+            let reserve_state = accounts::reserve(&ctx.account_data(&reserve)?)?;
+            let ix = instructions::initialize_reserve_insurance(&owner, &reserve, &reserve_state);
+            ctx.send(&[ix], &[])?;
+        }
+        Command::DepositInsurance {
+            reserve,
+            source,
+            amount,
+        } => {
+            let reserve_state = accounts::reserve(&ctx.account_data(&reserve)?)?;
+            let ix = instructions::deposit_reserve_insurance(
+                &owner,
+                &reserve,
+                &reserve_state,
+                &source,
+                amount,
+            );
+            ctx.send(&[ix], &[])?;
+        }
+        Command::WithdrawInsurance {
+            reserve,
+            destination,
+            amount,
+        } => {
+            let reserve_state = accounts::reserve(&ctx.account_data(&reserve)?)?;
+            let market_state =
+                accounts::lending_market(&ctx.account_data(&reserve_state.lending_market)?)?;
+            if market_state.has_config_timelock() {
+                ctx.queue(|queued_update| {
+                    instructions::queue_insurance_withdrawal(
+                        &owner,
+                        &reserve,
+                        &reserve_state,
+                        &destination,
+                        queued_update,
+                        amount,
+                    )
+                })?;
+            } else {
+                let ix = instructions::withdraw_reserve_insurance(
+                    &owner,
+                    &reserve,
+                    &reserve_state,
+                    &destination,
+                    amount,
+                );
+                ctx.send(&[ix], &[])?;
+            }
+        }
+        Command::SocializeLoss {
+            obligation,
+            reserve,
+        } => {
+            let reserve_state = accounts::reserve(&ctx.account_data(&reserve)?)?;
+            let refresh_ix = instructions::refresh_reserve(&reserve, &reserve_state);
+            let ix = instructions::socialize_loss(&owner, &obligation, &reserve, &reserve_state);
+            ctx.send(&[refresh_ix, ix], &[])?;
+        }
+        Command::SetFeeDistribution { market, recipients } => {
+            let shares = recipients
+                .iter()
+                .map(|recipient| parse_fee_share(recipient))
+                .collect::<Result<Vec<_>>>()?;
+            let exists = ctx
+                .rpc
+                .get_account_data(&pda::fee_distribution(&market))
+                .is_ok();
+            let ix = if exists {
+                instructions::update_fee_distribution(&owner, &market, shares)
+            } else {
+                instructions::initialize_fee_distribution(&owner, &market, shares)
+            };
+            ctx.send(&[ix], &[])?;
+        }
+        Command::DistributeFees {
+            reserve,
+            accounts: recipient_accounts,
+        } => {
+            let reserve_state = accounts::reserve(&ctx.account_data(&reserve)?)?;
+            let ix = instructions::distribute_fees(&reserve, &reserve_state, &recipient_accounts);
+            ctx.send(&[ix], &[])?;
+        }
+        Command::DiffReserve { reserve, config } => reserve_diff(&ctx, &reserve, &config, false)?,
+        Command::ApplyReserve { reserve, config } => reserve_diff(&ctx, &reserve, &config, true)?,
+        Command::DiffMarket { market, config } => market_diff(&ctx, &market, &config, false)?,
+        Command::ApplyMarket { market, config } => market_diff(&ctx, &market, &config, true)?,
+    }
+
+    Ok(())
+}
+
+fn reserve_diff(ctx: &Admin, reserve: &Pubkey, config: &Path, apply: bool) -> Result<()> {
+    let reserve_state = accounts::reserve(&ctx.account_data(reserve)?)?;
+    let file: ReserveConfigFile = config::load(config)?;
+    let desired = file.to_reserve_config(Some(&reserve_state.config))?;
+
+    let changes = plan::diff_reserve_config(&reserve_state.config, &desired);
+    if changes.is_empty() {
+        println!("reserve {reserve} is up to date");
+        return Ok(());
+    }
+    for change in &changes {
+        println!("{change}");
+    }
+
+    if apply {
+        let market = reserve_state.lending_market;
+        let market_state = accounts::lending_market(&ctx.account_data(&market)?)?;
+        // One transaction per update so a failing change does not hide the ones before it.
+        let ixs = plan::reserve_update_ixs(
+            &ctx.payer.pubkey(),
+            &market,
+            instructions::market_reserve_registry(&market_state),
+            reserve,
+            &changes,
+        );
+        for (change, ix) in changes.iter().zip(ixs) {
+            if market_state.has_config_timelock()
+                && !change.update.is_timelock_exempt(&reserve_state.config)
+            {
+                ctx.queue(|queued_update| {
+                    instructions::queue_reserve_update(
+                        &ctx.payer.pubkey(),
+                        &market,
+                        reserve,
+                        queued_update,
+                        change.update.clone(),
+                    )
+                })?;
+            } else {
+                ctx.send(&[ix], &[])?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn market_diff(ctx: &Admin, market: &Pubkey, config: &Path, apply: bool) -> Result<()> {
+    let market_state = accounts::lending_market(&ctx.account_data(market)?)?;
+    let file: MarketConfigFile = config::load(config)?;
+
+    let changes = plan::diff_market_config(&market_state, &file);
+    if changes.is_empty() {
+        println!("market {market} is up to date");
+        return Ok(());
+    }
+    for change in &changes {
+        println!("{change}");
+    }
+
+    if apply {
+        let ixs = plan::market_update_ixs(&ctx.payer.pubkey(), market, &changes);
+        for (change, ix) in changes.iter().zip(ixs) {
+            if market_state.has_config_timelock()
+                && !change.update.is_timelock_exempt(&market_state)
+            {
+                ctx.queue(|queued_update| {
+                    instructions::queue_market_update(
+                        &ctx.payer.pubkey(),
+                        market,
+                        queued_update,
+                        change.update,
+                    )
+                })?;
+            } else {
+                ctx.send(&[ix], &[])?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,340 @@
+use std::fmt;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use cluster_lend::{
+    AssetTier, LendingMarket, LendingMarketUpdate, ReserveConfig, ReserveConfigUpdate,
+    ReserveStatus,
+};
+use cluster_lend_client::instructions;
+
+use crate::config::MarketConfigFile;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReserveChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+    pub update: ReserveConfigUpdate,
+}
+
+impl fmt::Display for ReserveChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.from, self.to)
+    }
+}
+
+/// Lists every config field that differs between `current` and `desired`, ordered so that
+/// each intermediate config still passes `validate_reserve_config` when the changes are
+/// applied one by one.
+pub fn diff_reserve_config(current: &ReserveConfig, desired: &ReserveConfig) -> Vec<ReserveChange> {
+    let mut changes = Vec::new();
+
+    macro_rules! field {
+        ($name:literal, $($path:ident).+, $update:expr) => {
+            if current.$($path).+ != desired.$($path).+ {
+                let update = $update;
+                changes.push(ReserveChange {
+                    field: $name,
+                    from: format!("{:?}", current.$($path).+),
+                    to: format!("{:?}", desired.$($path).+),
+                    update: update(desired.$($path).+),
+                });
+            }
+        };
+    }
+
+    let raise_threshold_first =
+        desired.liquidation_threshold_pct >= current.liquidation_threshold_pct;
+    if raise_threshold_first {
+        field!(
+            "liquidation_threshold_pct",
+            liquidation_threshold_pct,
+            ReserveConfigUpdate::LiquidationThresholdPct
+        );
+    }
+    field!(
+        "loan_to_value_pct",
+        loan_to_value_pct,
+        ReserveConfigUpdate::LoanToValuePct
+    );
+    if !raise_threshold_first {
+        field!(
+            "liquidation_threshold_pct",
+            liquidation_threshold_pct,
+            ReserveConfigUpdate::LiquidationThresholdPct
+        );
+    }
+
+    let raise_max_bonus_first =
+        desired.max_liquidation_bonus_bps >= current.max_liquidation_bonus_bps;
+    if raise_max_bonus_first {
+        field!(
+            "max_liquidation_bonus_bps",
+            max_liquidation_bonus_bps,
+            ReserveConfigUpdate::MaxLiquidationBonusBps
+        );
+    }
+    field!(
+        "min_liquidation_bonus_bps",
+        min_liquidation_bonus_bps,
+        ReserveConfigUpdate::MinLiquidationBonusBps
+    );
+    if !raise_max_bonus_first {
+        field!(
+            "max_liquidation_bonus_bps",
+            max_liquidation_bonus_bps,
+            ReserveConfigUpdate::MaxLiquidationBonusBps
+        );
+    }
+
+    field!(
+        "bad_debt_liquidation_bonus_bps",
+        bad_debt_liquidation_bonus_bps,
+        ReserveConfigUpdate::BadDebtLiquidationBonusBps
+    );
+    field!(
+        "protocol_take_rate_pct",
+        protocol_take_rate_pct,
+        ReserveConfigUpdate::ProtocolTakeRatePct
+    );
+    field!(
+        "protocol_liquidation_fee_pct",
+        protocol_liquidation_fee_pct,
+        ReserveConfigUpdate::ProtocolLiquidationFeePct
+    );
+    field!(
+        "insurance_fee_pct",
+        insurance_fee_pct,
+        ReserveConfigUpdate::InsuranceFeePct
+    );
+    field!(
+        "deleveraging_margin_call_period_secs",
+        deleveraging_margin_call_period_secs,
+        ReserveConfigUpdate::DeleveragingMarginCallPeriodSecs
+    );
+    field!(
+        "deleveraging_threshold_slots_per_bps",
+        deleveraging_threshold_slots_per_bps,
+        ReserveConfigUpdate::DeleveragingThresholdSlotsPerBps
+    );
+    field!(
+        "borrow_fee_sf",
+        fees.borrow_fee_sf,
+        ReserveConfigUpdate::BorrowFeeSf
+    );
+    field!(
+        "flash_loan_fee_sf",
+        fees.flash_loan_fee_sf,
+        ReserveConfigUpdate::FlashLoanFeeSf
+    );
+    field!(
+        "borrow_factor_pct",
+        borrow_factor_pct,
+        ReserveConfigUpdate::BorrowFactorPct
+    );
+    field!(
+        "deposit_limit",
+        deposit_limit,
+        ReserveConfigUpdate::DepositLimit
+    );
+    field!(
+        "borrow_limit",
+        borrow_limit,
+        ReserveConfigUpdate::BorrowLimit
+    );
+    field!("asset_tier", asset_tier, |tier: u8| {
+        ReserveConfigUpdate::AssetTier(AssetTier::try_from(tier).unwrap())
+    });
+    field!("name", token_info.name, ReserveConfigUpdate::TokenInfoName);
+    field!(
+        "max_twap_divergence_bps",
+        token_info.max_twap_divergence_bps,
+        ReserveConfigUpdate::TokenInfoTwapDivergenceBps
+    );
+    field!(
+        "max_age_price_seconds",
+        token_info.max_age_price_seconds,
+        ReserveConfigUpdate::TokenInfoPriceMaxAgeSeconds
+    );
+    field!(
+        "max_age_twap_seconds",
+        token_info.max_age_twap_seconds,
+        ReserveConfigUpdate::TokenInfoTwapMaxAgeSeconds
+    );
+    field!(
+        "pyth_price",
+        token_info.pyth_configuration.price,
+        |price: Pubkey| ReserveConfigUpdate::PythPrice(price)
+    );
+    field!(
+        "borrow_rate_curve",
+        borrow_rate_curve,
+        ReserveConfigUpdate::BorrowRateCurve
+    );
+
+    for (field, from, to, update) in [
+        (
+            "deposit_withdrawal_cap",
+            &current.deposit_withdrawal_cap,
+            &desired.deposit_withdrawal_cap,
+            ReserveConfigUpdate::DepositWithdrawalCap {
+                capacity: desired.deposit_withdrawal_cap.config_capacity,
+                interval_length_seconds: desired
+                    .deposit_withdrawal_cap
+                    .config_interval_length_seconds,
+            },
+        ),
+        (
+            "debt_withdrawal_cap",
+            &current.debt_withdrawal_cap,
+            &desired.debt_withdrawal_cap,
+            ReserveConfigUpdate::DebtWithdrawalCap {
+                capacity: desired.debt_withdrawal_cap.config_capacity,
+                interval_length_seconds: desired.debt_withdrawal_cap.config_interval_length_seconds,
+            },
+        ),
+    ] {
+        if (from.config_capacity, from.config_interval_length_seconds)
+            != (to.config_capacity, to.config_interval_length_seconds)
+        {
+            changes.push(ReserveChange {
+                field,
+                from: format!(
+                    "{{ capacity: {}, interval: {}s }}",
+                    from.config_capacity, from.config_interval_length_seconds
+                ),
+                to: format!(
+                    "{{ capacity: {}, interval: {}s }}",
+                    to.config_capacity, to.config_interval_length_seconds
+                ),
+                update,
+            });
+        }
+    }
+
+    // Status goes last so that a reserve is only activated once it is fully configured.
+    field!("status", status, |status: u8| {
+        ReserveConfigUpdate::ReserveStatus(ReserveStatus::try_from(status).unwrap())
+    });
+
+    changes
+}
+
+pub fn reserve_update_ixs(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    reserve_registry: Option<Pubkey>,
+    reserve: &Pubkey,
+    changes: &[ReserveChange],
+) -> Vec<Instruction> {
+    changes
+        .iter()
+        .map(|change| {
+            instructions::update_reserve_config(
+                owner,
+                lending_market,
+                reserve_registry,
+                reserve,
+                change.update.clone(),
+            )
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+    pub update: LendingMarketUpdate,
+}
+
+impl fmt::Display for MarketChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.from, self.to)
+    }
+}
+
+pub fn diff_market_config(
+    current: &LendingMarket,
+    desired: &MarketConfigFile,
+) -> Vec<MarketChange> {
+    let current_file = MarketConfigFile::from_market(current);
+    let mut changes = Vec::new();
+
+    macro_rules! field {
+        ($name:ident, $update:expr) => {
+            if let Some(to) = desired.$name {
+                let from = current_file.$name.unwrap();
+                if from != to {
+                    changes.push(MarketChange {
+                        field: stringify!($name),
+                        from: format!("{:?}", from),
+                        to: format!("{:?}", to),
+                        update: $update(to),
+                    });
+                }
+            }
+        };
+    }
+
+    field!(emergency_mode, LendingMarketUpdate::EmergencyMode);
+    field!(
+        autodeleverage_enabled,
+        LendingMarketUpdate::AutodeleverageEnabled
+    );
+    field!(borrow_disabled, LendingMarketUpdate::BorrowingDisabled);
+    field!(
+        liquidation_max_debt_close_factor_pct,
+        LendingMarketUpdate::LiquidationCloseFactorPct
+    );
+    field!(
+        insolvency_risk_unhealthy_ltv_pct,
+        LendingMarketUpdate::InsolvencyRiskLtvPct
+    );
+    field!(
+        price_refresh_trigger_to_max_age_pct,
+        LendingMarketUpdate::PriceRefreshTriggerToMaxAgePct
+    );
+    field!(
+        max_liquidatable_debt_market_value_at_once,
+        LendingMarketUpdate::LiquidationMaxValue
+    );
+    field!(
+        global_unhealthy_borrow_value,
+        LendingMarketUpdate::GlobalUnhealthyBorrowValue
+    );
+    field!(
+        global_allowed_borrow_value,
+        LendingMarketUpdate::GlobalAllowedBorrowValue
+    );
+    field!(
+        min_full_liquidation_value_threshold,
+        LendingMarketUpdate::MinFullLiquidationThreshold
+    );
+    field!(
+        min_net_value_in_obligation_sf,
+        LendingMarketUpdate::MinNetValueObligationPostActionSf
+    );
+    field!(
+        config_timelock_secs,
+        LendingMarketUpdate::ConfigTimelockSecs
+    );
+    field!(
+        liquidation_bonus_ramp_slots,
+        LendingMarketUpdate::LiquidationBonusRampSlots
+    );
+
+    changes
+}
+
+pub fn market_update_ixs(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    changes: &[MarketChange],
+) -> Vec<Instruction> {
+    changes
+        .iter()
+        .map(|change| instructions::update_market_config(owner, lending_market, change.update))
+        .collect()
+}
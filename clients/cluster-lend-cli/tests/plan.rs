@@ -0,0 +1,184 @@
+use anchor_lang::prelude::Pubkey;
+use cluster_lend::{LendingMarket, LendingMarketUpdate, ReserveConfig, ReserveConfigUpdate};
+use cluster_lend_cli::{
+    config::{MarketConfigFile, ReserveConfigFile},
+    plan::{diff_market_config, diff_reserve_config, market_update_ixs},
+};
+use cluster_lend_client::accounts;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+
+const RESERVE_TOML: &str = r#"
+name = "SOL"
+status = "active"
+asset_tier = "regular"
+loan_to_value_pct = 75
+liquidation_threshold_pct = 85
+min_liquidation_bonus_bps = 200
+max_liquidation_bonus_bps = 500
+bad_debt_liquidation_bonus_bps = 10
+protocol_take_rate_pct = 0
+protocol_liquidation_fee_pct = 0
+deleveraging_margin_call_period_secs = 259200
+deleveraging_threshold_slots_per_bps = 7200
+borrow_fee_sf = 0
+flash_loan_fee_sf = 0
+borrow_factor_pct = 100
+deposit_limit = 10000000000000
+borrow_limit = 10000000000000
+borrow_rate_curve = [[0, 1], [100, 100], [10000, 100000]]
+pyth_price = "PythSo1Price1111111111111111111111111111111"
+max_age_price_seconds = 1000000000
+"#;
+
+fn reserve_config() -> ReserveConfig {
+    toml::from_str::<ReserveConfigFile>(RESERVE_TOML)
+        .unwrap()
+        .to_reserve_config(None)
+        .unwrap()
+}
+
+#[test]
+fn success_no_changes_for_identical_config() {
+    let config = reserve_config();
+    assert!(diff_reserve_config(&config, &config).is_empty());
+}
+
+#[test]
+fn success_raising_ltv_updates_threshold_first() {
+    let current = reserve_config();
+    let mut desired = current;
+    desired.loan_to_value_pct = 88;
+    desired.liquidation_threshold_pct = 90;
+
+    let changes = diff_reserve_config(&current, &desired);
+    let updates = changes.into_iter().map(|c| c.update).collect::<Vec<_>>();
+    assert_eq!(
+        updates,
+        vec![
+            ReserveConfigUpdate::LiquidationThresholdPct(90),
+            ReserveConfigUpdate::LoanToValuePct(88),
+        ]
+    );
+}
+
+#[test]
+fn success_lowering_ltv_updates_ltv_first() {
+    let current = reserve_config();
+    let mut desired = current;
+    desired.loan_to_value_pct = 50;
+    desired.liquidation_threshold_pct = 60;
+
+    let changes = diff_reserve_config(&current, &desired);
+    assert_eq!(changes[0].update, ReserveConfigUpdate::LoanToValuePct(50));
+    assert_eq!(
+        changes[1].update,
+        ReserveConfigUpdate::LiquidationThresholdPct(60)
+    );
+}
+
+#[test]
+fn success_borrow_rate_curve_is_a_single_update() {
+    let current = reserve_config();
+    let mut file: ReserveConfigFile = toml::from_str(RESERVE_TOML).unwrap();
+    file.borrow_rate_curve = vec![[0, 1], [8000, 500], [10000, 5000]];
+    let desired = file.to_reserve_config(Some(&current)).unwrap();
+
+    let changes = diff_reserve_config(&current, &desired);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(
+        changes[0].update,
+        ReserveConfigUpdate::BorrowRateCurve(desired.borrow_rate_curve)
+    );
+}
+
+#[test]
+fn success_market_diff_skips_unset_fields() {
+    let market = LendingMarket::default();
+    let file = MarketConfigFile {
+        borrow_disabled: Some(true),
+        ..MarketConfigFile::default()
+    };
+
+    let changes = diff_market_config(&market, &file);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(
+        changes[0].update,
+        LendingMarketUpdate::BorrowingDisabled(true)
+    );
+}
+
+#[tokio::test]
+async fn success_apply_market_config() {
+    fn fixed_entry(
+        program_id: &Pubkey,
+        accounts: &[anchor_lang::prelude::AccountInfo],
+        data: &[u8],
+    ) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+        let extended_lifetime_accs = unsafe {
+            core::mem::transmute::<_, &[anchor_lang::prelude::AccountInfo<'_>]>(accounts)
+        };
+        cluster_lend::entry(program_id, extended_lifetime_accs, data)
+    }
+
+    let program = ProgramTest::new("cluster_lend", cluster_lend::id(), processor!(fixed_entry));
+    let mut ctx = program.start_with_context().await;
+    let owner = Keypair::new();
+    let market = Keypair::new();
+
+    let mut ixs = vec![solana_sdk::system_instruction::transfer(
+        &ctx.payer.pubkey(),
+        &owner.pubkey(),
+        1_000_000_000,
+    )];
+    ixs.push(cluster_lend_client::instructions::initialize_market(
+        &owner.pubkey(),
+        &market.pubkey(),
+        *b"USD\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+    ));
+    let tx = Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&ctx.payer.pubkey()),
+        &[&ctx.payer, &owner, &market],
+        ctx.last_blockhash,
+    );
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let file = MarketConfigFile {
+        borrow_disabled: Some(true),
+        global_allowed_borrow_value: Some(42),
+        ..MarketConfigFile::default()
+    };
+
+    let load_market = |data: Vec<u8>| accounts::lending_market(&data).unwrap();
+    let before = load_market(
+        ctx.banks_client
+            .get_account(market.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    );
+    let changes = diff_market_config(&before, &file);
+    assert_eq!(changes.len(), 2);
+
+    let tx = Transaction::new_signed_with_payer(
+        &market_update_ixs(&owner.pubkey(), &market.pubkey(), &changes),
+        Some(&ctx.payer.pubkey()),
+        &[&ctx.payer, &owner],
+        ctx.last_blockhash,
+    );
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+
+    let after = load_market(
+        ctx.banks_client
+            .get_account(market.pubkey())
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    );
+    assert_eq!(after.borrow_disabled, 1);
+    assert_eq!(after.global_allowed_borrow_value, 42);
+    assert!(diff_market_config(&after, &file).is_empty());
+}
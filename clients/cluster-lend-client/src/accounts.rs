@@ -0,0 +1,55 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use bytemuck::Pod;
+use cluster_lend::{
+    FeeDistribution, LendingMarket, Obligation, QueuedUpdate, Reserve, ReserveRegistry,
+};
+
+use crate::{ClientError, ClientResult};
+
+const DISCRIMINATOR_LEN: usize = 8;
+
+pub fn deserialize_zero_copy<T: Discriminator + Pod>(data: &[u8]) -> ClientResult<T> {
+    let expected = DISCRIMINATOR_LEN + std::mem::size_of::<T>();
+    if data.len() < expected {
+        return Err(ClientError::AccountDataTooSmall {
+            expected,
+            actual: data.len(),
+        });
+    }
+    if data[..DISCRIMINATOR_LEN] != T::DISCRIMINATOR {
+        return Err(ClientError::InvalidAccountDiscriminator);
+    }
+
+    Ok(bytemuck::pod_read_unaligned(
+        &data[DISCRIMINATOR_LEN..expected],
+    ))
+}
+
+pub fn lending_market(data: &[u8]) -> ClientResult<LendingMarket> {
+    deserialize_zero_copy(data)
+}
+
+pub fn reserve(data: &[u8]) -> ClientResult<Box<Reserve>> {
+    deserialize_zero_copy(data).map(Box::new)
+}
+
+pub fn reserve_registry(data: &[u8]) -> ClientResult<Box<ReserveRegistry>> {
+    deserialize_zero_copy(data).map(Box::new)
+}
+
+pub fn fee_distribution(data: &[u8]) -> ClientResult<FeeDistribution> {
+    deserialize_zero_copy(data)
+}
+
+pub fn queued_update(data: &[u8]) -> ClientResult<QueuedUpdate> {
+    if data.get(..DISCRIMINATOR_LEN) != Some(&QueuedUpdate::DISCRIMINATOR[..]) {
+        return Err(ClientError::InvalidAccountDiscriminator);
+    }
+
+    QueuedUpdate::deserialize(&mut &data[DISCRIMINATOR_LEN..])
+        .map_err(|_| ClientError::InvalidAccountData)
+}
+
+pub fn obligation(data: &[u8]) -> ClientResult<Box<Obligation>> {
+    deserialize_zero_copy(data).map(Box::new)
+}
@@ -0,0 +1,36 @@
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientError {
+    AccountDataTooSmall { expected: usize, actual: usize },
+    InvalidAccountDiscriminator,
+    InvalidAccountData,
+    MissingReserve(Pubkey),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::AccountDataTooSmall { expected, actual } => write!(
+                f,
+                "account data too small: expected at least {expected} bytes, got {actual}"
+            ),
+            ClientError::InvalidAccountDiscriminator => {
+                write!(f, "account discriminator does not match the requested type")
+            }
+            ClientError::InvalidAccountData => {
+                write!(
+                    f,
+                    "account data does not deserialize into the requested type"
+                )
+            }
+            ClientError::MissingReserve(reserve) => {
+                write!(f, "reserve {reserve} was not provided")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
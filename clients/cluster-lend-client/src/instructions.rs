@@ -0,0 +1,1175 @@
+use std::collections::HashMap;
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{instruction::Instruction, system_program, sysvar},
+    AnchorSerialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::token;
+use cluster_lend::{
+    constants::{VALUE_BYTE_ARRAY_LEN_RESERVE, VALUE_BYTE_MAX_ARRAY_LEN_MARKET_UPDATE},
+    FeeShare, InitObligationArgs, LendingMarket, LendingMarketUpdate, MarketRole, Obligation,
+    QueuedUpdate, QueuedUpdateKind, Reserve, ReserveConfig, ReserveConfigUpdate,
+};
+
+use crate::{
+    pda::{
+        fee_distribution, init_reserve_pdas, lending_market_auth, reserve_insurance_vault,
+        reserve_registry, user_wsol,
+    },
+    refresh::with_refresh_ixs,
+    ClientResult,
+};
+
+fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: cluster_lend::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// Market instructions
+
+pub fn initialize_market(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    quote_currency: [u8; 32],
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::InitializeMarketCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            lending_market_authority: lending_market_auth(lending_market),
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::InitializeMarket { quote_currency },
+    )
+}
+
+pub fn update_market(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    mode: u64,
+    value: [u8; VALUE_BYTE_MAX_ARRAY_LEN_MARKET_UPDATE],
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::UpdateMarketCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::UpdateMarket { mode, value },
+    )
+}
+
+pub fn update_market_config(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    update: LendingMarketUpdate,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::UpdateMarketCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::UpdateMarketConfig { update },
+    )
+}
+
+/// Queues `update` in the new `queued_update` account, which must sign. Needed for updates
+/// that are not tightening once the market has a config timelock.
+pub fn queue_market_update(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    queued_update: &Pubkey,
+    update: LendingMarketUpdate,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::QueueMarketUpdateCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            queued_update: *queued_update,
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::QueueMarketUpdate { update },
+    )
+}
+
+/// Reserve counterpart of [`queue_market_update`].
+pub fn queue_reserve_update(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    reserve: &Pubkey,
+    queued_update: &Pubkey,
+    update: ReserveConfigUpdate,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::QueueReserveUpdateCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            reserve: *reserve,
+            queued_update: *queued_update,
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::QueueReserveUpdate { update },
+    )
+}
+
+/// Executes a queued market or reserve update. Anyone can send it once the timelock expired.
+/// `reserve_registry` comes from [`market_reserve_registry`].
+///
+/// # Panics
+///
+/// On a queued insurance withdrawal, which [`execute_insurance_withdrawal`] executes.
+pub fn execute_queued_update(
+    queued_update: &Pubkey,
+    queued_state: &QueuedUpdate,
+    reserve_registry: Option<Pubkey>,
+) -> Instruction {
+    let lending_market = queued_state.lending_market;
+    match &queued_state.update {
+        QueuedUpdateKind::Market(_) => ix(
+            cluster_lend::accounts::ExecuteMarketUpdateCtx {
+                payer: queued_state.payer,
+                lending_market,
+                queued_update: *queued_update,
+            },
+            cluster_lend::instruction::ExecuteMarketUpdate {},
+        ),
+        QueuedUpdateKind::Reserve { reserve, .. } => ix(
+            cluster_lend::accounts::ExecuteReserveUpdateCtx {
+                payer: queued_state.payer,
+                lending_market,
+                reserve: *reserve,
+                reserve_registry,
+                queued_update: *queued_update,
+            },
+            cluster_lend::instruction::ExecuteReserveUpdate {},
+        ),
+        QueuedUpdateKind::InsuranceWithdrawal { .. } => {
+            panic!("insurance withdrawals are executed by execute_insurance_withdrawal")
+        }
+    }
+}
+
+pub fn cancel_queued_update(
+    owner: &Pubkey,
+    queued_update: &Pubkey,
+    queued_state: &QueuedUpdate,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::CancelQueuedUpdateCtx {
+            owner: *owner,
+            lending_market: queued_state.lending_market,
+            payer: queued_state.payer,
+            queued_update: *queued_update,
+        },
+        cluster_lend::instruction::CancelQueuedUpdate {},
+    )
+}
+
+pub fn propose_market_role(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    role: MarketRole,
+    new_holder: &Pubkey,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::UpdateMarketCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::ProposeMarketRole {
+            role,
+            new_holder: *new_holder,
+        },
+    )
+}
+
+/// Must be signed by the holder proposed with [`propose_market_role`].
+pub fn accept_market_role(
+    pending_holder: &Pubkey,
+    lending_market: &Pubkey,
+    role: MarketRole,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::AcceptMarketRoleCtx {
+            pending_holder: *pending_holder,
+            lending_market: *lending_market,
+        },
+        cluster_lend::instruction::AcceptMarketRole { role },
+    )
+}
+
+pub fn cancel_market_role(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    role: MarketRole,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::UpdateMarketCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::CancelMarketRole { role },
+    )
+}
+
+/// Guardian counterpart of [`update_market_config`], limited to tightening updates.
+pub fn guardian_update_market(
+    guardian: &Pubkey,
+    lending_market: &Pubkey,
+    update: LendingMarketUpdate,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::GuardianUpdateMarketCtx {
+            guardian: *guardian,
+            lending_market: *lending_market,
+        },
+        cluster_lend::instruction::GuardianUpdateMarket { update },
+    )
+}
+
+pub fn redeem_fees(reserve: &Pubkey, reserve_state: &Reserve) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    ix(
+        cluster_lend::accounts::RedeemFeesCtx {
+            reserve: *reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_liquidity_fee_receiver: reserve_state.liquidity.fee_vault,
+            reserve_supply_liquidity: reserve_state.liquidity.supply_vault,
+            reserve_insurance_vault: insurance_vault(reserve_state),
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            token_program: reserve_state.liquidity.token_program(),
+        },
+        cluster_lend::instruction::RedeemFees {},
+    )
+}
+
+pub fn withdraw_protocol_fees(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    lending_market_owner_ata: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    ix(
+        cluster_lend::accounts::WithdrawProtocolFeesCtx {
+            owner: *owner,
+            lending_market,
+            reserve: *reserve,
+            lending_market_authority: lending_market_auth(&lending_market),
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            fee_vault: reserve_state.liquidity.fee_vault,
+            lending_market_owner_ata: *lending_market_owner_ata,
+            token_program: reserve_state.liquidity.token_program(),
+        },
+        cluster_lend::instruction::WithdrawProtocolFees { amount },
+    )
+}
+
+pub fn initialize_fee_distribution(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    shares: Vec<FeeShare>,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::InitializeFeeDistributionCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            fee_distribution: fee_distribution(lending_market),
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::InitializeFeeDistribution { shares },
+    )
+}
+
+pub fn update_fee_distribution(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    shares: Vec<FeeShare>,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::UpdateFeeDistributionCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            fee_distribution: fee_distribution(lending_market),
+        },
+        cluster_lend::instruction::UpdateFeeDistribution { shares },
+    )
+}
+
+/// Permissionless. `recipient_token_accounts` are liquidity mint accounts owned by each fee
+/// recipient, in the order of the market's `FeeDistribution`.
+pub fn distribute_fees(
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    recipient_token_accounts: &[Pubkey],
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    let mut instruction = ix(
+        cluster_lend::accounts::DistributeFeesCtx {
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            reserve: *reserve,
+            fee_distribution: fee_distribution(&lending_market),
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            fee_vault: reserve_state.liquidity.fee_vault,
+            token_program: reserve_state.liquidity.token_program(),
+        },
+        cluster_lend::instruction::DistributeFees {},
+    );
+    instruction.accounts.extend(
+        recipient_token_accounts
+            .iter()
+            .map(|account| AccountMeta::new(*account, false)),
+    );
+    instruction
+}
+
+/// Read-only, returns the reserve's `ReserveFeeLedger` as return data. The same counters are
+/// in `reserve_state.liquidity.fee_ledger` when the account is fetched directly.
+pub fn get_reserve_fee_ledger(reserve: &Pubkey) -> Instruction {
+    ix(
+        cluster_lend::accounts::GetReserveFeeLedgerCtx { reserve: *reserve },
+        cluster_lend::instruction::GetReserveFeeLedger {},
+    )
+}
+
+/// Creates the market's reserve registry, registering `existing_reserves` in order. The
+/// registry only stays complete if every reserve of the market is listed.
+pub fn initialize_reserve_registry(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    existing_reserves: &[Pubkey],
+) -> Instruction {
+    let mut instruction = ix(
+        cluster_lend::accounts::InitializeReserveRegistryCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            reserve_registry: reserve_registry(lending_market),
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::InitializeReserveRegistry {},
+    );
+    instruction.accounts.extend(
+        existing_reserves
+            .iter()
+            .map(|reserve| AccountMeta::new_readonly(*reserve, false)),
+    );
+    instruction
+}
+
+// Reserve instructions
+
+/// `initial_liquidity_source` is an owner token account of `liquidity_mint` funding the
+/// `initial_deposit_amount` seed deposit, whose cTokens stay locked in the reserve.
+pub fn initialize_reserve(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    reserve_registry: Option<Pubkey>,
+    reserve: &Pubkey,
+    liquidity_mint: &Pubkey,
+    liquidity_token_program: &Pubkey,
+    initial_liquidity_source: &Pubkey,
+) -> Instruction {
+    let pdas = init_reserve_pdas(lending_market, liquidity_mint);
+    ix(
+        cluster_lend::accounts::InitializeReserveCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            lending_market_authority: lending_market_auth(lending_market),
+            reserve: *reserve,
+            reserve_registry,
+            reserve_liquidity_mint: *liquidity_mint,
+            reserve_liquidity_supply: pdas.liquidity_supply_vault,
+            fee_receiver: pdas.fee_vault,
+            reserve_collateral_mint: pdas.collateral_ctoken_mint,
+            reserve_collateral_supply: pdas.collateral_supply_vault,
+            reserve_locked_collateral: pdas.locked_collateral_vault,
+            initial_liquidity_source: *initial_liquidity_source,
+            rent: sysvar::rent::ID,
+            collateral_token_program: token::ID,
+            liquidity_token_program: *liquidity_token_program,
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::InitializeReserve {},
+    )
+}
+
+pub fn update_reserve(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    reserve_registry: Option<Pubkey>,
+    reserve: &Pubkey,
+    config: &ReserveConfig,
+) -> Instruction {
+    let mut value = [0; VALUE_BYTE_ARRAY_LEN_RESERVE];
+    let data = config.try_to_vec().expect("ReserveConfig serialization");
+    value.copy_from_slice(&data);
+
+    ix(
+        cluster_lend::accounts::UpdateReserveCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            reserve: *reserve,
+            reserve_registry,
+        },
+        cluster_lend::instruction::UpdateReserve { value },
+    )
+}
+
+pub fn update_reserve_mode(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    reserve_registry: Option<Pubkey>,
+    reserve: &Pubkey,
+    mode: u64,
+    value: [u8; 32],
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::UpdateReserveCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            reserve: *reserve,
+            reserve_registry,
+        },
+        cluster_lend::instruction::UpdateReserveMode { mode, value },
+    )
+}
+
+pub fn update_reserve_config(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    reserve_registry: Option<Pubkey>,
+    reserve: &Pubkey,
+    update: ReserveConfigUpdate,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::UpdateReserveCtx {
+            owner: *owner,
+            lending_market: *lending_market,
+            reserve: *reserve,
+            reserve_registry,
+        },
+        cluster_lend::instruction::UpdateReserveConfig { update },
+    )
+}
+
+/// Guardian counterpart of [`update_reserve_config`], limited to tightening updates.
+pub fn guardian_update_reserve(
+    guardian: &Pubkey,
+    lending_market: &Pubkey,
+    reserve_registry: Option<Pubkey>,
+    reserve: &Pubkey,
+    update: ReserveConfigUpdate,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::GuardianUpdateReserveCtx {
+            guardian: *guardian,
+            lending_market: *lending_market,
+            reserve: *reserve,
+            reserve_registry,
+        },
+        cluster_lend::instruction::GuardianUpdateReserve { update },
+    )
+}
+
+/// Closes an emptied reserve, sweeping leftover liquidity to `lending_market_owner_ata`.
+pub fn close_reserve(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    reserve_registry: Option<Pubkey>,
+    lending_market_owner_ata: &Pubkey,
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    let pdas = init_reserve_pdas(&lending_market, &reserve_state.liquidity.mint_pubkey);
+    ix(
+        cluster_lend::accounts::CloseReserveCtx {
+            owner: *owner,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            reserve: *reserve,
+            reserve_registry,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_liquidity_supply: reserve_state.liquidity.supply_vault,
+            fee_receiver: reserve_state.liquidity.fee_vault,
+            reserve_collateral_mint: reserve_state.collateral.mint_pubkey,
+            reserve_collateral_supply: reserve_state.collateral.supply_vault,
+            reserve_locked_collateral: Some(pdas.locked_collateral_vault),
+            reserve_insurance_vault: insurance_vault(reserve_state),
+            lending_market_owner_ata: *lending_market_owner_ata,
+            collateral_token_program: token::ID,
+            liquidity_token_program: reserve_state.liquidity.token_program(),
+        },
+        cluster_lend::instruction::CloseReserve {},
+    )
+}
+
+/// Sends tokens sent straight to the reserve vaults to `liquidity_destination` (e.g. the fee
+/// vault) and `collateral_destination`.
+pub fn skim_reserve_excess(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    liquidity_destination: &Pubkey,
+    collateral_destination: &Pubkey,
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    ix(
+        cluster_lend::accounts::SkimReserveExcessCtx {
+            owner: *owner,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            reserve: *reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_liquidity_supply: reserve_state.liquidity.supply_vault,
+            reserve_collateral_supply: reserve_state.collateral.supply_vault,
+            liquidity_destination: *liquidity_destination,
+            collateral_destination: *collateral_destination,
+            collateral_token_program: token::ID,
+            liquidity_token_program: reserve_state.liquidity.token_program(),
+        },
+        cluster_lend::instruction::SkimReserveExcess {},
+    )
+}
+
+/// Permissionless and read-only. Returns an `AuditReserveReport` as return data, simulate it to
+/// read the report without sending.
+pub fn audit_reserve(reserve: &Pubkey, reserve_state: &Reserve) -> Instruction {
+    ix(
+        cluster_lend::accounts::AuditReserveCtx {
+            lending_market: reserve_state.lending_market,
+            reserve: *reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_liquidity_supply: reserve_state.liquidity.supply_vault,
+            fee_receiver: reserve_state.liquidity.fee_vault,
+            reserve_collateral_mint: reserve_state.collateral.mint_pubkey,
+            reserve_collateral_supply: reserve_state.collateral.supply_vault,
+        },
+        cluster_lend::instruction::AuditReserve {},
+    )
+}
+
+/// Permissionless. Like [`audit_reserve`], but a failed invariant pauses deposits, borrows and
+/// flash loans on the reserve.
+pub fn audit_reserve_and_pause(reserve: &Pubkey, reserve_state: &Reserve) -> Instruction {
+    ix(
+        cluster_lend::accounts::AuditReserveAndPauseCtx {
+            lending_market: reserve_state.lending_market,
+            reserve: *reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_liquidity_supply: reserve_state.liquidity.supply_vault,
+            fee_receiver: reserve_state.liquidity.fee_vault,
+            reserve_collateral_mint: reserve_state.collateral.mint_pubkey,
+            reserve_collateral_supply: reserve_state.collateral.supply_vault,
+        },
+        cluster_lend::instruction::AuditReserveAndPause {},
+    )
+}
+
+/// Creates the reserve insurance vault, which then receives `insurance_fee_pct` of the fees.
+pub fn initialize_reserve_insurance(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    ix(
+        cluster_lend::accounts::InitializeReserveInsuranceCtx {
+            owner: *owner,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            reserve: *reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            insurance_vault: reserve_insurance_vault(
+                &lending_market,
+                &reserve_state.liquidity.mint_pubkey,
+            ),
+            token_program: reserve_state.liquidity.token_program(),
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::InitializeReserveInsurance {},
+    )
+}
+
+fn reserve_insurance_accounts(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    owner_token_account: &Pubkey,
+) -> cluster_lend::accounts::ReserveInsuranceCtx {
+    let lending_market = reserve_state.lending_market;
+    cluster_lend::accounts::ReserveInsuranceCtx {
+        owner: *owner,
+        lending_market,
+        lending_market_authority: lending_market_auth(&lending_market),
+        reserve: *reserve,
+        reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+        insurance_vault: reserve_state.liquidity.insurance_vault,
+        owner_token_account: *owner_token_account,
+        token_program: reserve_state.liquidity.token_program(),
+    }
+}
+
+pub fn deposit_reserve_insurance(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    owner_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    ix(
+        reserve_insurance_accounts(owner, reserve, reserve_state, owner_token_account),
+        cluster_lend::instruction::DepositReserveInsurance { amount },
+    )
+}
+
+pub fn withdraw_reserve_insurance(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    owner_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    ix(
+        reserve_insurance_accounts(owner, reserve, reserve_state, owner_token_account),
+        cluster_lend::instruction::WithdrawReserveInsurance { amount },
+    )
+}
+
+/// Queues a withdrawal out of the insurance vault into `destination`, needed once the market
+/// has a config timelock. `queued_update` is a new account and must sign.
+pub fn queue_insurance_withdrawal(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    destination: &Pubkey,
+    queued_update: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    ix(
+        cluster_lend::accounts::QueueInsuranceWithdrawalCtx {
+            owner: *owner,
+            lending_market: reserve_state.lending_market,
+            reserve: *reserve,
+            destination_token_account: *destination,
+            queued_update: *queued_update,
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::QueueInsuranceWithdrawal { amount },
+    )
+}
+
+/// Executes a queued insurance withdrawal. Anyone can send it once the timelock expired.
+///
+/// # Panics
+///
+/// On a queued config update, which [`execute_queued_update`] executes.
+pub fn execute_insurance_withdrawal(
+    queued_update: &Pubkey,
+    queued_state: &QueuedUpdate,
+    reserve_state: &Reserve,
+) -> Instruction {
+    let QueuedUpdateKind::InsuranceWithdrawal {
+        reserve,
+        destination,
+        ..
+    } = queued_state.update
+    else {
+        panic!("queued update is not an insurance withdrawal");
+    };
+    let lending_market = queued_state.lending_market;
+    ix(
+        cluster_lend::accounts::ExecuteInsuranceWithdrawalCtx {
+            payer: queued_state.payer,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            insurance_vault: reserve_state.liquidity.insurance_vault,
+            destination_token_account: destination,
+            queued_update: *queued_update,
+            token_program: reserve_state.liquidity.token_program(),
+        },
+        cluster_lend::instruction::ExecuteInsuranceWithdrawal {},
+    )
+}
+
+/// Writes off the `reserve` borrow of an obligation without collateral, out of the insurance
+/// vault first. Like `redeem_fees`, it needs `refresh_reserve` earlier in the same slot.
+pub fn socialize_loss(
+    owner: &Pubkey,
+    obligation: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    ix(
+        cluster_lend::accounts::SocializeLossCtx {
+            owner: *owner,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            obligation: *obligation,
+            reserve: *reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_liquidity_supply: reserve_state.liquidity.supply_vault,
+            reserve_insurance_vault: insurance_vault(reserve_state),
+            token_program: reserve_state.liquidity.token_program(),
+        },
+        cluster_lend::instruction::SocializeLoss {},
+    )
+}
+
+pub use crate::refresh::refresh_reserve;
+
+pub fn deposit_reserve_liquidity(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    user_source_liquidity: Option<&Pubkey>,
+    user_destination_collateral: &Pubkey,
+    liquidity_amount: u64,
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    ix(
+        cluster_lend::accounts::DepositReserveLiquidityCtx {
+            owner: *owner,
+            reserve: *reserve,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_liquidity_supply: reserve_state.liquidity.supply_vault,
+            reserve_collateral_mint: reserve_state.collateral.mint_pubkey,
+            user_source_liquidity: user_source_liquidity.copied(),
+            user_wsol: user_source_liquidity.is_none().then(|| user_wsol(owner)),
+            user_destination_collateral: *user_destination_collateral,
+            collateral_token_program: token::ID,
+            liquidity_token_program: reserve_state.liquidity.token_program(),
+            system_program: user_source_liquidity
+                .is_none()
+                .then_some(system_program::ID),
+            instruction_sysvar_account: sysvar::instructions::ID,
+        },
+        cluster_lend::instruction::DepositReserveLiquidity { liquidity_amount },
+    )
+}
+
+pub fn redeem_reserve_collateral(
+    owner: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    user_source_collateral: &Pubkey,
+    user_destination_liquidity: Option<&Pubkey>,
+    collateral_amount: u64,
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    ix(
+        cluster_lend::accounts::RedeemReserveCollateralCtx {
+            owner: *owner,
+            lending_market,
+            reserve: *reserve,
+            lending_market_authority: lending_market_auth(&lending_market),
+            reserve_collateral_mint: reserve_state.collateral.mint_pubkey,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_liquidity_supply: reserve_state.liquidity.supply_vault,
+            user_source_collateral: *user_source_collateral,
+            user_destination_liquidity: user_destination_liquidity.copied(),
+            user_wsol: user_destination_liquidity
+                .is_none()
+                .then(|| user_wsol(owner)),
+            collateral_token_program: token::ID,
+            liquidity_token_program: reserve_state.liquidity.token_program(),
+            system_program: user_destination_liquidity
+                .is_none()
+                .then_some(system_program::ID),
+            instruction_sysvar_account: sysvar::instructions::ID,
+        },
+        cluster_lend::instruction::RedeemReserveCollateral { collateral_amount },
+    )
+}
+
+// Obligation instructions
+
+pub fn initialize_obligation(
+    owner: &Pubkey,
+    fee_payer: &Pubkey,
+    lending_market: &Pubkey,
+    args: InitObligationArgs,
+    seed1_account: &Pubkey,
+    seed2_account: &Pubkey,
+) -> Instruction {
+    let obligation =
+        crate::pda::obligation(&args, owner, lending_market, seed1_account, seed2_account);
+    ix(
+        cluster_lend::accounts::InitializeObligationCtx {
+            owner: *owner,
+            fee_payer: *fee_payer,
+            obligation,
+            lending_market: *lending_market,
+            seed1_account: *seed1_account,
+            seed2_account: *seed2_account,
+            rent: sysvar::rent::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        cluster_lend::instruction::InitializeObligation { args },
+    )
+}
+
+pub use crate::refresh::refresh_obligation;
+
+pub fn deposit_obligation_collateral(
+    owner: &Pubkey,
+    obligation: &Pubkey,
+    obligation_state: &Obligation,
+    deposit_reserve: &Pubkey,
+    reserves: &HashMap<Pubkey, Reserve>,
+    user_source_collateral: &Pubkey,
+    collateral_amount: u64,
+) -> ClientResult<Vec<Instruction>> {
+    let reserve_state = reserve_state(reserves, deposit_reserve)?;
+    let deposit_ix = ix(
+        cluster_lend::accounts::DepositObligationCollateralCtx {
+            owner: *owner,
+            obligation: *obligation,
+            lending_market: obligation_state.lending_market,
+            deposit_reserve: *deposit_reserve,
+            reserve_destination_collateral: reserve_state.collateral.supply_vault,
+            user_source_collateral: *user_source_collateral,
+            token_program: token::ID,
+            instruction_sysvar_account: sysvar::instructions::ID,
+        },
+        cluster_lend::instruction::DepositObligationCollateral { collateral_amount },
+    );
+
+    with_refresh_ixs(
+        obligation,
+        obligation_state,
+        &[*deposit_reserve],
+        reserves,
+        deposit_ix,
+    )
+}
+
+pub fn withdraw_obligation_collateral(
+    owner: &Pubkey,
+    obligation: &Pubkey,
+    obligation_state: &Obligation,
+    withdraw_reserve: &Pubkey,
+    reserves: &HashMap<Pubkey, Reserve>,
+    user_destination_collateral: &Pubkey,
+    collateral_amount: u64,
+) -> ClientResult<Vec<Instruction>> {
+    let reserve_state = reserve_state(reserves, withdraw_reserve)?;
+    let lending_market = obligation_state.lending_market;
+    let withdraw_ix = ix(
+        cluster_lend::accounts::WithdrawObligationCollateralCtx {
+            owner: *owner,
+            obligation: *obligation,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            withdraw_reserve: *withdraw_reserve,
+            reserve_source_collateral: reserve_state.collateral.supply_vault,
+            user_destination_collateral: *user_destination_collateral,
+            token_program: token::ID,
+            instruction_sysvar_account: sysvar::instructions::ID,
+        },
+        cluster_lend::instruction::WithdrawObligationCollateral { collateral_amount },
+    );
+
+    with_refresh_ixs(
+        obligation,
+        obligation_state,
+        &[*withdraw_reserve],
+        reserves,
+        withdraw_ix,
+    )
+}
+
+pub fn borrow_obligation_liquidity(
+    owner: &Pubkey,
+    obligation: &Pubkey,
+    obligation_state: &Obligation,
+    borrow_reserve: &Pubkey,
+    reserves: &HashMap<Pubkey, Reserve>,
+    user_destination_liquidity: Option<&Pubkey>,
+    liquidity_amount: u64,
+) -> ClientResult<Vec<Instruction>> {
+    let reserve_state = reserve_state(reserves, borrow_reserve)?;
+    let lending_market = obligation_state.lending_market;
+    let borrow_ix = ix(
+        cluster_lend::accounts::BorrowObligationLiquidityCtx {
+            owner: *owner,
+            obligation: *obligation,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            borrow_reserve: *borrow_reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_source_liquidity: reserve_state.liquidity.supply_vault,
+            borrow_reserve_liquidity_fee_receiver: reserve_state.liquidity.fee_vault,
+            user_destination_liquidity: user_destination_liquidity.copied(),
+            user_wsol: user_destination_liquidity
+                .is_none()
+                .then(|| user_wsol(owner)),
+            token_program: reserve_state.liquidity.token_program(),
+            system_program: user_destination_liquidity
+                .is_none()
+                .then_some(system_program::ID),
+            instruction_sysvar_account: sysvar::instructions::ID,
+        },
+        cluster_lend::instruction::BorrowObligationLiquidity { liquidity_amount },
+    );
+
+    with_refresh_ixs(
+        obligation,
+        obligation_state,
+        &[*borrow_reserve],
+        reserves,
+        borrow_ix,
+    )
+}
+
+pub fn repay_obligation_liquidity(
+    owner: &Pubkey,
+    obligation: &Pubkey,
+    obligation_state: &Obligation,
+    repay_reserve: &Pubkey,
+    reserves: &HashMap<Pubkey, Reserve>,
+    user_source_liquidity: Option<&Pubkey>,
+    liquidity_amount: u64,
+) -> ClientResult<Vec<Instruction>> {
+    let reserve_state = reserve_state(reserves, repay_reserve)?;
+    let repay_ix = ix(
+        cluster_lend::accounts::RepayObligationLiquidityCtx {
+            owner: *owner,
+            obligation: *obligation,
+            lending_market: obligation_state.lending_market,
+            repay_reserve: *repay_reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_destination_liquidity: reserve_state.liquidity.supply_vault,
+            user_source_liquidity: user_source_liquidity.copied(),
+            user_wsol: user_source_liquidity.is_none().then(|| user_wsol(owner)),
+            token_program: reserve_state.liquidity.token_program(),
+            system_program: user_source_liquidity
+                .is_none()
+                .then_some(system_program::ID),
+            instruction_sysvar_account: sysvar::instructions::ID,
+        },
+        cluster_lend::instruction::RepayObligationLiquidity { liquidity_amount },
+    );
+
+    with_refresh_ixs(
+        obligation,
+        obligation_state,
+        &[*repay_reserve],
+        reserves,
+        repay_ix,
+    )
+}
+
+pub struct LiquidateObligationArgs {
+    pub liquidity_amount: u64,
+    pub min_acceptable_received_collateral_amount: u64,
+    pub max_allowed_ltv_override_percent: u64,
+}
+
+pub struct LiquidatorAccounts {
+    pub liquidator: Pubkey,
+    pub user_source_liquidity: Pubkey,
+    pub user_destination_collateral: Pubkey,
+    pub user_destination_liquidity: Pubkey,
+}
+
+pub fn liquidate_obligation(
+    liquidator: &LiquidatorAccounts,
+    obligation: &Pubkey,
+    obligation_state: &Obligation,
+    repay_reserve: &Pubkey,
+    withdraw_reserve: &Pubkey,
+    reserves: &HashMap<Pubkey, Reserve>,
+    args: LiquidateObligationArgs,
+) -> ClientResult<Vec<Instruction>> {
+    let repay_reserve_state = reserve_state(reserves, repay_reserve)?;
+    let withdraw_reserve_state = reserve_state(reserves, withdraw_reserve)?;
+    let lending_market = obligation_state.lending_market;
+    let liquidate_ix = ix(
+        cluster_lend::accounts::LiquidateObligationCtx {
+            liquidator: liquidator.liquidator,
+            obligation: *obligation,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            repay_reserve: *repay_reserve,
+            repay_reserve_liquidity_mint: repay_reserve_state.liquidity.mint_pubkey,
+            repay_reserve_liquidity_supply: repay_reserve_state.liquidity.supply_vault,
+            withdraw_reserve: *withdraw_reserve,
+            withdraw_reserve_collateral_mint: withdraw_reserve_state.collateral.mint_pubkey,
+            withdraw_reserve_collateral_supply: withdraw_reserve_state.collateral.supply_vault,
+            withdraw_reserve_liquidity_mint: withdraw_reserve_state.liquidity.mint_pubkey,
+            withdraw_reserve_liquidity_supply: withdraw_reserve_state.liquidity.supply_vault,
+            withdraw_reserve_liquidity_fee_receiver: withdraw_reserve_state.liquidity.fee_vault,
+            withdraw_reserve_insurance_vault: insurance_vault(withdraw_reserve_state),
+            user_source_liquidity: liquidator.user_source_liquidity,
+            user_destination_collateral: liquidator.user_destination_collateral,
+            user_destination_liquidity: liquidator.user_destination_liquidity,
+            collateral_token_program: token::ID,
+            repay_liquidity_token_program: repay_reserve_state.liquidity.token_program(),
+            withdraw_liquidity_token_program: withdraw_reserve_state.liquidity.token_program(),
+            instruction_sysvar_account: sysvar::instructions::ID,
+        },
+        cluster_lend::instruction::LiquidateObligation {
+            liquidity_amount: args.liquidity_amount,
+            min_acceptable_received_collateral_amount: args
+                .min_acceptable_received_collateral_amount,
+            max_allowed_ltv_override_percent: args.max_allowed_ltv_override_percent,
+        },
+    );
+
+    with_refresh_ixs(
+        obligation,
+        obligation_state,
+        &[*withdraw_reserve, *repay_reserve],
+        reserves,
+        liquidate_ix,
+    )
+}
+
+/// The liquidator receives the withdraw reserve cTokens in `user_destination_collateral`
+/// instead of redeeming them; `min_acceptable_received_collateral_amount` is in cTokens.
+/// `user_destination_liquidity` is not used.
+pub fn liquidate_obligation_no_redeem(
+    liquidator: &LiquidatorAccounts,
+    obligation: &Pubkey,
+    obligation_state: &Obligation,
+    repay_reserve: &Pubkey,
+    withdraw_reserve: &Pubkey,
+    reserves: &HashMap<Pubkey, Reserve>,
+    args: LiquidateObligationArgs,
+) -> ClientResult<Vec<Instruction>> {
+    let repay_reserve_state = reserve_state(reserves, repay_reserve)?;
+    let withdraw_reserve_state = reserve_state(reserves, withdraw_reserve)?;
+    let lending_market = obligation_state.lending_market;
+    let liquidate_ix = ix(
+        cluster_lend::accounts::LiquidateObligationNoRedeemCtx {
+            liquidator: liquidator.liquidator,
+            obligation: *obligation,
+            lending_market,
+            lending_market_authority: lending_market_auth(&lending_market),
+            repay_reserve: *repay_reserve,
+            repay_reserve_liquidity_mint: repay_reserve_state.liquidity.mint_pubkey,
+            repay_reserve_liquidity_supply: repay_reserve_state.liquidity.supply_vault,
+            withdraw_reserve: *withdraw_reserve,
+            withdraw_reserve_collateral_mint: withdraw_reserve_state.collateral.mint_pubkey,
+            withdraw_reserve_collateral_supply: withdraw_reserve_state.collateral.supply_vault,
+            withdraw_reserve_liquidity_mint: withdraw_reserve_state.liquidity.mint_pubkey,
+            withdraw_reserve_liquidity_supply: withdraw_reserve_state.liquidity.supply_vault,
+            withdraw_reserve_insurance_vault: insurance_vault(withdraw_reserve_state),
+            user_source_liquidity: liquidator.user_source_liquidity,
+            user_destination_collateral: liquidator.user_destination_collateral,
+            collateral_token_program: token::ID,
+            repay_liquidity_token_program: repay_reserve_state.liquidity.token_program(),
+            withdraw_liquidity_token_program: withdraw_reserve_state.liquidity.token_program(),
+            instruction_sysvar_account: sysvar::instructions::ID,
+        },
+        cluster_lend::instruction::LiquidateObligationNoRedeem {
+            liquidity_amount: args.liquidity_amount,
+            min_acceptable_received_collateral_amount: args
+                .min_acceptable_received_collateral_amount,
+            max_allowed_ltv_override_percent: args.max_allowed_ltv_override_percent,
+        },
+    );
+
+    with_refresh_ixs(
+        obligation,
+        obligation_state,
+        &[*withdraw_reserve, *repay_reserve],
+        reserves,
+        liquidate_ix,
+    )
+}
+
+// Flash loan instructions
+
+pub fn flash_borrow_reserve_liquidity(
+    user_transfer_authority: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    user_destination_liquidity: &Pubkey,
+    liquidity_amount: u64,
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    ix(
+        cluster_lend::accounts::FlashBorrowReserveCtx {
+            user_transfer_authority: *user_transfer_authority,
+            lending_market_authority: lending_market_auth(&lending_market),
+            lending_market,
+            reserve: *reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_source_liquidity: reserve_state.liquidity.supply_vault,
+            user_destination_liquidity: *user_destination_liquidity,
+            reserve_liquidity_fee_receiver: reserve_state.liquidity.fee_vault,
+            sysvar_info: sysvar::instructions::ID,
+            token_program: reserve_state.liquidity.token_program(),
+        },
+        cluster_lend::instruction::FlashBorrowReserveLiquidity { liquidity_amount },
+    )
+}
+
+pub fn flash_repay_reserve_liquidity(
+    user_transfer_authority: &Pubkey,
+    reserve: &Pubkey,
+    reserve_state: &Reserve,
+    user_source_liquidity: &Pubkey,
+    liquidity_amount: u64,
+    borrow_instruction_index: u8,
+) -> Instruction {
+    let lending_market = reserve_state.lending_market;
+    ix(
+        cluster_lend::accounts::FlashRepayReserveCtx {
+            user_transfer_authority: *user_transfer_authority,
+            lending_market_authority: lending_market_auth(&lending_market),
+            lending_market,
+            reserve: *reserve,
+            reserve_liquidity_mint: reserve_state.liquidity.mint_pubkey,
+            reserve_destination_liquidity: reserve_state.liquidity.supply_vault,
+            user_source_liquidity: *user_source_liquidity,
+            reserve_liquidity_fee_receiver: reserve_state.liquidity.fee_vault,
+            sysvar_info: sysvar::instructions::ID,
+            token_program: reserve_state.liquidity.token_program(),
+        },
+        cluster_lend::instruction::FlashRepayReserveLiquidity {
+            liquidity_amount,
+            borrow_instruction_index,
+        },
+    )
+}
+
+/// The registry that reserve instructions of this market have to keep up to date, `None` for
+/// markets that never initialized one.
+pub fn market_reserve_registry(lending_market_state: &LendingMarket) -> Option<Pubkey> {
+    lending_market_state
+        .has_reserve_registry()
+        .then_some(lending_market_state.reserve_registry)
+}
+
+fn reserve_state<'a>(
+    reserves: &'a HashMap<Pubkey, Reserve>,
+    reserve: &Pubkey,
+) -> ClientResult<&'a Reserve> {
+    reserves
+        .get(reserve)
+        .ok_or(crate::ClientError::MissingReserve(*reserve))
+}
+
+/// The reserve insurance vault, `None` for reserves without one.
+fn insurance_vault(reserve_state: &Reserve) -> Option<Pubkey> {
+    let vault = reserve_state.liquidity.insurance_vault;
+    (vault != Pubkey::default()).then_some(vault)
+}
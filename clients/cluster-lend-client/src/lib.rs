@@ -0,0 +1,19 @@
+//! Off-chain helpers for building `cluster_lend` transactions.
+//!
+//! * [`instructions`] has one typed builder per program entrypoint.
+//! * [`refresh`] prepends the `refresh_reserve`/`refresh_obligation` instructions that
+//!   the program checks for in `check_refresh_ixs!`.
+//! * [`pda`] derives every program address used by the market.
+//! * [`accounts`] deserializes the zero-copy `LendingMarket`, `Reserve`, `ReserveRegistry`,
+//!   `FeeDistribution` and `Obligation` accounts, and the borsh `QueuedUpdate` account, from raw account data.
+
+pub mod accounts;
+pub mod error;
+pub mod instructions;
+pub mod pda;
+pub mod refresh;
+
+pub use cluster_lend;
+pub use error::ClientError;
+
+pub type ClientResult<T> = std::result::Result<T, ClientError>;
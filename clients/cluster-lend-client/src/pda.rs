@@ -0,0 +1,24 @@
+use anchor_lang::prelude::Pubkey;
+pub use cluster_lend::utils::seeds::pda::*;
+use cluster_lend::InitObligationArgs;
+
+pub fn obligation(
+    args: &InitObligationArgs,
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    seed1_account: &Pubkey,
+    seed2_account: &Pubkey,
+) -> Pubkey {
+    let (obligation, _obligation_bump) = Pubkey::find_program_address(
+        &[
+            &[args.tag],
+            &[args.id],
+            owner.as_ref(),
+            lending_market.as_ref(),
+            seed1_account.as_ref(),
+            seed2_account.as_ref(),
+        ],
+        &cluster_lend::ID,
+    );
+    obligation
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::instruction::Instruction,
+    InstructionData, ToAccountMetas,
+};
+use cluster_lend::{Obligation, Reserve};
+
+use crate::{ClientError, ClientResult};
+
+pub fn pyth_oracle(reserve: &Reserve) -> Option<Pubkey> {
+    let pyth = &reserve.config.token_info.pyth_configuration;
+    if pyth.is_enabled() {
+        Some(pyth.price)
+    } else {
+        None
+    }
+}
+
+pub fn refresh_reserve(reserve: &Pubkey, reserve_state: &Reserve) -> Instruction {
+    let accounts = cluster_lend::accounts::RefreshReserveCtx {
+        reserve: *reserve,
+        lending_market: reserve_state.lending_market,
+        pyth_oracle: pyth_oracle(reserve_state),
+    };
+
+    Instruction {
+        program_id: cluster_lend::ID,
+        accounts: accounts.to_account_metas(None),
+        data: cluster_lend::instruction::RefreshReserve {}.data(),
+    }
+}
+
+/// Refreshes all `reserves` of one market in a single instruction. Reserves without an
+/// enabled oracle get the program id in their oracle slot.
+pub fn refresh_reserves_batch(
+    lending_market: &Pubkey,
+    reserves: &[(Pubkey, &Reserve)],
+) -> Instruction {
+    let accounts = cluster_lend::accounts::RefreshReservesBatchCtx {
+        lending_market: *lending_market,
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    for (reserve, reserve_state) in reserves {
+        account_metas.push(AccountMeta::new(*reserve, false));
+        account_metas.push(AccountMeta::new_readonly(
+            pyth_oracle(reserve_state).unwrap_or(cluster_lend::ID),
+            false,
+        ));
+    }
+
+    Instruction {
+        program_id: cluster_lend::ID,
+        accounts: account_metas,
+        data: cluster_lend::instruction::RefreshReservesBatch {}.data(),
+    }
+}
+
+/// Deposit reserves first, then borrow reserves, in slot order, which is the order
+/// `refresh_obligation` expects its remaining accounts in.
+pub fn obligation_reserves(obligation: &Obligation) -> Vec<Pubkey> {
+    obligation
+        .deposits
+        .iter()
+        .map(|deposit| deposit.deposit_reserve)
+        .chain(
+            obligation
+                .borrows
+                .iter()
+                .map(|borrow| borrow.borrow_reserve),
+        )
+        .filter(|reserve| *reserve != Pubkey::default())
+        .collect()
+}
+
+pub fn refresh_obligation(obligation: &Pubkey, obligation_state: &Obligation) -> Instruction {
+    let accounts = cluster_lend::accounts::RefreshObligationCtx {
+        lending_market: obligation_state.lending_market,
+        obligation: *obligation,
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(
+        obligation_reserves(obligation_state)
+            .into_iter()
+            .map(|reserve| AccountMeta::new(reserve, false)),
+    );
+
+    Instruction {
+        program_id: cluster_lend::ID,
+        accounts: account_metas,
+        data: cluster_lend::instruction::RefreshObligation {}.data(),
+    }
+}
+
+/// Wraps an obligation instruction with the refresh instructions `check_refresh` requires
+/// in front of it.
+///
+/// The resulting order is:
+/// 1. `refresh_reserve` for every obligation reserve that is not a target reserve, so that
+///    `refresh_obligation` does not fail on a stale reserve,
+/// 2. `refresh_reserve` for each distinct target reserve, in the order the program lists them
+///    (e.g. `[withdraw_reserve, repay_reserve]` for liquidations),
+/// 3. `refresh_obligation`,
+/// 4. `ix`.
+pub fn with_refresh_ixs(
+    obligation: &Pubkey,
+    obligation_state: &Obligation,
+    target_reserves: &[Pubkey],
+    reserves: &HashMap<Pubkey, Reserve>,
+    ix: Instruction,
+) -> ClientResult<Vec<Instruction>> {
+    let mut ixs = Vec::new();
+    for reserve in refresh_order(obligation_state, target_reserves) {
+        let reserve_state = reserves
+            .get(&reserve)
+            .ok_or(ClientError::MissingReserve(reserve))?;
+        ixs.push(refresh_reserve(&reserve, reserve_state));
+    }
+    ixs.push(refresh_obligation(obligation, obligation_state));
+    ixs.push(ix);
+
+    Ok(ixs)
+}
+
+/// Same as [`with_refresh_ixs`], but refreshes all reserves with one
+/// `refresh_reserves_batch`, which keeps obligations with many positions under the
+/// transaction size limit.
+pub fn with_batch_refresh_ixs(
+    obligation: &Pubkey,
+    obligation_state: &Obligation,
+    target_reserves: &[Pubkey],
+    reserves: &HashMap<Pubkey, Reserve>,
+    ix: Instruction,
+) -> ClientResult<Vec<Instruction>> {
+    let batch = refresh_order(obligation_state, target_reserves)
+        .into_iter()
+        .map(|reserve| {
+            reserves
+                .get(&reserve)
+                .map(|reserve_state| (reserve, reserve_state))
+                .ok_or(ClientError::MissingReserve(reserve))
+        })
+        .collect::<ClientResult<Vec<_>>>()?;
+
+    Ok(vec![
+        refresh_reserves_batch(&obligation_state.lending_market, &batch),
+        refresh_obligation(obligation, obligation_state),
+        ix,
+    ])
+}
+
+fn refresh_order(obligation_state: &Obligation, target_reserves: &[Pubkey]) -> Vec<Pubkey> {
+    let mut target_reserves = target_reserves.to_vec();
+    target_reserves.dedup();
+
+    obligation_reserves(obligation_state)
+        .into_iter()
+        .filter(|reserve| !target_reserves.contains(reserve))
+        .fold(Vec::new(), |mut acc, reserve| {
+            if !acc.contains(&reserve) {
+                acc.push(reserve);
+            }
+            acc
+        })
+        .into_iter()
+        .chain(target_reserves)
+        .collect()
+}
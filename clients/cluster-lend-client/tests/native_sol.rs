@@ -0,0 +1,59 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program},
+};
+use cluster_lend::Reserve;
+use cluster_lend_client::{instructions, pda};
+
+fn has_account(ix: &Instruction, key: &Pubkey) -> bool {
+    ix.accounts.iter().any(|meta| meta.pubkey == *key)
+}
+
+/// A default reserve holds all-zero keys, which are the system program id.
+fn reserve() -> Reserve {
+    let mut reserve = Reserve {
+        lending_market: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    reserve.liquidity.mint_pubkey = Pubkey::new_unique();
+    reserve.liquidity.supply_vault = Pubkey::new_unique();
+    reserve.liquidity.fee_vault = Pubkey::new_unique();
+    reserve.collateral.mint_pubkey = Pubkey::new_unique();
+    reserve.collateral.supply_vault = Pubkey::new_unique();
+    reserve
+}
+
+#[test]
+fn success_native_deposit_uses_user_wsol() {
+    let owner = Pubkey::new_unique();
+    let ix = instructions::deposit_reserve_liquidity(
+        &owner,
+        &Pubkey::new_unique(),
+        &reserve(),
+        None,
+        &Pubkey::new_unique(),
+        1_000,
+    );
+
+    assert!(has_account(&ix, &pda::user_wsol(&owner)));
+    assert!(has_account(&ix, &system_program::ID));
+    assert!(ix.accounts[0].is_writable);
+}
+
+#[test]
+fn success_token_account_deposit_skips_user_wsol() {
+    let owner = Pubkey::new_unique();
+    let user_source_liquidity = Pubkey::new_unique();
+    let ix = instructions::deposit_reserve_liquidity(
+        &owner,
+        &Pubkey::new_unique(),
+        &reserve(),
+        Some(&user_source_liquidity),
+        &Pubkey::new_unique(),
+        1_000,
+    );
+
+    assert!(has_account(&ix, &user_source_liquidity));
+    assert!(!has_account(&ix, &pda::user_wsol(&owner)));
+    assert!(!has_account(&ix, &system_program::ID));
+}
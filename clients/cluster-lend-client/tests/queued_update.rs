@@ -0,0 +1,124 @@
+use anchor_lang::{prelude::Pubkey, AccountSerialize, InstructionData};
+use cluster_lend::{
+    LendingMarketUpdate, QueuedUpdate, QueuedUpdateKind, Reserve, ReserveConfig,
+    ReserveConfigUpdate,
+};
+use cluster_lend_client::{accounts, instructions, pda};
+
+fn queued(update: QueuedUpdateKind) -> QueuedUpdate {
+    QueuedUpdate {
+        lending_market: Pubkey::new_unique(),
+        payer: Pubkey::new_unique(),
+        earliest_execution_ts: 1_700_000_000,
+        update,
+    }
+}
+
+#[test]
+fn success_queued_update_roundtrip() {
+    let state = queued(QueuedUpdateKind::Reserve {
+        reserve: Pubkey::new_unique(),
+        update: ReserveConfigUpdate::DepositLimit(42),
+    });
+
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    // Accounts are allocated for the largest update, the tail stays zeroed
+    data.resize(QueuedUpdate::SPACE, 0);
+
+    let decoded = accounts::queued_update(&data).unwrap();
+    assert_eq!(decoded.lending_market, state.lending_market);
+    assert_eq!(decoded.payer, state.payer);
+    assert_eq!(decoded.earliest_execution_ts, state.earliest_execution_ts);
+    assert_eq!(decoded.update, state.update);
+}
+
+#[test]
+fn success_largest_update_fills_the_account() {
+    let state = queued(QueuedUpdateKind::Reserve {
+        reserve: Pubkey::new_unique(),
+        update: ReserveConfigUpdate::EntireReserveConfig(ReserveConfig::default()),
+    });
+
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), QueuedUpdate::SPACE);
+}
+
+#[test]
+fn success_execute_picks_market_or_reserve_entrypoint() {
+    let queued_update = Pubkey::new_unique();
+
+    let market_state = queued(QueuedUpdateKind::Market(
+        LendingMarketUpdate::GlobalAllowedBorrowValue(1),
+    ));
+    let ix = instructions::execute_queued_update(&queued_update, &market_state, None);
+    assert_eq!(
+        ix.data,
+        cluster_lend::instruction::ExecuteMarketUpdate {}.data()
+    );
+    assert_eq!(ix.accounts[0].pubkey, market_state.payer);
+
+    let reserve = Pubkey::new_unique();
+    let reserve_state = queued(QueuedUpdateKind::Reserve {
+        reserve,
+        update: ReserveConfigUpdate::BorrowLimit(1),
+    });
+    let reserve_registry = pda::reserve_registry(&reserve_state.lending_market);
+    let ix =
+        instructions::execute_queued_update(&queued_update, &reserve_state, Some(reserve_registry));
+    assert_eq!(
+        ix.data,
+        cluster_lend::instruction::ExecuteReserveUpdate {}.data()
+    );
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == reserve));
+    assert!(ix
+        .accounts
+        .iter()
+        .any(|meta| meta.pubkey == reserve_registry));
+}
+
+#[test]
+fn success_execute_without_registry_passes_program_id() {
+    let reserve_state = queued(QueuedUpdateKind::Reserve {
+        reserve: Pubkey::new_unique(),
+        update: ReserveConfigUpdate::BorrowLimit(1),
+    });
+    let ix = instructions::execute_queued_update(&Pubkey::new_unique(), &reserve_state, None);
+    assert!(ix
+        .accounts
+        .iter()
+        .all(|meta| meta.pubkey != pda::reserve_registry(&reserve_state.lending_market)));
+    assert!(ix
+        .accounts
+        .iter()
+        .any(|meta| meta.pubkey == cluster_lend::ID));
+}
+
+#[test]
+fn success_execute_insurance_withdrawal_pays_the_queued_destination() {
+    let reserve = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let state = queued(QueuedUpdateKind::InsuranceWithdrawal {
+        reserve,
+        destination,
+        amount: 1,
+    });
+    let mut reserve_state = Reserve::default();
+    reserve_state.liquidity.insurance_vault = Pubkey::new_unique();
+
+    let ix =
+        instructions::execute_insurance_withdrawal(&Pubkey::new_unique(), &state, &reserve_state);
+    assert_eq!(
+        ix.data,
+        cluster_lend::instruction::ExecuteInsuranceWithdrawal {}.data()
+    );
+    assert_eq!(ix.accounts[0].pubkey, state.payer);
+    for key in [
+        reserve,
+        destination,
+        reserve_state.liquidity.insurance_vault,
+    ] {
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == key));
+    }
+}
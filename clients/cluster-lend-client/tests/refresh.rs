@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, Discriminator};
+use cluster_lend::{instruction, Obligation, Reserve};
+use cluster_lend_client::{instructions, refresh, ClientError};
+
+fn reserve_with_market(lending_market: Pubkey) -> Reserve {
+    Reserve {
+        lending_market,
+        ..Reserve::default()
+    }
+}
+
+fn discriminator(ix: &Instruction) -> [u8; 8] {
+    ix.data[..8].try_into().unwrap()
+}
+
+#[test]
+fn success_refresh_order_matches_check_refresh() {
+    let lending_market = Pubkey::new_unique();
+    let obligation_key = Pubkey::new_unique();
+    let collateral_reserve = Pubkey::new_unique();
+    let repay_reserve = Pubkey::new_unique();
+    let withdraw_reserve = collateral_reserve;
+
+    let mut obligation = Obligation {
+        lending_market,
+        ..Obligation::default()
+    };
+    obligation.deposits[0].deposit_reserve = collateral_reserve;
+    obligation.borrows[0].borrow_reserve = repay_reserve;
+
+    let reserves = HashMap::from([
+        (collateral_reserve, reserve_with_market(lending_market)),
+        (repay_reserve, reserve_with_market(lending_market)),
+    ]);
+
+    let ixs = refresh::with_refresh_ixs(
+        &obligation_key,
+        &obligation,
+        &[withdraw_reserve, repay_reserve],
+        &reserves,
+        Instruction {
+            program_id: cluster_lend::ID,
+            accounts: vec![],
+            data: vec![],
+        },
+    )
+    .unwrap();
+
+    assert_eq!(ixs.len(), 4);
+    assert_eq!(
+        discriminator(&ixs[0]),
+        instruction::RefreshReserve::DISCRIMINATOR
+    );
+    assert_eq!(ixs[0].accounts[0].pubkey, withdraw_reserve);
+    assert_eq!(
+        discriminator(&ixs[1]),
+        instruction::RefreshReserve::DISCRIMINATOR
+    );
+    assert_eq!(ixs[1].accounts[0].pubkey, repay_reserve);
+    assert_eq!(
+        discriminator(&ixs[2]),
+        instruction::RefreshObligation::DISCRIMINATOR
+    );
+    assert_eq!(ixs[2].accounts[1].pubkey, obligation_key);
+    assert_eq!(
+        ixs[2].accounts[2..]
+            .iter()
+            .map(|meta| meta.pubkey)
+            .collect::<Vec<_>>(),
+        vec![collateral_reserve, repay_reserve]
+    );
+}
+
+#[test]
+fn success_untouched_obligation_reserves_refreshed_first() {
+    let lending_market = Pubkey::new_unique();
+    let obligation_key = Pubkey::new_unique();
+    let collateral_reserve = Pubkey::new_unique();
+    let borrow_reserve = Pubkey::new_unique();
+
+    let mut obligation = Obligation {
+        lending_market,
+        ..Obligation::default()
+    };
+    obligation.deposits[0].deposit_reserve = collateral_reserve;
+
+    let reserves = HashMap::from([
+        (collateral_reserve, reserve_with_market(lending_market)),
+        (borrow_reserve, reserve_with_market(lending_market)),
+    ]);
+
+    let ixs = instructions::borrow_obligation_liquidity(
+        &Pubkey::new_unique(),
+        &obligation_key,
+        &obligation,
+        &borrow_reserve,
+        &reserves,
+        Some(&Pubkey::new_unique()),
+        100,
+    )
+    .unwrap();
+
+    assert_eq!(ixs.len(), 4);
+    assert_eq!(ixs[0].accounts[0].pubkey, collateral_reserve);
+    assert_eq!(ixs[1].accounts[0].pubkey, borrow_reserve);
+    assert_eq!(
+        discriminator(&ixs[3]),
+        instruction::BorrowObligationLiquidity::DISCRIMINATOR
+    );
+}
+
+#[test]
+fn failure_missing_reserve_state() {
+    let obligation = Obligation::default();
+    let reserve = Pubkey::new_unique();
+
+    let res = refresh::with_refresh_ixs(
+        &Pubkey::new_unique(),
+        &obligation,
+        &[reserve],
+        &HashMap::new(),
+        Instruction {
+            program_id: cluster_lend::ID,
+            accounts: vec![],
+            data: vec![],
+        },
+    );
+
+    assert_eq!(res.unwrap_err(), ClientError::MissingReserve(reserve));
+}
+
+#[test]
+fn success_batch_refresh_covers_all_reserves() {
+    let lending_market = Pubkey::new_unique();
+    let obligation_key = Pubkey::new_unique();
+    let collateral_reserve = Pubkey::new_unique();
+    let borrow_reserve = Pubkey::new_unique();
+
+    let mut obligation = Obligation {
+        lending_market,
+        ..Obligation::default()
+    };
+    obligation.deposits[0].deposit_reserve = collateral_reserve;
+
+    let reserves = HashMap::from([
+        (collateral_reserve, reserve_with_market(lending_market)),
+        (borrow_reserve, reserve_with_market(lending_market)),
+    ]);
+
+    let ixs = refresh::with_batch_refresh_ixs(
+        &obligation_key,
+        &obligation,
+        &[borrow_reserve],
+        &reserves,
+        Instruction {
+            program_id: cluster_lend::ID,
+            accounts: vec![],
+            data: vec![],
+        },
+    )
+    .unwrap();
+
+    assert_eq!(ixs.len(), 3);
+    assert_eq!(
+        discriminator(&ixs[0]),
+        instruction::RefreshReservesBatch::DISCRIMINATOR
+    );
+    assert_eq!(ixs[0].accounts[0].pubkey, lending_market);
+    assert_eq!(
+        ixs[0].accounts[1..]
+            .iter()
+            .map(|meta| meta.pubkey)
+            .collect::<Vec<_>>(),
+        vec![
+            collateral_reserve,
+            cluster_lend::ID,
+            borrow_reserve,
+            cluster_lend::ID
+        ]
+    );
+    assert_eq!(
+        discriminator(&ixs[1]),
+        instruction::RefreshObligation::DISCRIMINATOR
+    );
+}
@@ -22,6 +22,13 @@ pub const UNINITIALIZED_VERSION: u8 = 0;
 pub const INITIAL_COLLATERAL_RATIO: u64 = 1;
 pub const INITIAL_COLLATERAL_RATE: Fraction = fraction!(1);
 
+/// Smallest liquidity the owner deposits when listing a reserve, see [`initial_deposit_amount`].
+pub const MIN_INITIAL_DEPOSIT_AMOUNT: u64 = 1_000;
+
+/// The seed deposit is one whole token divided by this, so it keeps its value on mints with
+/// many decimals.
+pub const INITIAL_DEPOSIT_TOKEN_DIVISOR: u64 = 1_000;
+
 pub const LIQUIDATION_CLOSE_FACTOR: u8 = 20;
 
 pub const LIQUIDATION_CLOSE_VALUE: u64 = 2;
@@ -32,6 +39,12 @@ pub const MIN_AUTODELEVERAGE_BONUS_BPS: u64 = 50;
 
 pub const MAX_OBLIGATION_RESERVES: u64 = 20;
 
+pub const MAX_RESERVES_PER_MARKET: usize = 64;
+
+pub const MAX_FEE_RECIPIENTS: usize = 8;
+
+pub const MAX_CONFIG_TIMELOCK_SECS: u64 = 30 * 24 * 60 * 60;
+
 pub const CLOSE_TO_INSOLVENCY_RISKY_LTV: u8 = 95;
 
 pub const NULL_PUBKEY: pubkey::Pubkey = solana_program::pubkey::Pubkey::new_from_array([
@@ -83,3 +96,11 @@ pub fn ten_pow(x: usize) -> u64 {
 
     POWERS_OF_TEN[x]
 }
+
+/// Liquidity the owner deposits when listing a reserve of a mint with `mint_decimals`. The
+/// minted cTokens are locked forever, so the collateral supply never drops back to the few
+/// units an exchange rate attack needs.
+pub fn initial_deposit_amount(mint_decimals: u8) -> u64 {
+    let one_token = ten_pow(usize::from(mint_decimals).min(19));
+    (one_token / INITIAL_DEPOSIT_TOKEN_DIVISOR).max(MIN_INITIAL_DEPOSIT_AMOUNT)
+}
@@ -166,6 +166,42 @@ pub enum LendingError {
     ReserveVaultBalanceMismatch,
     #[msg("Reserve internal state accounting has been unexpectedly modified")]
     ReserveAccountingMismatch,
+    #[msg("Liquidity mint has a token extension that is not supported")]
+    UnsupportedTokenExtension,
+    #[msg(
+        "Native SOL requires a wrapped SOL reserve, the user wSOL account and the system program"
+    )]
+    InvalidNativeSolAccounts,
+    #[msg("Reserve registry is full")]
+    ReserveRegistryFull,
+    #[msg("Reserve is not in the market reserve registry")]
+    ReserveNotRegistered,
+    #[msg("The market has a reserve registry, it has to be passed to keep it up to date")]
+    ReserveRegistryMissing,
+    #[msg("No transfer is pending for this market role")]
+    NoPendingRoleTransfer,
+    #[msg("Signer is not the pending holder of this market role")]
+    InvalidPendingRoleHolder,
+    #[msg("Guardian can only tighten market and reserve settings")]
+    GuardianUpdateNotAllowed,
+    #[msg("Market has a config timelock, queue this update instead")]
+    UpdateRequiresTimelock,
+    #[msg("Queued update cannot be executed before its timelock expires")]
+    QueuedUpdateNotReady,
+    #[msg("Queued update does not target the given accounts")]
+    QueuedUpdateMismatch,
+    #[msg("Operation is paused on this reserve")]
+    ReserveOperationPaused,
+    #[msg("Reserve is reduce-only and accepts no new exposure")]
+    ReserveReduceOnly,
+    #[msg("Reserve still has debt, collateral or unredeemed fees")]
+    ReserveNotEmpty,
+    #[msg("Fee recipients must be unique, non-zero and their shares must add up to 100%")]
+    InvalidFeeDistribution,
+    #[msg("Fee distribution has no recipients")]
+    FeeDistributionNotConfigured,
+    #[msg("The reserve insurance vault is required to pay its share of the fees")]
+    InsuranceVaultMissing,
 }
 
 pub type LendingResult<T = ()> = std::result::Result<T, LendingError>;
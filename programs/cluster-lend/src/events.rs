@@ -0,0 +1,216 @@
+use anchor_lang::prelude::*;
+
+use crate::{AuditReserveReport, FeeShare, LendingMarketUpdate, MarketRole, ReserveConfigUpdate};
+
+#[event]
+pub struct DepositReserveLiquidityEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub owner: Pubkey,
+    pub liquidity_amount: u64,
+    pub collateral_amount: u64,
+}
+
+#[event]
+pub struct RedeemReserveCollateralEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub owner: Pubkey,
+    pub collateral_amount: u64,
+    pub liquidity_amount: u64,
+}
+
+#[event]
+pub struct DepositObligationCollateralEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub obligation: Pubkey,
+    pub owner: Pubkey,
+    pub collateral_amount: u64,
+}
+
+#[event]
+pub struct WithdrawObligationCollateralEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub obligation: Pubkey,
+    pub owner: Pubkey,
+    pub collateral_amount: u64,
+}
+
+#[event]
+pub struct BorrowObligationLiquidityEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub obligation: Pubkey,
+    pub owner: Pubkey,
+    pub receive_amount: u64,
+    pub borrow_fee: u64,
+}
+
+#[event]
+pub struct RepayObligationLiquidityEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub obligation: Pubkey,
+    pub owner: Pubkey,
+    pub repay_amount: u64,
+}
+
+#[event]
+pub struct LiquidateObligationEvent {
+    pub lending_market: Pubkey,
+    pub obligation: Pubkey,
+    pub liquidator: Pubkey,
+    pub repay_reserve: Pubkey,
+    pub withdraw_reserve: Pubkey,
+    pub repay_amount: u64,
+    /// cTokens redeemed, or kept by the liquidator with `liquidate_obligation_no_redeem`.
+    pub withdraw_collateral_amount: u64,
+    /// Liquidity the liquidator received after redeeming the seized collateral, zero when the
+    /// withdraw reserve could not be redeemed and collateral was kept instead.
+    pub withdraw_liquidity_amount: u64,
+    pub liquidation_bonus_rate_sf: u128,
+    pub protocol_fee: u64,
+}
+
+#[event]
+pub struct FlashBorrowReserveEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub user_transfer_authority: Pubkey,
+    pub liquidity_amount: u64,
+}
+
+#[event]
+pub struct FlashRepayReserveEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub user_transfer_authority: Pubkey,
+    pub liquidity_amount: u64,
+    pub origination_fee: u64,
+}
+
+#[event]
+pub struct RedeemFeesEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WithdrawProtocolFeesEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct UpdateMarketConfigEvent {
+    pub lending_market: Pubkey,
+    pub update: LendingMarketUpdate,
+}
+
+#[event]
+pub struct ProposeMarketRoleEvent {
+    pub lending_market: Pubkey,
+    pub role: MarketRole,
+    pub pending_holder: Pubkey,
+}
+
+#[event]
+pub struct AcceptMarketRoleEvent {
+    pub lending_market: Pubkey,
+    pub role: MarketRole,
+    pub previous_holder: Pubkey,
+    pub new_holder: Pubkey,
+}
+
+#[event]
+pub struct CancelMarketRoleEvent {
+    pub lending_market: Pubkey,
+    pub role: MarketRole,
+    pub cancelled_holder: Pubkey,
+}
+
+#[event]
+pub struct CloseReserveEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub swept_fees: u64,
+    pub swept_supply: u64,
+}
+
+#[event]
+pub struct SkimReserveExcessEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub liquidity_amount: u64,
+    pub collateral_amount: u64,
+}
+
+#[event]
+pub struct AuditReserveEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub report: AuditReserveReport,
+    pub paused: bool,
+}
+
+#[event]
+pub struct UpdateFeeDistributionEvent {
+    pub lending_market: Pubkey,
+    pub shares: Vec<FeeShare>,
+}
+
+#[event]
+pub struct DistributeFeesEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DepositReserveInsuranceEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WithdrawReserveInsuranceEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SocializeLossEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub obligation: Pubkey,
+    /// Debt repaid out of the insurance vault
+    pub insurance_amount: u64,
+    /// Debt forgiven at the expense of depositors
+    pub socialized_amount_sf: u128,
+}
+
+#[event]
+pub struct QueueConfigUpdateEvent {
+    pub lending_market: Pubkey,
+    pub queued_update: Pubkey,
+    pub earliest_execution_ts: i64,
+}
+
+#[event]
+pub struct CancelQueuedUpdateEvent {
+    pub lending_market: Pubkey,
+    pub queued_update: Pubkey,
+}
+
+#[event]
+pub struct UpdateReserveConfigEvent {
+    pub lending_market: Pubkey,
+    pub reserve: Pubkey,
+    pub update: ReserveConfigUpdate,
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{Mint, TokenAccount},
+    token_interface,
+};
+
+use crate::{
+    events::AuditReserveEvent,
+    state::{AuditReserveReport, LendingMarket, Reserve, ReservePauseFlags},
+    utils::token_2022,
+};
+
+/// Operations `audit_reserve_and_pause` stops on a failed audit. Repaying, withdrawing and
+/// liquidating stay open so that users can still reduce their exposure.
+const PAUSED_ON_FAILED_AUDIT: ReservePauseFlags = ReservePauseFlags::DEPOSIT
+    .union(ReservePauseFlags::BORROW)
+    .union(ReservePauseFlags::FLASH_LOAN);
+
+/// Checks the reserve accounting against its vaults. Anyone can call it, the reserve is only
+/// read.
+pub fn process_audit_reserve(ctx: Context<AuditReserveCtx>) -> Result<AuditReserveReport> {
+    let report = ctx.accounts.report()?;
+
+    if !report.is_healthy() {
+        msg!("Reserve accounting invariant failed: {:?}", report);
+
+        emit!(AuditReserveEvent {
+            lending_market: ctx.accounts.lending_market.key(),
+            reserve: ctx.accounts.reserve.key(),
+            report,
+            paused: false,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Same checks as `audit_reserve`, but a failed invariant also pauses deposits, borrows and
+/// flash loans on the reserve until the owner resumes them.
+pub fn process_audit_reserve_and_pause(
+    ctx: Context<AuditReserveAndPauseCtx>,
+) -> Result<AuditReserveReport> {
+    let report = ctx.accounts.report()?;
+
+    if !report.is_healthy() {
+        msg!("Reserve accounting invariant failed: {:?}", report);
+
+        let reserve = &mut ctx.accounts.reserve.load_mut()?;
+        reserve.config.paused_operations |= PAUSED_ON_FAILED_AUDIT.bits();
+
+        emit!(AuditReserveEvent {
+            lending_market: ctx.accounts.lending_market.key(),
+            reserve: ctx.accounts.reserve.key(),
+            report,
+            paused: true,
+        });
+    }
+
+    Ok(report)
+}
+
+#[derive(Accounts)]
+pub struct AuditReserveCtx<'info> {
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(
+        address = reserve.load()?.liquidity.mint_pubkey,
+    )]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    #[account(
+        address = reserve.load()?.liquidity.supply_vault,
+    )]
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(
+        address = reserve.load()?.liquidity.fee_vault,
+    )]
+    pub fee_receiver: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(
+        address = reserve.load()?.collateral.mint_pubkey,
+    )]
+    pub reserve_collateral_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        address = reserve.load()?.collateral.supply_vault,
+    )]
+    pub reserve_collateral_supply: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> AuditReserveCtx<'info> {
+    fn report(&self) -> Result<AuditReserveReport> {
+        Ok(self.reserve.load()?.audit(
+            self.reserve_liquidity_supply.amount,
+            self.reserve_collateral_supply.amount,
+            self.reserve_collateral_mint.supply,
+            self.fee_receiver.amount,
+            !token_2022::has_transfer_fee_config(&self.reserve_liquidity_mint.to_account_info())?,
+        ))
+    }
+}
+
+#[derive(Accounts)]
+pub struct AuditReserveAndPauseCtx<'info> {
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(
+        address = reserve.load()?.liquidity.mint_pubkey,
+    )]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    #[account(
+        address = reserve.load()?.liquidity.supply_vault,
+    )]
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(
+        address = reserve.load()?.liquidity.fee_vault,
+    )]
+    pub fee_receiver: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(
+        address = reserve.load()?.collateral.mint_pubkey,
+    )]
+    pub reserve_collateral_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        address = reserve.load()?.collateral.supply_vault,
+    )]
+    pub reserve_collateral_supply: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> AuditReserveAndPauseCtx<'info> {
+    fn report(&self) -> Result<AuditReserveReport> {
+        Ok(self.reserve.load()?.audit(
+            self.reserve_liquidity_supply.amount,
+            self.reserve_collateral_supply.amount,
+            self.reserve_collateral_mint.supply,
+            self.fee_receiver.amount,
+            !token_2022::has_transfer_fee_config(&self.reserve_liquidity_mint.to_account_info())?,
+        ))
+    }
+}
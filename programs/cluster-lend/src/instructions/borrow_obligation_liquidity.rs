@@ -3,22 +3,26 @@ use anchor_lang::{
     solana_program::sysvar::{instructions::Instructions as SysInstructions, SysvarId},
     Accounts,
 };
-use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::{
+    token,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
 use crate::{
     check_refresh_ixs,
     errors::LendingError,
+    events::BorrowObligationLiquidityEvent,
     gen_signer_seeds,
     lending_market::{lending_checks, lending_operations},
     state::{LendingMarket, Reserve},
-    utils::{seeds, token_transfer},
-    xmsg, CalculateBorrowResult, LendingAction, Obligation,
+    utils::{seeds, token_transfer, NativeSolAccounts},
+    xmsg, BorrowObligationLiquidityReturn, CalculateBorrowResult, LendingAction, Obligation,
 };
 
 pub fn process_borrow_obligation_liquidity(
     ctx: Context<BorrowObligationLiquidityCtx>,
     liquidity_amount: u64,
-) -> Result<()> {
+) -> Result<BorrowObligationLiquidityReturn> {
     check_refresh_ixs!(ctx, borrow_reserve);
     lending_checks::borrow_obligation_liquidity_checks(&ctx)?;
 
@@ -35,11 +39,7 @@ pub fn process_borrow_obligation_liquidity(
         token::accessor::amount(&ctx.accounts.reserve_source_liquidity.to_account_info())?;
     let initial_reserve_available_liquidity = borrow_reserve.liquidity.available_amount;
 
-    let CalculateBorrowResult {
-        receive_amount,
-        borrow_fee,
-        ..
-    } = lending_operations::borrow_obligation_liquidity(
+    let borrow_result = lending_operations::borrow_obligation_liquidity(
         lending_market,
         borrow_reserve,
         obligation,
@@ -47,12 +47,19 @@ pub fn process_borrow_obligation_liquidity(
         clock,
         ctx.accounts.borrow_reserve.key(),
     )?;
+    let CalculateBorrowResult {
+        receive_amount,
+        borrow_fee,
+        ..
+    } = borrow_result;
 
     xmsg!("pnl: Borrow obligation liquidity {receive_amount} with borrow_fee {borrow_fee}",);
 
     if borrow_fee > 0 {
         token_transfer::send_origination_fees_transfer(
             ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.decimals,
             ctx.accounts.reserve_source_liquidity.to_account_info(),
             ctx.accounts
                 .borrow_reserve_liquidity_fee_receiver
@@ -63,15 +70,38 @@ pub fn process_borrow_obligation_liquidity(
         )?;
     }
 
+    // Without a destination token account the borrowed liquidity is unwrapped and paid out in
+    // lamports
+    let (user_destination_liquidity, native_sol) = match &ctx.accounts.user_destination_liquidity {
+        Some(user_destination_liquidity) => (user_destination_liquidity.to_account_info(), None),
+        None => {
+            let native_sol = NativeSolAccounts::new(
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.user_wsol.as_ref(),
+                ctx.accounts.reserve_liquidity_mint.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.as_ref(),
+            )?;
+            native_sol.open(0)?;
+            (native_sol.user_wsol(), Some(native_sol))
+        }
+    };
+
     token_transfer::borrow_obligation_liquidity_transfer(
         ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
         ctx.accounts.reserve_source_liquidity.to_account_info(),
-        ctx.accounts.user_destination_liquidity.to_account_info(),
+        user_destination_liquidity,
         ctx.accounts.lending_market_authority.to_account_info(),
         authority_signer_seeds,
         receive_amount,
     )?;
 
+    if let Some(native_sol) = &native_sol {
+        native_sol.close()?;
+    }
+
     lending_checks::post_transfer_vault_balance_liquidity_reserve_checks(
         token::accessor::amount(&ctx.accounts.reserve_source_liquidity.to_account_info()).unwrap(),
         borrow_reserve.liquidity.available_amount,
@@ -80,11 +110,21 @@ pub fn process_borrow_obligation_liquidity(
         LendingAction::Subtractive(borrow_fee + receive_amount),
     )?;
 
-    Ok(())
+    emit!(BorrowObligationLiquidityEvent {
+        lending_market: lending_market_key,
+        reserve: ctx.accounts.borrow_reserve.key(),
+        obligation: ctx.accounts.obligation.key(),
+        owner: ctx.accounts.owner.key(),
+        receive_amount,
+        borrow_fee,
+    });
+
+    Ok(BorrowObligationLiquidityReturn::from(&borrow_result))
 }
 
 #[derive(Accounts)]
 pub struct BorrowObligationLiquidityCtx<'info> {
+    #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(mut,
@@ -107,23 +147,32 @@ pub struct BorrowObligationLiquidityCtx<'info> {
     )]
     pub borrow_reserve: AccountLoader<'info, Reserve>,
 
+    #[account(address = borrow_reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(mut,
         address = borrow_reserve.load()?.liquidity.supply_vault
     )]
-    pub reserve_source_liquidity: Box<Account<'info, TokenAccount>>,
+    pub reserve_source_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut,
         address = borrow_reserve.load()?.liquidity.fee_vault
     )]
-    pub borrow_reserve_liquidity_fee_receiver: Box<Account<'info, TokenAccount>>,
+    pub borrow_reserve_liquidity_fee_receiver: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Omitted to receive native SOL from a wrapped SOL reserve
     #[account(mut,
         token::mint = reserve_source_liquidity.mint,
         token::authority = owner
     )]
-    pub user_destination_liquidity: Box<Account<'info, TokenAccount>>,
-
-    pub token_program: Program<'info, Token>,
+    pub user_destination_liquidity: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// CHECK: temporary wSOL PDA of the owner, checked in NativeSolAccounts
+    #[account(mut)]
+    pub user_wsol: Option<UncheckedAccount<'info>>,
+
+    #[account(address = borrow_reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Option<Program<'info, System>>,
 
     /// CHECK: instruction_sysvar account
     #[account(address = SysInstructions::id())]
@@ -0,0 +1,229 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{self, Mint, Token, TokenAccount},
+    token_interface::{self, TokenInterface},
+};
+
+use crate::{
+    errors::LendingError,
+    events::CloseReserveEvent,
+    gen_signer_seeds,
+    instructions::registry_to_update,
+    state::{LendingMarket, Reserve, ReserveRegistry},
+    utils::{seeds, spltoken, token_2022, token_transfer},
+};
+
+pub fn process_close_reserve(ctx: Context<CloseReserveCtx>) -> Result<()> {
+    let lending_market = ctx.accounts.lending_market.load()?;
+    let lending_market_key = ctx.accounts.lending_market.key();
+    let reserve_key = ctx.accounts.reserve.key();
+
+    // Reserves listed before seed deposits existed have no locked collateral account
+    let locked_collateral = ctx
+        .accounts
+        .reserve_locked_collateral
+        .as_ref()
+        .map_or(0, |account| account.amount);
+
+    {
+        let reserve = ctx.accounts.reserve.load()?;
+        if !reserve.is_empty(locked_collateral)? {
+            msg!("Reserve {} is not empty", reserve_key);
+            return err!(LendingError::ReserveNotEmpty);
+        }
+        if reserve.liquidity.insurance_vault != Pubkey::default()
+            && ctx.accounts.reserve_insurance_vault.is_none()
+        {
+            return err!(LendingError::InsuranceVaultMissing);
+        }
+    }
+
+    let authority_signer_seeds: &[&[u8]] =
+        gen_signer_seeds!(lending_market_key, lending_market.bump as u8);
+
+    let swept_fees = ctx.accounts.fee_receiver.amount;
+    let swept_supply = ctx.accounts.reserve_liquidity_supply.amount;
+
+    msg!(
+        "Closing reserve {}, sweeping fees: {}, supply: {}",
+        reserve_key,
+        swept_fees,
+        swept_supply
+    );
+
+    let mut vaults = vec![
+        (ctx.accounts.fee_receiver.to_account_info(), swept_fees),
+        (
+            ctx.accounts.reserve_liquidity_supply.to_account_info(),
+            swept_supply,
+        ),
+    ];
+    // The remaining insurance goes back to the owner with the fees
+    if let Some(insurance_vault) = &ctx.accounts.reserve_insurance_vault {
+        vaults.push((insurance_vault.to_account_info(), insurance_vault.amount));
+    }
+
+    token_2022::harvest_withheld_tokens_to_mint(
+        ctx.accounts.liquidity_token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        &vaults
+            .iter()
+            .map(|(vault, _)| vault.clone())
+            .collect::<Vec<_>>(),
+    )?;
+
+    for (vault, amount) in vaults {
+        if amount > 0 {
+            token_transfer::withdraw_fees_from_reserve(
+                ctx.accounts.liquidity_token_program.to_account_info(),
+                ctx.accounts.reserve_liquidity_mint.to_account_info(),
+                ctx.accounts.reserve_liquidity_mint.decimals,
+                vault.clone(),
+                ctx.accounts.lending_market_owner_ata.to_account_info(),
+                ctx.accounts.lending_market_authority.to_account_info(),
+                authority_signer_seeds,
+                amount,
+            )?;
+        }
+
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.liquidity_token_program.to_account_info(),
+            token_interface::CloseAccount {
+                account: vault,
+                destination: ctx.accounts.owner.to_account_info(),
+                authority: ctx.accounts.lending_market_authority.to_account_info(),
+            },
+            &[authority_signer_seeds],
+        ))?;
+    }
+
+    if let Some(reserve_locked_collateral) = &ctx.accounts.reserve_locked_collateral {
+        // The seed liquidity backing these cTokens was swept with the supply vault above
+        if locked_collateral > 0 {
+            spltoken::burn_with_signer(
+                ctx.accounts.reserve_collateral_mint.to_account_info(),
+                reserve_locked_collateral.to_account_info(),
+                ctx.accounts.lending_market_authority.to_account_info(),
+                ctx.accounts.collateral_token_program.to_account_info(),
+                locked_collateral,
+                &[authority_signer_seeds],
+            )?;
+        }
+
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.collateral_token_program.to_account_info(),
+            token::CloseAccount {
+                account: reserve_locked_collateral.to_account_info(),
+                destination: ctx.accounts.owner.to_account_info(),
+                authority: ctx.accounts.lending_market_authority.to_account_info(),
+            },
+            &[authority_signer_seeds],
+        ))?;
+    }
+
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.collateral_token_program.to_account_info(),
+        token::CloseAccount {
+            account: ctx.accounts.reserve_collateral_supply.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.lending_market_authority.to_account_info(),
+        },
+        &[authority_signer_seeds],
+    ))?;
+
+    if let Some(reserve_registry) =
+        registry_to_update(&lending_market, &ctx.accounts.reserve_registry)?
+    {
+        reserve_registry.load_mut()?.remove_reserve(&reserve_key)?;
+    }
+
+    emit!(CloseReserveEvent {
+        lending_market: lending_market_key,
+        reserve: reserve_key,
+        swept_fees,
+        swept_supply,
+    });
+
+    Ok(())
+}
+
+/// The collateral mint stays behind: SPL Token mints cannot be closed. Its supply is zero and
+/// only the market authority can mint, so it is inert once the reserve is gone.
+/// `reserve_locked_collateral` is only omitted for reserves listed without a seed deposit, and
+/// `reserve_insurance_vault` for reserves without an insurance vault.
+#[derive(Accounts)]
+pub struct CloseReserveCtx<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ LendingError::InvalidMarketOwner,
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    /// CHECK: market authority PDA
+    #[account(
+        seeds = [seeds::LENDING_MARKET_AUTH, lending_market.key().as_ref()],
+        bump = lending_market.load()?.bump as u8,
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    #[account(mut,
+        has_one = lending_market,
+        close = owner,
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    /// Only omitted on markets without a reserve registry.
+    #[account(mut,
+        address = lending_market.load()?.reserve_registry
+    )]
+    pub reserve_registry: Option<AccountLoader<'info, ReserveRegistry>>,
+
+    /// Writable to harvest the transfer fees withheld on the vaults
+    #[account(mut,
+        address = reserve.load()?.liquidity.mint_pubkey,
+    )]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    #[account(mut,
+        address = reserve.load()?.liquidity.supply_vault,
+    )]
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(mut,
+        address = reserve.load()?.liquidity.fee_vault,
+    )]
+    pub fee_receiver: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(mut,
+        address = reserve.load()?.collateral.mint_pubkey,
+    )]
+    pub reserve_collateral_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut,
+        address = reserve.load()?.collateral.supply_vault,
+    )]
+    pub reserve_collateral_supply: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut,
+        seeds = [seeds::RESERVE_LOCKED_COLL, lending_market.key().as_ref(), reserve_liquidity_mint.key().as_ref()],
+        bump,
+    )]
+    pub reserve_locked_collateral: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut,
+        address = reserve.load()?.liquidity.insurance_vault,
+    )]
+    pub reserve_insurance_vault:
+        Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    #[account(mut,
+        token::mint = reserve.load()?.liquidity.mint_pubkey,
+    )]
+    pub lending_market_owner_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    pub collateral_token_program: Program<'info, Token>,
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub liquidity_token_program: Interface<'info, TokenInterface>,
+}
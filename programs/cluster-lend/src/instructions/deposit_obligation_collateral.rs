@@ -7,6 +7,7 @@ use anchor_spl::token::{Token, TokenAccount};
 
 use crate::{
     check_refresh_ixs,
+    events::DepositObligationCollateralEvent,
     lending_market::{lending_checks, lending_operations},
     state::{LendingMarket, Reserve},
     utils::token_transfer,
@@ -16,7 +17,7 @@ use crate::{
 pub fn process_deposit_obligation_collateral(
     ctx: Context<DepositObligationCollateralCtx>,
     collateral_amount: u64,
-) -> Result<()> {
+) -> Result<u64> {
     check_refresh_ixs!(ctx, deposit_reserve);
     lending_checks::deposit_obligation_collateral_checks(&DepositObligationCollateralAccounts {
         obligation: ctx.accounts.obligation.clone(),
@@ -59,7 +60,15 @@ pub fn process_deposit_obligation_collateral(
         collateral_amount,
     )?;
 
-    Ok(())
+    emit!(DepositObligationCollateralEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        reserve: ctx.accounts.deposit_reserve.key(),
+        obligation: ctx.accounts.obligation.key(),
+        owner: ctx.accounts.owner.key(),
+        collateral_amount,
+    });
+
+    Ok(collateral_amount)
 }
 
 #[derive(Accounts)]
@@ -3,24 +3,28 @@ use anchor_lang::{
     solana_program::sysvar::{instructions::Instructions as SysInstructions, SysvarId},
     Accounts,
 };
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::{
+    token::{self, Mint, Token, TokenAccount},
+    token_interface::{self, TokenInterface},
+};
 
 use crate::{
     errors::LendingError,
+    events::DepositReserveLiquidityEvent,
     gen_signer_seeds,
     lending_market::{
         deposit_reserve_liquidity, lending_checks,
         post_transfer_vault_balance_liquidity_reserve_checks, refresh_reserve,
     },
     state::{LendingAction, LendingMarket, Reserve},
-    utils::{seeds, token_transfer},
+    utils::{seeds, token_2022, token_transfer, NativeSolAccounts},
     DepositReserveLiquidityAccounts,
 };
 
 pub fn process_deposit_reserve_liquidity(
     ctx: Context<DepositReserveLiquidityCtx>,
     liquidity_amount: u64,
-) -> Result<()> {
+) -> Result<u64> {
     require!(liquidity_amount != 0, LendingError::InvalidAmount);
 
     lending_checks::deposit_reserve_liquidity_checks(&DepositReserveLiquidityAccounts {
@@ -32,14 +36,16 @@ pub fn process_deposit_reserve_liquidity(
         owner: ctx.accounts.owner.clone(),
         user_source_liquidity: ctx.accounts.user_source_liquidity.clone(),
         user_destination_collateral: ctx.accounts.user_destination_collateral.clone(),
-        token_program: ctx.accounts.token_program.clone(),
+        reserve_liquidity_mint: ctx.accounts.reserve_liquidity_mint.clone(),
+        collateral_token_program: ctx.accounts.collateral_token_program.clone(),
+        liquidity_token_program: ctx.accounts.liquidity_token_program.clone(),
     })?;
 
     let reserve = &mut ctx.accounts.reserve.load_mut()?;
     let lending_market = &ctx.accounts.lending_market.load()?;
     let clock = &Clock::get()?;
 
-    refresh_reserve(reserve, &clock, None)?;
+    refresh_reserve(reserve, clock, None)?;
 
     let lending_market_key = ctx.accounts.lending_market.key();
     let authority_signer_seeds =
@@ -48,7 +54,16 @@ pub fn process_deposit_reserve_liquidity(
     let initial_reserve_token_balance =
         token::accessor::amount(&ctx.accounts.reserve_liquidity_supply.to_account_info())?;
     let initial_reserve_available_liquidity = reserve.liquidity.available_amount;
-    let collateral_amount = deposit_reserve_liquidity(reserve, &clock, liquidity_amount)?;
+
+    // Only what reaches the vault after a Token-2022 transfer fee is credited to the reserve
+    let net_liquidity_amount = liquidity_amount
+        - token_2022::transfer_fee(
+            &ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            liquidity_amount,
+        )?;
+    require!(net_liquidity_amount != 0, LendingError::InvalidAmount);
+
+    let collateral_amount = deposit_reserve_liquidity(reserve, clock, net_liquidity_amount)?;
 
     msg!(
         "pnl: Depositing in reserve {:?} liquidity {}",
@@ -56,11 +71,31 @@ pub fn process_deposit_reserve_liquidity(
         liquidity_amount
     );
 
+    // Without a source token account the deposit is paid in lamports, wrapped into a temporary
+    // wSOL account for the duration of the instruction
+    let (user_source_liquidity, native_sol) = match &ctx.accounts.user_source_liquidity {
+        Some(user_source_liquidity) => (user_source_liquidity.to_account_info(), None),
+        None => {
+            let native_sol = NativeSolAccounts::new(
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.user_wsol.as_ref(),
+                ctx.accounts.reserve_liquidity_mint.to_account_info(),
+                ctx.accounts.liquidity_token_program.to_account_info(),
+                ctx.accounts.system_program.as_ref(),
+            )?;
+            native_sol.open(liquidity_amount)?;
+            (native_sol.user_wsol(), Some(native_sol))
+        }
+    };
+
     token_transfer::deposit_reserve_liquidity_transfer(
-        ctx.accounts.user_source_liquidity.to_account_info(),
+        user_source_liquidity,
         ctx.accounts.reserve_liquidity_supply.to_account_info(),
         ctx.accounts.owner.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.liquidity_token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
+        ctx.accounts.collateral_token_program.to_account_info(),
         ctx.accounts.reserve_collateral_mint.to_account_info(),
         ctx.accounts.user_destination_collateral.to_account_info(),
         ctx.accounts.lending_market_authority.clone(),
@@ -69,19 +104,32 @@ pub fn process_deposit_reserve_liquidity(
         collateral_amount,
     )?;
 
+    if let Some(native_sol) = &native_sol {
+        native_sol.close()?;
+    }
+
     post_transfer_vault_balance_liquidity_reserve_checks(
         token::accessor::amount(&ctx.accounts.reserve_liquidity_supply.to_account_info()).unwrap(),
         reserve.liquidity.available_amount,
         initial_reserve_token_balance,
         initial_reserve_available_liquidity,
-        LendingAction::Additive(liquidity_amount),
+        LendingAction::Additive(net_liquidity_amount),
     )?;
 
-    Ok(())
+    emit!(DepositReserveLiquidityEvent {
+        lending_market: lending_market_key,
+        reserve: ctx.accounts.reserve.key(),
+        owner: ctx.accounts.owner.key(),
+        liquidity_amount: net_liquidity_amount,
+        collateral_amount,
+    });
+
+    Ok(collateral_amount)
 }
 
 #[derive(Accounts)]
 pub struct DepositReserveLiquidityCtx<'info> {
+    #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(mut,
@@ -98,22 +146,32 @@ pub struct DepositReserveLiquidityCtx<'info> {
     )]
     pub lending_market_authority: AccountInfo<'info>,
 
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
     #[account(mut, address = reserve.load()?.liquidity.supply_vault)]
-    pub reserve_liquidity_supply: Box<Account<'info, TokenAccount>>,
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(mut, address = reserve.load()?.collateral.mint_pubkey)]
     pub reserve_collateral_mint: Box<Account<'info, Mint>>,
 
+    /// Omitted to deposit native SOL into a wrapped SOL reserve
     #[account(mut,
         token::mint = reserve_liquidity_supply.mint
     )]
-    pub user_source_liquidity: Box<Account<'info, TokenAccount>>,
+    pub user_source_liquidity: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    /// CHECK: temporary wSOL PDA of the owner, checked in NativeSolAccounts
+    #[account(mut)]
+    pub user_wsol: Option<UncheckedAccount<'info>>,
     #[account(mut,
         token::mint = reserve_collateral_mint.key()
     )]
     pub user_destination_collateral: Box<Account<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token>,
+    pub collateral_token_program: Program<'info, Token>,
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub liquidity_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Option<Program<'info, System>>,
 
     /// CHECK: instruction_sysvar account
     #[account(address = SysInstructions::id())]
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::LendingError,
+    events::DistributeFeesEvent,
+    gen_signer_seeds,
+    state::{FeeDistribution, LendingMarket, Reserve},
+    utils::{seeds, token_transfer},
+};
+
+/// Splits the reserve fee vault between the market's fee recipients. The remaining accounts
+/// are one token account of the liquidity mint per recipient, owned by that recipient and in
+/// the order of the fee distribution.
+pub fn process_distribute_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeFeesCtx<'info>>,
+) -> Result<()> {
+    let lending_market = ctx.accounts.lending_market.load()?;
+    let lending_market_key = ctx.accounts.lending_market.key();
+    let fee_distribution = ctx.accounts.fee_distribution.load()?;
+
+    if fee_distribution.recipients_count == 0 {
+        return err!(LendingError::FeeDistributionNotConfigured);
+    }
+    if ctx.remaining_accounts.len() != fee_distribution.recipients_count as usize {
+        msg!(
+            "Expected {} recipient token accounts, got {}",
+            fee_distribution.recipients_count,
+            ctx.remaining_accounts.len()
+        );
+        return err!(LendingError::InvalidAccountInput);
+    }
+
+    let total = ctx.accounts.fee_vault.amount;
+    let amounts = fee_distribution.split(total);
+    let authority_signer_seeds = gen_signer_seeds!(lending_market_key, lending_market.bump as u8);
+
+    let mut distributed: u64 = 0;
+    for ((recipient, amount), account) in fee_distribution
+        .recipients()
+        .iter()
+        .zip(amounts)
+        .zip(ctx.remaining_accounts)
+    {
+        let destination = InterfaceAccount::<TokenAccount>::try_from(account)?;
+        require_keys_eq!(
+            destination.owner,
+            recipient.recipient,
+            LendingError::InvalidAccountInput
+        );
+        require_keys_eq!(
+            destination.mint,
+            ctx.accounts.reserve_liquidity_mint.key(),
+            LendingError::InvalidAccountInput
+        );
+
+        if amount == 0 {
+            continue;
+        }
+
+        token_transfer::withdraw_fees_from_reserve(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.decimals,
+            ctx.accounts.fee_vault.to_account_info(),
+            account.clone(),
+            ctx.accounts.lending_market_authority.to_account_info(),
+            authority_signer_seeds,
+            amount,
+        )?;
+        distributed += amount;
+    }
+
+    msg!("Distributed fees: {} of {}", distributed, total);
+
+    ctx.accounts
+        .reserve
+        .load_mut()?
+        .liquidity
+        .fee_ledger
+        .record_withdrawn_fees(distributed);
+
+    emit!(DistributeFeesEvent {
+        lending_market: lending_market_key,
+        reserve: ctx.accounts.reserve.key(),
+        amount: distributed,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DistributeFeesCtx<'info> {
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    /// CHECK: market authority PDA
+    #[account(
+        seeds = [seeds::LENDING_MARKET_AUTH, lending_market.key().as_ref()],
+        bump = lending_market.load()?.bump as u8,
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(
+        seeds = [seeds::FEE_DISTRIBUTION, lending_market.key().as_ref()],
+        bump,
+    )]
+    pub fee_distribution: AccountLoader<'info, FeeDistribution>,
+
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut,
+        address = reserve.load()?.liquidity.fee_vault,
+        token::authority = lending_market_authority,
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
+}
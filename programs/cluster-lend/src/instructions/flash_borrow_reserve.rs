@@ -1,7 +1,11 @@
 use anchor_lang::{prelude::*, solana_program::sysvar, Accounts};
-use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::{
+    token,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
 use crate::{
+    events::FlashBorrowReserveEvent,
     gen_signer_seeds,
     lending_market::{flash_ixs, lending_checks, lending_operations},
     state::{LendingMarket, Reserve},
@@ -12,7 +16,7 @@ use crate::{
 pub fn process_flash_borrow_reserve(
     ctx: Context<FlashBorrowReserveCtx>,
     liquidity_amount: u64,
-) -> Result<()> {
+) -> Result<u64> {
     lending_checks::flash_borrow_reserve_liquidity_checks(&ctx)?;
     let reserve = &mut ctx.accounts.reserve.load_mut()?;
     let lending_market = &ctx.accounts.lending_market.load()?;
@@ -31,6 +35,8 @@ pub fn process_flash_borrow_reserve(
 
     token_transfer::borrow_obligation_liquidity_transfer(
         ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
         ctx.accounts.reserve_source_liquidity.to_account_info(),
         ctx.accounts.user_destination_liquidity.to_account_info(),
         ctx.accounts.lending_market_authority.to_account_info(),
@@ -46,7 +52,14 @@ pub fn process_flash_borrow_reserve(
         LendingAction::Subtractive(liquidity_amount),
     )?;
 
-    Ok(())
+    emit!(FlashBorrowReserveEvent {
+        lending_market: lending_market_key,
+        reserve: ctx.accounts.reserve.key(),
+        user_transfer_authority: ctx.accounts.user_transfer_authority.key(),
+        liquidity_amount,
+    });
+
+    Ok(liquidity_amount)
 }
 
 #[derive(Accounts)]
@@ -67,21 +80,25 @@ pub struct FlashBorrowReserveCtx<'info> {
     )]
     pub reserve: AccountLoader<'info, Reserve>,
 
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(mut,
         address = reserve.load()?.liquidity.supply_vault
     )]
-    pub reserve_source_liquidity: Box<Account<'info, TokenAccount>>,
+    pub reserve_source_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
-    pub user_destination_liquidity: Box<Account<'info, TokenAccount>>,
+    pub user_destination_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut,
         address = reserve.load()?.liquidity.fee_vault
     )]
-    pub reserve_liquidity_fee_receiver: Box<Account<'info, TokenAccount>>,
+    pub reserve_liquidity_fee_receiver: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: instruction_sysvar account
     #[account(address = sysvar::instructions::ID)]
     pub sysvar_info: AccountInfo<'info>,
-    pub token_program: Program<'info, Token>,
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
 }
@@ -1,18 +1,22 @@
 use anchor_lang::{prelude::*, solana_program::sysvar, Accounts};
-use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::{
+    token,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
 use crate::{
+    events::FlashRepayReserveEvent,
     lending_market::{flash_ixs, lending_checks, lending_operations},
     state::{LendingMarket, Reserve},
-    utils::{seeds, token_transfer},
-    LendingAction,
+    utils::{seeds, token_2022, token_transfer},
+    FlashRepayReserveLiquidityReturn, LendingAction,
 };
 
 pub fn process_flash_repay_reserve(
     ctx: Context<FlashRepayReserveCtx>,
     liquidity_amount: u64,
     borrow_instruction_index: u8,
-) -> Result<()> {
+) -> Result<FlashRepayReserveLiquidityReturn> {
     lending_checks::flash_repay_reserve_liquidity_checks(&ctx)?;
 
     let reserve = &mut ctx.accounts.reserve.load_mut()?;
@@ -32,15 +36,22 @@ pub fn process_flash_repay_reserve(
 
     token_transfer::repay_obligation_liquidity_transfer(
         ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
         ctx.accounts.user_source_liquidity.to_account_info(),
         ctx.accounts.reserve_destination_liquidity.to_account_info(),
         ctx.accounts.user_transfer_authority.to_account_info(),
-        flash_loan_amount,
+        token_2022::amount_with_transfer_fee(
+            &ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            flash_loan_amount,
+        )?,
     )?;
 
     if reserve_origination_fee > 0 {
         token_transfer::pay_borrowing_fees_transfer(
             ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.decimals,
             ctx.accounts.user_source_liquidity.to_account_info(),
             ctx.accounts
                 .reserve_liquidity_fee_receiver
@@ -59,7 +70,18 @@ pub fn process_flash_repay_reserve(
         LendingAction::Additive(flash_loan_amount),
     )?;
 
-    Ok(())
+    emit!(FlashRepayReserveEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        reserve: ctx.accounts.reserve.key(),
+        user_transfer_authority: ctx.accounts.user_transfer_authority.key(),
+        liquidity_amount: flash_loan_amount,
+        origination_fee: reserve_origination_fee,
+    });
+
+    Ok(FlashRepayReserveLiquidityReturn {
+        flash_loan_amount,
+        origination_fee: reserve_origination_fee,
+    })
 }
 
 #[derive(Accounts)]
@@ -80,21 +102,25 @@ pub struct FlashRepayReserveCtx<'info> {
     )]
     pub reserve: AccountLoader<'info, Reserve>,
 
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(mut,
         address = reserve.load()?.liquidity.supply_vault
     )]
-    pub reserve_destination_liquidity: Box<Account<'info, TokenAccount>>,
+    pub reserve_destination_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
-    pub user_source_liquidity: Box<Account<'info, TokenAccount>>,
+    pub user_source_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut,
         address = reserve.load()?.liquidity.fee_vault
     )]
-    pub reserve_liquidity_fee_receiver: Box<Account<'info, TokenAccount>>,
+    pub reserve_liquidity_fee_receiver: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: instruction_sysvar account
     #[account(address = sysvar::instructions::ID)]
     pub sysvar_info: AccountInfo<'info>,
-    pub token_program: Program<'info, Token>,
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
 }
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Reserve, ReserveFeeLedger};
+
+/// Read-only, returns the reserve's lifetime fee counters as return data.
+pub fn process_get_reserve_fee_ledger(
+    ctx: Context<GetReserveFeeLedgerCtx>,
+) -> Result<ReserveFeeLedger> {
+    Ok(ctx.accounts.reserve.load()?.liquidity.fee_ledger)
+}
+
+#[derive(Accounts)]
+pub struct GetReserveFeeLedgerCtx<'info> {
+    pub reserve: AccountLoader<'info, Reserve>,
+}
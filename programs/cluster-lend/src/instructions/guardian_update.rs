@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::LendingError,
+    events::UpdateMarketConfigEvent,
+    instructions::apply_reserve_config_update,
+    lending_market::lending_operations,
+    state::{LendingMarket, LendingMarketUpdate, ReserveRegistry},
+    Reserve, ReserveConfigUpdate,
+};
+
+pub fn process_guardian_update_market(
+    ctx: Context<GuardianUpdateMarketCtx>,
+    update: LendingMarketUpdate,
+) -> Result<()> {
+    let market = &mut ctx.accounts.lending_market.load_mut()?;
+
+    if !update.is_tightening(market) {
+        msg!("Guardian cannot apply {:?}", update);
+        return err!(LendingError::GuardianUpdateNotAllowed);
+    }
+
+    lending_operations::update_lending_market_config(market, &update)?;
+
+    emit!(UpdateMarketConfigEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        update,
+    });
+
+    Ok(())
+}
+
+pub fn process_guardian_update_reserve(
+    ctx: Context<GuardianUpdateReserveCtx>,
+    update: ReserveConfigUpdate,
+) -> Result<()> {
+    if !update.is_tightening(&ctx.accounts.reserve.load()?.config) {
+        msg!("Guardian cannot apply {:?}", update);
+        return err!(LendingError::GuardianUpdateNotAllowed);
+    }
+
+    apply_reserve_config_update(
+        &ctx.accounts.lending_market,
+        &ctx.accounts.reserve,
+        &ctx.accounts.reserve_registry,
+        update,
+    )
+}
+
+#[derive(Accounts)]
+pub struct GuardianUpdateMarketCtx<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(mut,
+        has_one = guardian
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+}
+
+#[derive(Accounts)]
+pub struct GuardianUpdateReserveCtx<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        has_one = guardian
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    /// Only omitted on markets without a reserve registry.
+    #[account(mut,
+        address = lending_market.load()?.reserve_registry
+    )]
+    pub reserve_registry: Option<AccountLoader<'info, ReserveRegistry>>,
+}
@@ -1,17 +1,28 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::{
+    token::{Mint, Token, TokenAccount},
+    token_interface::{self, TokenInterface},
+};
 
 use crate::{
+    constants::initial_deposit_amount,
     errors::LendingError,
+    gen_signer_seeds,
+    instructions::registry_to_update,
     state::{
         InitReserveParams, LendingMarket, NewReserveCollateralParams, NewReserveLiquidityParams,
-        Reserve, ReserveCollateral, ReserveConfig, ReserveLiquidity, ReserveStatus,
+        Reserve, ReserveCollateral, ReserveConfig, ReserveLiquidity, ReserveRegistry,
+        ReserveStatus,
     },
-    utils::seeds,
+    utils::{seeds, token_2022, token_transfer, validate_liquidity_mint_extensions},
 };
 
 pub fn process_initialize_reserve(ctx: Context<InitializeReserveCtx>) -> Result<()> {
+    validate_liquidity_mint_extensions(&ctx.accounts.reserve_liquidity_mint.to_account_info())?;
+
     let clock = &Clock::get()?;
+    let lending_market = ctx.accounts.lending_market.load()?;
+    let lending_market_key = ctx.accounts.lending_market.key();
     let reserve = &mut ctx.accounts.reserve.load_init()?;
 
     reserve.init(InitReserveParams {
@@ -20,6 +31,7 @@ pub fn process_initialize_reserve(ctx: Context<InitializeReserveCtx>) -> Result<
         liquidity: Box::new(ReserveLiquidity::new(NewReserveLiquidityParams {
             mint_pubkey: ctx.accounts.reserve_liquidity_mint.key(),
             mint_decimals: ctx.accounts.reserve_liquidity_mint.decimals,
+            token_program: ctx.accounts.liquidity_token_program.key(),
             supply_vault: ctx.accounts.reserve_liquidity_supply.key(),
             fee_vault: ctx.accounts.fee_receiver.key(),
             market_price_sf: 0,
@@ -34,6 +46,53 @@ pub fn process_initialize_reserve(ctx: Context<InitializeReserveCtx>) -> Result<
         }),
     });
 
+    // Like regular deposits, only what reaches the vault after a Token-2022 transfer fee is
+    // credited to the reserve
+    let seed_amount = initial_deposit_amount(ctx.accounts.reserve_liquidity_mint.decimals);
+    let net_seed_amount = seed_amount
+        - token_2022::transfer_fee(
+            &ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            seed_amount,
+        )?;
+    require!(net_seed_amount != 0, LendingError::InvalidAmount);
+
+    let collateral_amount = reserve.deposit_liquidity(net_seed_amount)?;
+
+    if let Some(reserve_registry) =
+        registry_to_update(&lending_market, &ctx.accounts.reserve_registry)?
+    {
+        reserve_registry.load_mut()?.add_reserve(
+            ctx.accounts.reserve.key(),
+            ctx.accounts.reserve_liquidity_mint.key(),
+            ReserveStatus::Hidden,
+            clock.slot,
+        )?;
+    }
+
+    msg!(
+        "Seeding reserve with {} liquidity, locking {} collateral",
+        net_seed_amount,
+        collateral_amount
+    );
+
+    let authority_signer_seeds = gen_signer_seeds!(lending_market_key, lending_market.bump as u8);
+
+    token_transfer::deposit_reserve_liquidity_transfer(
+        ctx.accounts.initial_liquidity_source.to_account_info(),
+        ctx.accounts.reserve_liquidity_supply.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.liquidity_token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.reserve_collateral_mint.to_account_info(),
+        ctx.accounts.reserve_locked_collateral.to_account_info(),
+        ctx.accounts.lending_market_authority.to_account_info(),
+        authority_signer_seeds,
+        seed_amount,
+        collateral_amount,
+    )?;
+
     Ok(())
 }
 
@@ -61,32 +120,44 @@ pub struct InitializeReserveCtx<'info> {
     )]
     pub reserve: AccountLoader<'info, Reserve>,
 
-    pub reserve_liquidity_mint: Box<Account<'info, Mint>>,
+    /// Only omitted on markets without a reserve registry.
+    #[account(mut,
+        address = lending_market.load()?.reserve_registry
+    )]
+    pub reserve_registry: Option<AccountLoader<'info, ReserveRegistry>>,
+
+    #[account(
+        mint::token_program = liquidity_token_program,
+    )]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     #[account(init,
         seeds = [seeds::RESERVE_LIQ_SUPPLY, lending_market.key().as_ref(), reserve_liquidity_mint.key().as_ref()],
         bump,
         payer = owner,
         token::mint = reserve_liquidity_mint,
-        token::authority = lending_market_authority
+        token::authority = lending_market_authority,
+        token::token_program = liquidity_token_program,
     )]
-    pub reserve_liquidity_supply: Box<Account<'info, TokenAccount>>,
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(init,
         seeds = [seeds::FEE_RECEIVER, lending_market.key().as_ref(), reserve_liquidity_mint.key().as_ref()],
         bump,
         payer = owner,
         token::mint = reserve_liquidity_mint,
-        token::authority = lending_market_authority
+        token::authority = lending_market_authority,
+        token::token_program = liquidity_token_program,
     )]
-    pub fee_receiver: Box<Account<'info, TokenAccount>>,
+    pub fee_receiver: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(init,
         seeds = [seeds::RESERVE_COLL_MINT, lending_market.key().as_ref(), reserve_liquidity_mint.key().as_ref()],
         bump,
         payer = owner,
         mint::decimals = 6,
-        mint::authority = lending_market_authority
+        mint::authority = lending_market_authority,
+        mint::token_program = collateral_token_program,
     )]
     pub reserve_collateral_mint: Box<Account<'info, Mint>>,
 
@@ -95,11 +166,31 @@ pub struct InitializeReserveCtx<'info> {
         bump,
         payer = owner,
         token::mint = reserve_collateral_mint,
-        token::authority = lending_market_authority
+        token::authority = lending_market_authority,
+        token::token_program = collateral_token_program,
     )]
     pub reserve_collateral_supply: Box<Account<'info, TokenAccount>>,
 
+    /// Holds the cTokens minted for the seed deposit. Only the market authority can move them
+    /// and no instruction does until the reserve is closed.
+    #[account(init,
+        seeds = [seeds::RESERVE_LOCKED_COLL, lending_market.key().as_ref(), reserve_liquidity_mint.key().as_ref()],
+        bump,
+        payer = owner,
+        token::mint = reserve_collateral_mint,
+        token::authority = lending_market_authority,
+        token::token_program = collateral_token_program,
+    )]
+    pub reserve_locked_collateral: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut,
+        token::mint = reserve_liquidity_mint,
+        token::authority = owner,
+    )]
+    pub initial_liquidity_source: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
     pub rent: Sysvar<'info, Rent>,
-    pub token_program: Program<'info, Token>,
+    pub collateral_token_program: Program<'info, Token>,
+    pub liquidity_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::LendingError,
+    state::{LendingMarket, Reserve, ReserveRegistry},
+    utils::{seeds, FatAccountLoader},
+};
+
+/// Creates the market's reserve registry. Reserves that already exist are passed in the
+/// remaining accounts and registered in order; their creation slot is unknown and recorded
+/// as zero. From then on every instruction that lists or changes the status of a reserve
+/// requires the registry.
+pub fn process_initialize_reserve_registry(
+    ctx: Context<InitializeReserveRegistryCtx>,
+) -> Result<()> {
+    let lending_market_key = ctx.accounts.lending_market.key();
+    ctx.accounts.lending_market.load_mut()?.reserve_registry = ctx.accounts.reserve_registry.key();
+    let registry = &mut ctx.accounts.reserve_registry.load_init()?;
+    registry.init(lending_market_key);
+
+    for account in ctx.remaining_accounts.iter() {
+        let reserve = FatAccountLoader::<Reserve>::try_from(account)?;
+        let reserve = reserve.load()?;
+        require_keys_eq!(
+            reserve.lending_market,
+            lending_market_key,
+            LendingError::InvalidAccountInput
+        );
+
+        registry.add_reserve(
+            account.key(),
+            reserve.liquidity.mint_pubkey,
+            reserve.config.status(),
+            0,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The registry to keep up to date, `None` on markets that never created one.
+pub(crate) fn registry_to_update<'a, 'info>(
+    lending_market: &LendingMarket,
+    reserve_registry: &'a Option<AccountLoader<'info, ReserveRegistry>>,
+) -> Result<Option<&'a AccountLoader<'info, ReserveRegistry>>> {
+    match reserve_registry {
+        Some(reserve_registry) => Ok(Some(reserve_registry)),
+        None if lending_market.has_reserve_registry() => {
+            err!(LendingError::ReserveRegistryMissing)
+        }
+        None => Ok(None),
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeReserveRegistryCtx<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut,
+        has_one = owner @ LendingError::InvalidMarketOwner,
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(init,
+        seeds = [seeds::RESERVE_REGISTRY, lending_market.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + std::mem::size_of::<ReserveRegistry>()
+    )]
+    pub reserve_registry: AccountLoader<'info, ReserveRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -2,14 +2,20 @@ use anchor_lang::{
     prelude::*,
     solana_program::sysvar::{instructions::Instructions as SysInstructions, SysvarId},
 };
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::{
+    token::{self, Mint, Token, TokenAccount},
+    token_interface::{self, TokenInterface},
+};
 
 use crate::{
-    check_refresh_ixs, gen_signer_seeds,
+    check_refresh_ixs,
+    errors::LendingError,
+    events::LiquidateObligationEvent,
+    gen_signer_seeds,
     lending_market::{lending_checks, lending_operations},
-    utils::{seeds, token_transfer},
-    xmsg, LendingAction, LendingMarket, LiquidateAndRedeemResult, Obligation,
-    RedeemReserveCollateralAccounts, Reserve,
+    utils::{seeds, spltoken, token_2022, token_transfer},
+    xmsg, LendingAction, LendingMarket, LiquidateAndRedeemResult, LiquidateObligationReturn,
+    LiquidateWithoutRedeemResult, Obligation, RedeemReserveCollateralAccounts, Reserve,
 };
 
 pub fn process_liquidate_obligation(
@@ -17,7 +23,7 @@ pub fn process_liquidate_obligation(
     liquidity_amount: u64,
     min_acceptable_received_collateral_amount: u64,
     max_allowed_ltv_override_percent: u64,
-) -> Result<()> {
+) -> Result<LiquidateObligationReturn> {
     xmsg!(
         "LiquidateObligationAndRedeemReserveCollateral amount {} max_allowed_ltv_override_percent {}",
         liquidity_amount,
@@ -29,14 +35,16 @@ pub fn process_liquidate_obligation(
     lending_checks::liquidate_obligation_checks(&ctx)?;
     lending_checks::redeem_reserve_collateral_checks(&RedeemReserveCollateralAccounts {
         user_source_collateral: ctx.accounts.user_destination_collateral.clone(),
-        user_destination_liquidity: ctx.accounts.user_destination_liquidity.clone(),
+        user_destination_liquidity: Some(ctx.accounts.user_destination_liquidity.clone()),
         reserve: ctx.accounts.withdraw_reserve.clone(),
         reserve_collateral_mint: ctx.accounts.withdraw_reserve_collateral_mint.clone(),
+        reserve_liquidity_mint: ctx.accounts.withdraw_reserve_liquidity_mint.clone(),
         reserve_liquidity_supply: ctx.accounts.withdraw_reserve_liquidity_supply.clone(),
         lending_market: ctx.accounts.lending_market.clone(),
         lending_market_authority: ctx.accounts.lending_market_authority.clone(),
         owner: ctx.accounts.liquidator.clone(),
-        token_program: ctx.accounts.token_program.clone(),
+        collateral_token_program: ctx.accounts.collateral_token_program.clone(),
+        liquidity_token_program: ctx.accounts.withdraw_liquidity_token_program.clone(),
     })?;
 
     let lending_market = &ctx.accounts.lending_market.load()?;
@@ -77,13 +85,7 @@ pub fn process_liquidate_obligation(
 
     let authority_signer_seeds = gen_signer_seeds!(lending_market_key, lending_market.bump as u8);
 
-    let LiquidateAndRedeemResult {
-        repay_amount,
-        withdraw_collateral_amount,
-        withdraw_amount,
-        total_withdraw_liquidity_amount,
-        ..
-    } = lending_operations::liquidate_and_redeem(
+    let liquidation_result = lending_operations::liquidate_and_redeem(
         lending_market,
         &ctx.accounts.repay_reserve,
         &ctx.accounts.withdraw_reserve,
@@ -93,19 +95,31 @@ pub fn process_liquidate_obligation(
         min_acceptable_received_collateral_amount,
         max_allowed_ltv_override_pct_opt,
     )?;
+    let LiquidateAndRedeemResult {
+        repay_amount,
+        withdraw_collateral_amount,
+        withdraw_amount,
+        total_withdraw_liquidity_amount,
+        ..
+    } = liquidation_result;
 
     token_transfer::repay_obligation_liquidity_transfer(
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.repay_liquidity_token_program.to_account_info(),
+        ctx.accounts.repay_reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.repay_reserve_liquidity_mint.decimals,
         ctx.accounts.user_source_liquidity.to_account_info(),
         ctx.accounts
             .repay_reserve_liquidity_supply
             .to_account_info(),
         ctx.accounts.liquidator.to_account_info(),
-        repay_amount,
+        token_2022::amount_with_transfer_fee(
+            &ctx.accounts.repay_reserve_liquidity_mint.to_account_info(),
+            repay_amount,
+        )?,
     )?;
 
     token_transfer::withdraw_obligation_collateral_transfer(
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.collateral_token_program.to_account_info(),
         ctx.accounts.user_destination_collateral.to_account_info(),
         ctx.accounts
             .withdraw_reserve_collateral_supply
@@ -117,12 +131,19 @@ pub fn process_liquidate_obligation(
 
     if let Some((withdraw_liquidity_amount, protocol_fee)) = total_withdraw_liquidity_amount {
         token_transfer::redeem_reserve_collateral_transfer(
-            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
             ctx.accounts
                 .withdraw_reserve_collateral_mint
                 .to_account_info(),
             ctx.accounts.user_destination_collateral.to_account_info(),
             ctx.accounts.liquidator.to_account_info(),
+            ctx.accounts
+                .withdraw_liquidity_token_program
+                .to_account_info(),
+            ctx.accounts
+                .withdraw_reserve_liquidity_mint
+                .to_account_info(),
+            ctx.accounts.withdraw_reserve_liquidity_mint.decimals,
             ctx.accounts
                 .withdraw_reserve_liquidity_supply
                 .to_account_info(),
@@ -133,20 +154,54 @@ pub fn process_liquidate_obligation(
             withdraw_liquidity_amount,
         )?;
 
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                anchor_spl::token::Transfer {
-                    from: ctx.accounts.user_destination_liquidity.to_account_info(),
-                    to: ctx
-                        .accounts
-                        .withdraw_reserve_liquidity_fee_receiver
-                        .to_account_info(),
-                    authority: ctx.accounts.liquidator.to_account_info(),
-                },
-            ),
-            protocol_fee,
+        let insurance_fee = ctx
+            .accounts
+            .withdraw_reserve
+            .load()?
+            .insurance_fee(protocol_fee);
+
+        token_transfer::pay_borrowing_fees_transfer(
+            ctx.accounts
+                .withdraw_liquidity_token_program
+                .to_account_info(),
+            ctx.accounts
+                .withdraw_reserve_liquidity_mint
+                .to_account_info(),
+            ctx.accounts.withdraw_reserve_liquidity_mint.decimals,
+            ctx.accounts.user_destination_liquidity.to_account_info(),
+            ctx.accounts
+                .withdraw_reserve_liquidity_fee_receiver
+                .to_account_info(),
+            ctx.accounts.liquidator.to_account_info(),
+            protocol_fee - insurance_fee,
         )?;
+
+        if insurance_fee > 0 {
+            let insurance_vault = ctx
+                .accounts
+                .withdraw_reserve_insurance_vault
+                .as_ref()
+                .ok_or_else(|| error!(LendingError::InsuranceVaultMissing))?;
+            token_transfer::pay_borrowing_fees_transfer(
+                ctx.accounts
+                    .withdraw_liquidity_token_program
+                    .to_account_info(),
+                ctx.accounts
+                    .withdraw_reserve_liquidity_mint
+                    .to_account_info(),
+                ctx.accounts.withdraw_reserve_liquidity_mint.decimals,
+                ctx.accounts.user_destination_liquidity.to_account_info(),
+                insurance_vault.to_account_info(),
+                ctx.accounts.liquidator.to_account_info(),
+                insurance_fee,
+            )?;
+            ctx.accounts
+                .withdraw_reserve
+                .load_mut()?
+                .liquidity
+                .fee_ledger
+                .record_insurance_fees(insurance_fee);
+        }
         let withdraw_reserve = &ctx.accounts.withdraw_reserve.load()?;
 
         let net_withdrawal_amount = if ctx
@@ -206,7 +261,230 @@ pub fn process_liquidate_obligation(
         )?;
     }
 
-    Ok(())
+    let liquidation_return = LiquidateObligationReturn::from(&liquidation_result);
+
+    emit!(LiquidateObligationEvent {
+        lending_market: lending_market_key,
+        obligation: ctx.accounts.obligation.key(),
+        liquidator: ctx.accounts.liquidator.key(),
+        repay_reserve: ctx.accounts.repay_reserve.key(),
+        withdraw_reserve: ctx.accounts.withdraw_reserve.key(),
+        repay_amount,
+        withdraw_collateral_amount,
+        withdraw_liquidity_amount: liquidation_return.withdraw_liquidity_amount,
+        liquidation_bonus_rate_sf: liquidation_return.liquidation_bonus_rate_sf,
+        protocol_fee: liquidation_return.protocol_fee,
+    });
+
+    Ok(liquidation_return)
+}
+
+/// Liquidates without redeeming the seized collateral, so a fully utilized withdraw reserve does
+/// not block the liquidation. The liquidator receives the withdraw reserve cTokens and the
+/// protocol fee is burnt from them. Only its insurance share is paid out in liquidity.
+pub fn process_liquidate_obligation_no_redeem(
+    ctx: Context<LiquidateObligationNoRedeemCtx>,
+    liquidity_amount: u64,
+    min_acceptable_received_collateral_amount: u64,
+    max_allowed_ltv_override_percent: u64,
+) -> Result<LiquidateObligationReturn> {
+    xmsg!(
+        "LiquidateObligationNoRedeem amount {} max_allowed_ltv_override_percent {}",
+        liquidity_amount,
+        max_allowed_ltv_override_percent
+    );
+
+    check_refresh_ixs!(ctx, withdraw_reserve, repay_reserve);
+
+    lending_checks::liquidate_obligation_no_redeem_checks(&ctx)?;
+
+    let lending_market = &ctx.accounts.lending_market.load()?;
+    let obligation = &mut ctx.accounts.obligation.load_mut()?;
+    let lending_market_key = ctx.accounts.lending_market.key();
+    let clock = &Clock::get()?;
+
+    let max_allowed_ltv_override_pct_opt = if ctx.accounts.liquidator.key() == obligation.owner
+        && max_allowed_ltv_override_percent > 0
+    {
+        if cfg!(feature = "staging") {
+            Some(max_allowed_ltv_override_percent)
+        } else {
+            msg!("Warning! Attempting to set an ltv override outside the staging program");
+            None
+        }
+    } else {
+        None
+    };
+
+    let initial_withdraw_reserve_token_balance = token::accessor::amount(
+        &ctx.accounts
+            .withdraw_reserve_liquidity_supply
+            .to_account_info(),
+    )?;
+
+    let initial_repay_reserve_token_balance = token::accessor::amount(
+        &ctx.accounts
+            .repay_reserve_liquidity_supply
+            .to_account_info(),
+    )?;
+
+    let (initial_repay_reserve_available_amount, initial_withdraw_reserve_available_amount) =
+        lending_checks::initial_liquidation_reserve_liquidity_available_amount(
+            &ctx.accounts.repay_reserve,
+            &ctx.accounts.withdraw_reserve,
+        );
+
+    let authority_signer_seeds = gen_signer_seeds!(lending_market_key, lending_market.bump as u8);
+
+    let liquidation_result = lending_operations::liquidate_without_redeem(
+        lending_market,
+        &ctx.accounts.repay_reserve,
+        &ctx.accounts.withdraw_reserve,
+        obligation,
+        clock,
+        liquidity_amount,
+        min_acceptable_received_collateral_amount,
+        max_allowed_ltv_override_pct_opt,
+    )?;
+    let LiquidateWithoutRedeemResult {
+        repay_amount,
+        withdraw_amount,
+        protocol_fee_collateral_amount,
+        insurance_fee,
+        ..
+    } = liquidation_result;
+
+    token_transfer::repay_obligation_liquidity_transfer(
+        ctx.accounts.repay_liquidity_token_program.to_account_info(),
+        ctx.accounts.repay_reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.repay_reserve_liquidity_mint.decimals,
+        ctx.accounts.user_source_liquidity.to_account_info(),
+        ctx.accounts
+            .repay_reserve_liquidity_supply
+            .to_account_info(),
+        ctx.accounts.liquidator.to_account_info(),
+        token_2022::amount_with_transfer_fee(
+            &ctx.accounts.repay_reserve_liquidity_mint.to_account_info(),
+            repay_amount,
+        )?,
+    )?;
+
+    token_transfer::withdraw_obligation_collateral_transfer(
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.user_destination_collateral.to_account_info(),
+        ctx.accounts
+            .withdraw_reserve_collateral_supply
+            .to_account_info(),
+        ctx.accounts.lending_market_authority.to_account_info(),
+        authority_signer_seeds,
+        withdraw_amount,
+    )?;
+
+    if protocol_fee_collateral_amount > 0 {
+        spltoken::burn(
+            ctx.accounts
+                .withdraw_reserve_collateral_mint
+                .to_account_info(),
+            ctx.accounts.user_destination_collateral.to_account_info(),
+            ctx.accounts.liquidator.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            protocol_fee_collateral_amount,
+        )?;
+    }
+
+    if insurance_fee > 0 {
+        let insurance_vault = ctx
+            .accounts
+            .withdraw_reserve_insurance_vault
+            .as_ref()
+            .ok_or_else(|| error!(LendingError::InsuranceVaultMissing))?;
+        token_transfer::withdraw_fees_from_reserve(
+            ctx.accounts
+                .withdraw_liquidity_token_program
+                .to_account_info(),
+            ctx.accounts
+                .withdraw_reserve_liquidity_mint
+                .to_account_info(),
+            ctx.accounts.withdraw_reserve_liquidity_mint.decimals,
+            ctx.accounts
+                .withdraw_reserve_liquidity_supply
+                .to_account_info(),
+            insurance_vault.to_account_info(),
+            ctx.accounts.lending_market_authority.to_account_info(),
+            authority_signer_seeds,
+            insurance_fee,
+        )?;
+        // The insurance vault only receives the share net of the mint's transfer fee
+        let net_insurance_fee = insurance_fee
+            - token_2022::transfer_fee(
+                &ctx.accounts
+                    .withdraw_reserve_liquidity_mint
+                    .to_account_info(),
+                insurance_fee,
+            )?;
+        ctx.accounts
+            .withdraw_reserve
+            .load_mut()?
+            .liquidity
+            .fee_ledger
+            .record_insurance_fees(net_insurance_fee);
+    }
+
+    let same_supply_vault = ctx.accounts.withdraw_reserve_liquidity_supply.key()
+        == ctx.accounts.repay_reserve_liquidity_supply.key();
+
+    if !same_supply_vault {
+        let withdraw_reserve = &ctx.accounts.withdraw_reserve.load()?;
+        lending_checks::post_transfer_vault_balance_liquidity_reserve_checks(
+            token::accessor::amount(
+                &ctx.accounts
+                    .withdraw_reserve_liquidity_supply
+                    .to_account_info(),
+            )
+            .unwrap(),
+            withdraw_reserve.liquidity.available_amount,
+            initial_withdraw_reserve_token_balance,
+            initial_withdraw_reserve_available_amount,
+            LendingAction::Subtractive(insurance_fee),
+        )?;
+    }
+
+    let repay_reserve = &ctx.accounts.repay_reserve.load()?;
+    // With a single reserve the insurance fee leaves the vault the repayment went to
+    let repay_reserve_action = match (same_supply_vault, repay_amount.checked_sub(insurance_fee)) {
+        (false, _) => LendingAction::Additive(repay_amount),
+        (true, Some(net_repay_amount)) => LendingAction::Additive(net_repay_amount),
+        (true, None) => LendingAction::Subtractive(insurance_fee - repay_amount),
+    };
+    lending_checks::post_transfer_vault_balance_liquidity_reserve_checks(
+        token::accessor::amount(
+            &ctx.accounts
+                .repay_reserve_liquidity_supply
+                .to_account_info(),
+        )
+        .unwrap(),
+        repay_reserve.liquidity.available_amount,
+        initial_repay_reserve_token_balance,
+        initial_repay_reserve_available_amount,
+        repay_reserve_action,
+    )?;
+
+    let liquidation_return = LiquidateObligationReturn::from(&liquidation_result);
+
+    emit!(LiquidateObligationEvent {
+        lending_market: lending_market_key,
+        obligation: ctx.accounts.obligation.key(),
+        liquidator: ctx.accounts.liquidator.key(),
+        repay_reserve: ctx.accounts.repay_reserve.key(),
+        withdraw_reserve: ctx.accounts.withdraw_reserve.key(),
+        repay_amount,
+        withdraw_collateral_amount: liquidation_return.withdraw_collateral_amount,
+        withdraw_liquidity_amount: liquidation_return.withdraw_liquidity_amount,
+        liquidation_bonus_rate_sf: liquidation_return.liquidation_bonus_rate_sf,
+        protocol_fee: liquidation_return.protocol_fee,
+    });
+
+    Ok(liquidation_return)
 }
 
 #[derive(Accounts)]
@@ -232,10 +510,13 @@ pub struct LiquidateObligationCtx<'info> {
     )]
     pub repay_reserve: AccountLoader<'info, Reserve>,
 
+    #[account(address = repay_reserve.load()?.liquidity.mint_pubkey)]
+    pub repay_reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
     #[account(mut,
         address = repay_reserve.load()?.liquidity.supply_vault
     )]
-    pub repay_reserve_liquidity_supply: Box<Account<'info, TokenAccount>>,
+    pub repay_reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(mut,
         has_one = lending_market
@@ -249,23 +530,113 @@ pub struct LiquidateObligationCtx<'info> {
         address = withdraw_reserve.load()?.collateral.supply_vault
     )]
     pub withdraw_reserve_collateral_supply: Box<Account<'info, TokenAccount>>,
+    #[account(address = withdraw_reserve.load()?.liquidity.mint_pubkey)]
+    pub withdraw_reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
     #[account(mut,
         address = withdraw_reserve.load()?.liquidity.supply_vault
     )]
-    pub withdraw_reserve_liquidity_supply: Box<Account<'info, TokenAccount>>,
+    pub withdraw_reserve_liquidity_supply:
+        Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     #[account(mut,
         address = withdraw_reserve.load()?.liquidity.fee_vault
     )]
-    pub withdraw_reserve_liquidity_fee_receiver: Box<Account<'info, TokenAccount>>,
+    pub withdraw_reserve_liquidity_fee_receiver:
+        Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    /// Required once the withdraw reserve has an insurance vault and a non-zero
+    /// `insurance_fee_pct`.
+    #[account(mut,
+        address = withdraw_reserve.load()?.liquidity.insurance_vault
+    )]
+    pub withdraw_reserve_insurance_vault:
+        Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
 
     #[account(mut)]
-    pub user_source_liquidity: Box<Account<'info, TokenAccount>>,
+    pub user_source_liquidity: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     #[account(mut)]
     pub user_destination_collateral: Box<Account<'info, TokenAccount>>,
     #[account(mut)]
-    pub user_destination_liquidity: Box<Account<'info, TokenAccount>>,
+    pub user_destination_liquidity: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    pub collateral_token_program: Program<'info, Token>,
+    #[account(address = repay_reserve.load()?.liquidity.token_program())]
+    pub repay_liquidity_token_program: Interface<'info, TokenInterface>,
+    #[account(address = withdraw_reserve.load()?.liquidity.token_program())]
+    pub withdraw_liquidity_token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: instruction_sysvar account
+    #[account(address = SysInstructions::id())]
+    pub instruction_sysvar_account: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LiquidateObligationNoRedeemCtx<'info> {
+    pub liquidator: Signer<'info>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub obligation: AccountLoader<'info, Obligation>,
+
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    /// CHECK: market authority PDA
+    #[account(
+        seeds = [seeds::LENDING_MARKET_AUTH, lending_market.key().as_ref()],
+        bump = lending_market.load()?.bump as u8,
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub repay_reserve: AccountLoader<'info, Reserve>,
+
+    #[account(address = repay_reserve.load()?.liquidity.mint_pubkey)]
+    pub repay_reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    #[account(mut,
+        address = repay_reserve.load()?.liquidity.supply_vault
+    )]
+    pub repay_reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub withdraw_reserve: AccountLoader<'info, Reserve>,
+    #[account(mut,
+        address = withdraw_reserve.load()?.collateral.mint_pubkey
+    )]
+    pub withdraw_reserve_collateral_mint: Box<Account<'info, Mint>>,
+    #[account(mut,
+        address = withdraw_reserve.load()?.collateral.supply_vault
+    )]
+    pub withdraw_reserve_collateral_supply: Box<Account<'info, TokenAccount>>,
+    /// Source of the insurance share of the protocol fee.
+    #[account(address = withdraw_reserve.load()?.liquidity.mint_pubkey)]
+    pub withdraw_reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(mut,
+        address = withdraw_reserve.load()?.liquidity.supply_vault
+    )]
+    pub withdraw_reserve_liquidity_supply:
+        Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    /// Required once the withdraw reserve has an insurance vault and a non-zero
+    /// `insurance_fee_pct`.
+    #[account(mut,
+        address = withdraw_reserve.load()?.liquidity.insurance_vault
+    )]
+    pub withdraw_reserve_insurance_vault:
+        Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    #[account(mut)]
+    pub user_source_liquidity: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+    #[account(mut)]
+    pub user_destination_collateral: Box<Account<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token>,
+    pub collateral_token_program: Program<'info, Token>,
+    #[account(address = repay_reserve.load()?.liquidity.token_program())]
+    pub repay_liquidity_token_program: Interface<'info, TokenInterface>,
+    #[account(address = withdraw_reserve.load()?.liquidity.token_program())]
+    pub withdraw_liquidity_token_program: Interface<'info, TokenInterface>,
 
     /// CHECK: instruction_sysvar account
     #[account(address = SysInstructions::id())]
@@ -1,39 +1,63 @@
+mod audit_reserve;
 mod borrow_obligation_liquidity;
+mod close_reserve;
 mod deposit_obligation_collateral;
 mod deposit_reserve_liquidity;
+mod distribute_fees;
 mod flash_borrow_reserve;
 mod flash_repay_reserve;
+mod get_reserve_fee_ledger;
+mod guardian_update;
 mod initialize_market;
 mod initialize_obligation;
 mod initialize_reserve;
+mod initialize_reserve_registry;
 mod liquidate_obligation;
+mod queue_config_update;
 mod redeem_fees;
 mod redeem_reserve_collateral;
 mod refresh_obligation;
 mod refresh_reserve;
+mod refresh_reserves_batch;
 mod repay_obligation_liquidity;
+mod reserve_insurance;
+mod set_fee_distribution;
+mod skim_reserve_excess;
+mod socialize_loss;
+mod transfer_market_role;
 mod update_market;
-mod update_market_owner;
 mod update_reserve;
 mod withdraw_obligation_collateral;
 mod withdraw_protocol_fees;
 
+pub use audit_reserve::*;
 pub use borrow_obligation_liquidity::*;
+pub use close_reserve::*;
 pub use deposit_obligation_collateral::*;
 pub use deposit_reserve_liquidity::*;
+pub use distribute_fees::*;
 pub use flash_borrow_reserve::*;
 pub use flash_repay_reserve::*;
+pub use get_reserve_fee_ledger::*;
+pub use guardian_update::*;
 pub use initialize_market::*;
 pub use initialize_obligation::*;
 pub use initialize_reserve::*;
+pub use initialize_reserve_registry::*;
 pub use liquidate_obligation::*;
+pub use queue_config_update::*;
 pub use redeem_fees::*;
 pub use redeem_reserve_collateral::*;
 pub use refresh_obligation::*;
 pub use refresh_reserve::*;
+pub use refresh_reserves_batch::*;
 pub use repay_obligation_liquidity::*;
+pub use reserve_insurance::*;
+pub use set_fee_distribution::*;
+pub use skim_reserve_excess::*;
+pub use socialize_loss::*;
+pub use transfer_market_role::*;
 pub use update_market::*;
-pub use update_market_owner::*;
 pub use update_reserve::*;
 pub use withdraw_obligation_collateral::*;
 pub use withdraw_protocol_fees::*;
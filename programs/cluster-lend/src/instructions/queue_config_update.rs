@@ -0,0 +1,250 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::LendingError,
+    events::{CancelQueuedUpdateEvent, QueueConfigUpdateEvent, UpdateMarketConfigEvent},
+    instructions::apply_reserve_config_update,
+    lending_market::lending_operations,
+    state::{LendingMarket, LendingMarketUpdate, QueuedUpdate, QueuedUpdateKind, ReserveRegistry},
+    Reserve, ReserveConfigUpdate,
+};
+
+pub fn process_queue_market_update(
+    ctx: Context<QueueMarketUpdateCtx>,
+    update: LendingMarketUpdate,
+) -> Result<()> {
+    let timelock_secs = ctx.accounts.lending_market.load()?.config_timelock_secs;
+    queue_update(
+        &mut ctx.accounts.queued_update,
+        ctx.accounts.lending_market.key(),
+        ctx.accounts.owner.key(),
+        timelock_secs,
+        QueuedUpdateKind::Market(update),
+    )
+}
+
+pub fn process_queue_reserve_update(
+    ctx: Context<QueueReserveUpdateCtx>,
+    update: ReserveConfigUpdate,
+) -> Result<()> {
+    let timelock_secs = ctx.accounts.lending_market.load()?.config_timelock_secs;
+    queue_update(
+        &mut ctx.accounts.queued_update,
+        ctx.accounts.lending_market.key(),
+        ctx.accounts.owner.key(),
+        timelock_secs,
+        QueuedUpdateKind::Reserve {
+            reserve: ctx.accounts.reserve.key(),
+            update,
+        },
+    )
+}
+
+pub fn process_execute_market_update(ctx: Context<ExecuteMarketUpdateCtx>) -> Result<()> {
+    check_ready(&ctx.accounts.queued_update)?;
+
+    let update = match &ctx.accounts.queued_update.update {
+        QueuedUpdateKind::Market(update) => *update,
+        QueuedUpdateKind::Reserve { .. } | QueuedUpdateKind::InsuranceWithdrawal { .. } => {
+            return err!(LendingError::QueuedUpdateMismatch)
+        }
+    };
+
+    let market = &mut ctx.accounts.lending_market.load_mut()?;
+    lending_operations::update_lending_market_config(market, &update)?;
+
+    emit!(UpdateMarketConfigEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        update,
+    });
+
+    Ok(())
+}
+
+pub fn process_execute_reserve_update(ctx: Context<ExecuteReserveUpdateCtx>) -> Result<()> {
+    check_ready(&ctx.accounts.queued_update)?;
+
+    let (reserve, update) = match &ctx.accounts.queued_update.update {
+        QueuedUpdateKind::Reserve { reserve, update } => (*reserve, update.clone()),
+        QueuedUpdateKind::Market(_) | QueuedUpdateKind::InsuranceWithdrawal { .. } => {
+            return err!(LendingError::QueuedUpdateMismatch)
+        }
+    };
+    require_keys_eq!(
+        reserve,
+        ctx.accounts.reserve.key(),
+        LendingError::QueuedUpdateMismatch
+    );
+
+    apply_reserve_config_update(
+        &ctx.accounts.lending_market,
+        &ctx.accounts.reserve,
+        &ctx.accounts.reserve_registry,
+        update,
+    )
+}
+
+pub fn process_cancel_queued_update(ctx: Context<CancelQueuedUpdateCtx>) -> Result<()> {
+    emit!(CancelQueuedUpdateEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        queued_update: ctx.accounts.queued_update.key(),
+    });
+
+    Ok(())
+}
+
+pub(crate) fn queue_update(
+    queued_update: &mut Account<QueuedUpdate>,
+    lending_market: Pubkey,
+    payer: Pubkey,
+    timelock_secs: u64,
+    update: QueuedUpdateKind,
+) -> Result<()> {
+    let earliest_execution_ts = Clock::get()?
+        .unix_timestamp
+        .checked_add(timelock_secs as i64)
+        .ok_or(LendingError::MathOverflow)?;
+
+    msg!(
+        "Queueing {:?}, executable from {}",
+        update,
+        earliest_execution_ts
+    );
+
+    queued_update.set_inner(QueuedUpdate {
+        lending_market,
+        payer,
+        earliest_execution_ts,
+        update,
+    });
+
+    emit!(QueueConfigUpdateEvent {
+        lending_market,
+        queued_update: queued_update.key(),
+        earliest_execution_ts,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn check_ready(queued_update: &QueuedUpdate) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if now < queued_update.earliest_execution_ts {
+        msg!(
+            "Queued update executable from {}, now is {}",
+            queued_update.earliest_execution_ts,
+            now
+        );
+        return err!(LendingError::QueuedUpdateNotReady);
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct QueueMarketUpdateCtx<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(init,
+        payer = owner,
+        space = QueuedUpdate::SPACE,
+    )]
+    pub queued_update: Account<'info, QueuedUpdate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueReserveUpdateCtx<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(init,
+        payer = owner,
+        space = QueuedUpdate::SPACE,
+    )]
+    pub queued_update: Account<'info, QueuedUpdate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMarketUpdateCtx<'info> {
+    /// CHECK: rent receiver recorded in the queued update
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(mut,
+        has_one = lending_market,
+        has_one = payer,
+        close = payer,
+    )]
+    pub queued_update: Account<'info, QueuedUpdate>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteReserveUpdateCtx<'info> {
+    /// CHECK: rent receiver recorded in the queued update
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    /// Only omitted on markets without a reserve registry.
+    #[account(mut,
+        address = lending_market.load()?.reserve_registry
+    )]
+    pub reserve_registry: Option<AccountLoader<'info, ReserveRegistry>>,
+
+    #[account(mut,
+        has_one = lending_market,
+        has_one = payer,
+        close = payer,
+    )]
+    pub queued_update: Account<'info, QueuedUpdate>,
+}
+
+#[derive(Accounts)]
+pub struct CancelQueuedUpdateCtx<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    /// CHECK: rent receiver recorded in the queued update
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+
+    #[account(mut,
+        has_one = lending_market,
+        has_one = payer,
+        close = payer,
+    )]
+    pub queued_update: Account<'info, QueuedUpdate>,
+}
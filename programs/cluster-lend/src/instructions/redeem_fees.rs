@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
+    errors::LendingError,
+    events::RedeemFeesEvent,
     gen_signer_seeds,
     lending_market::lending_operations,
-    utils::{seeds, token_transfer},
+    utils::{seeds, token_2022, token_transfer},
     LendingMarket, Reserve,
 };
 
@@ -18,20 +20,60 @@ pub fn process_redeem_fees(ctx: Context<RedeemFeesCtx>) -> Result<()> {
     let authority_signer_seeds = gen_signer_seeds!(lending_market_key, lending_market.bump as u8);
 
     let withdraw_amount = lending_operations::redeem_fees(reserve, clock.slot)?;
+    let insurance_amount = reserve.insurance_fee(withdraw_amount);
 
-    msg!("Redeeming fees: {}", withdraw_amount);
+    msg!(
+        "Redeeming fees: {}, to insurance: {}",
+        withdraw_amount,
+        insurance_amount
+    );
 
     token_transfer::withdraw_fees_from_reserve(
         ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
         ctx.accounts.reserve_supply_liquidity.to_account_info(),
         ctx.accounts
             .reserve_liquidity_fee_receiver
             .to_account_info(),
         ctx.accounts.lending_market_authority.to_account_info(),
         authority_signer_seeds,
-        withdraw_amount,
+        withdraw_amount - insurance_amount,
     )?;
 
+    if insurance_amount > 0 {
+        let insurance_vault = ctx
+            .accounts
+            .reserve_insurance_vault
+            .as_ref()
+            .ok_or_else(|| error!(LendingError::InsuranceVaultMissing))?;
+        token_transfer::withdraw_fees_from_reserve(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.decimals,
+            ctx.accounts.reserve_supply_liquidity.to_account_info(),
+            insurance_vault.to_account_info(),
+            ctx.accounts.lending_market_authority.to_account_info(),
+            authority_signer_seeds,
+            insurance_amount,
+        )?;
+        let net_insurance_amount = insurance_amount
+            - token_2022::transfer_fee(
+                &ctx.accounts.reserve_liquidity_mint.to_account_info(),
+                insurance_amount,
+            )?;
+        reserve
+            .liquidity
+            .fee_ledger
+            .record_insurance_fees(net_insurance_amount);
+    }
+
+    emit!(RedeemFeesEvent {
+        lending_market: lending_market_key,
+        reserve: ctx.accounts.reserve.key(),
+        amount: withdraw_amount,
+    });
+
     Ok(())
 }
 
@@ -41,11 +83,18 @@ pub struct RedeemFeesCtx<'info> {
         has_one = lending_market)]
     pub reserve: AccountLoader<'info, Reserve>,
 
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(mut, address = reserve.load()?.liquidity.fee_vault)]
-    pub reserve_liquidity_fee_receiver: Box<Account<'info, TokenAccount>>,
+    pub reserve_liquidity_fee_receiver: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, address = reserve.load()?.liquidity.supply_vault)]
-    pub reserve_supply_liquidity: Box<Account<'info, TokenAccount>>,
+    pub reserve_supply_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Required once the reserve has an insurance vault and a non-zero `insurance_fee_pct`.
+    #[account(mut, address = reserve.load()?.liquidity.insurance_vault)]
+    pub reserve_insurance_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     pub lending_market: AccountLoader<'info, LendingMarket>,
 
@@ -56,5 +105,6 @@ pub struct RedeemFeesCtx<'info> {
     )]
     pub lending_market_authority: AccountInfo<'info>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
 }
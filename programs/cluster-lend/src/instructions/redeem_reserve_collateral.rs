@@ -3,32 +3,38 @@ use anchor_lang::{
     solana_program::sysvar::{instructions::Instructions as SysInstructions, SysvarId},
     Accounts,
 };
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::{
+    token::{self, Mint, Token, TokenAccount},
+    token_interface::{self, TokenInterface},
+};
 
 use crate::{
+    events::RedeemReserveCollateralEvent,
     gen_signer_seeds,
     lending_market::{
         post_transfer_vault_balance_liquidity_reserve_checks, redeem_reserve_collateral,
         redeem_reserve_collateral_checks, refresh_reserve,
     },
     state::{LendingAction, LendingMarket, RedeemReserveCollateralAccounts, Reserve},
-    utils::{seeds, token_transfer},
+    utils::{seeds, token_transfer, NativeSolAccounts},
 };
 
 pub fn process_redeem_reserve_collateral(
     ctx: Context<RedeemReserveCollateralCtx>,
     collateral_amount: u64,
-) -> Result<()> {
+) -> Result<u64> {
     redeem_reserve_collateral_checks(&RedeemReserveCollateralAccounts {
         user_source_collateral: ctx.accounts.user_source_collateral.clone(),
         user_destination_liquidity: ctx.accounts.user_destination_liquidity.clone(),
         reserve: ctx.accounts.reserve.clone(),
         reserve_collateral_mint: ctx.accounts.reserve_collateral_mint.clone(),
+        reserve_liquidity_mint: ctx.accounts.reserve_liquidity_mint.clone(),
         reserve_liquidity_supply: ctx.accounts.reserve_liquidity_supply.clone(),
         lending_market: ctx.accounts.lending_market.clone(),
         lending_market_authority: ctx.accounts.lending_market_authority.clone(),
         owner: ctx.accounts.owner.clone(),
-        token_program: ctx.accounts.token_program.clone(),
+        collateral_token_program: ctx.accounts.collateral_token_program.clone(),
+        liquidity_token_program: ctx.accounts.liquidity_token_program.clone(),
     })?;
 
     let reserve = &mut ctx.accounts.reserve.load_mut()?;
@@ -52,19 +58,42 @@ pub fn process_redeem_reserve_collateral(
         withdraw_liquidity_amount
     );
 
+    // Without a destination token account the liquidity is unwrapped and paid out in lamports
+    let (user_destination_liquidity, native_sol) = match &ctx.accounts.user_destination_liquidity {
+        Some(user_destination_liquidity) => (user_destination_liquidity.to_account_info(), None),
+        None => {
+            let native_sol = NativeSolAccounts::new(
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.user_wsol.as_ref(),
+                ctx.accounts.reserve_liquidity_mint.to_account_info(),
+                ctx.accounts.liquidity_token_program.to_account_info(),
+                ctx.accounts.system_program.as_ref(),
+            )?;
+            native_sol.open(0)?;
+            (native_sol.user_wsol(), Some(native_sol))
+        }
+    };
+
     token_transfer::redeem_reserve_collateral_transfer(
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.collateral_token_program.to_account_info(),
         ctx.accounts.reserve_collateral_mint.to_account_info(),
         ctx.accounts.user_source_collateral.to_account_info(),
         ctx.accounts.owner.to_account_info(),
+        ctx.accounts.liquidity_token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
         ctx.accounts.reserve_liquidity_supply.to_account_info(),
-        ctx.accounts.user_destination_liquidity.to_account_info(),
+        user_destination_liquidity,
         ctx.accounts.lending_market_authority.clone(),
         authority_signer_seeds,
         collateral_amount,
         withdraw_liquidity_amount,
     )?;
 
+    if let Some(native_sol) = &native_sol {
+        native_sol.close()?;
+    }
+
     post_transfer_vault_balance_liquidity_reserve_checks(
         token::accessor::amount(&ctx.accounts.reserve_liquidity_supply.to_account_info()).unwrap(),
         reserve.liquidity.available_amount,
@@ -73,11 +102,20 @@ pub fn process_redeem_reserve_collateral(
         LendingAction::Subtractive(withdraw_liquidity_amount),
     )?;
 
-    Ok(())
+    emit!(RedeemReserveCollateralEvent {
+        lending_market: lending_market_key,
+        reserve: ctx.accounts.reserve.key(),
+        owner: ctx.accounts.owner.key(),
+        collateral_amount,
+        liquidity_amount: withdraw_liquidity_amount,
+    });
+
+    Ok(withdraw_liquidity_amount)
 }
 
 #[derive(Accounts)]
 pub struct RedeemReserveCollateralCtx<'info> {
+    #[account(mut)]
     pub owner: Signer<'info>,
 
     pub lending_market: AccountLoader<'info, LendingMarket>,
@@ -98,21 +136,31 @@ pub struct RedeemReserveCollateralCtx<'info> {
         address = reserve.load()?.collateral.mint_pubkey
     )]
     pub reserve_collateral_mint: Box<Account<'info, Mint>>,
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
     #[account(mut,
         address = reserve.load()?.liquidity.supply_vault
     )]
-    pub reserve_liquidity_supply: Box<Account<'info, TokenAccount>>,
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(mut,
         token::mint = reserve_collateral_mint
     )]
     pub user_source_collateral: Box<Account<'info, TokenAccount>>,
+    /// Omitted to receive native SOL from a wrapped SOL reserve
     #[account(mut,
         token::mint = reserve.load()?.liquidity.mint_pubkey
     )]
-    pub user_destination_liquidity: Box<Account<'info, TokenAccount>>,
-
-    pub token_program: Program<'info, Token>,
+    pub user_destination_liquidity:
+        Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+    /// CHECK: temporary wSOL PDA of the owner, checked in NativeSolAccounts
+    #[account(mut)]
+    pub user_wsol: Option<UncheckedAccount<'info>>,
+
+    pub collateral_token_program: Program<'info, Token>,
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub liquidity_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Option<Program<'info, System>>,
 
     /// CHECK: instruction_sysvar account
     #[account(address = SysInstructions::id())]
@@ -15,6 +15,15 @@ pub fn process_refresh_reserve(ctx: Context<RefreshReserveCtx>) -> Result<()> {
 
     constraints::check_remaining_accounts(&ctx)?;
 
+    refresh_reserve_with_oracle(reserve, lending_market, &ctx.accounts.pyth_oracle, clock)
+}
+
+pub(crate) fn refresh_reserve_with_oracle(
+    reserve: &mut Reserve,
+    lending_market: &LendingMarket,
+    pyth_oracle: &Option<AccountInfo>,
+    clock: &Clock,
+) -> Result<()> {
     require!(
         reserve.version == PROGRAM_VERSION as u64,
         LendingError::ReserveDeprecated
@@ -28,11 +37,11 @@ pub fn process_refresh_reserve(ctx: Context<RefreshReserveCtx>) -> Result<()> {
         reserve
             .config
             .token_info
-            .validate_token_info_config(&ctx.accounts.pyth_oracle)?;
+            .validate_token_info_config(pyth_oracle)?;
 
         get_price(
             &reserve.config.token_info,
-            ctx.accounts.pyth_oracle.as_ref(),
+            pyth_oracle.as_ref(),
             clock.unix_timestamp,
         )?
     } else {
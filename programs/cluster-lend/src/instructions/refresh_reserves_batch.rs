@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use super::refresh_reserve_with_oracle;
+use crate::{
+    errors::LendingError,
+    state::{LendingMarket, Reserve},
+    utils::FatAccountLoader,
+};
+
+/// Refreshes every `(reserve, pyth_oracle)` pair passed in the remaining accounts. A missing
+/// oracle is marked by passing the program id in its slot, as for optional Anchor accounts.
+pub fn process_refresh_reserves_batch(ctx: Context<RefreshReservesBatchCtx>) -> Result<()> {
+    let clock = &Clock::get()?;
+    let lending_market = &ctx.accounts.lending_market.load()?;
+
+    require!(
+        !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len() % 2 == 0,
+        LendingError::InvalidAccountInput
+    );
+
+    for accounts in ctx.remaining_accounts.chunks_exact(2) {
+        let reserve_loader = FatAccountLoader::<Reserve>::try_from(&accounts[0])?;
+        let reserve = &mut reserve_loader.load_mut()?;
+        require_keys_eq!(
+            reserve.lending_market,
+            ctx.accounts.lending_market.key(),
+            LendingError::InvalidAccountInput
+        );
+
+        let pyth_oracle = if accounts[1].key() == crate::ID {
+            None
+        } else {
+            Some(accounts[1].clone())
+        };
+
+        refresh_reserve_with_oracle(reserve, lending_market, &pyth_oracle, clock)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefreshReservesBatchCtx<'info> {
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+}
@@ -3,20 +3,24 @@ use anchor_lang::{
     solana_program::sysvar::{instructions::Instructions as SysInstructions, SysvarId},
     Accounts,
 };
-use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::{
+    token,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
 use crate::{
     check_refresh_ixs,
+    events::RepayObligationLiquidityEvent,
     lending_market::{lending_checks, lending_operations},
     state::{LendingAction, LendingMarket, Reserve},
-    utils::token_transfer,
+    utils::{token_2022, token_transfer, NativeSolAccounts},
     xmsg, Obligation,
 };
 
 pub fn process_repay_obligation_liquidity(
     ctx: Context<RepayObligationLiquidityCtx>,
     liquidity_amount: u64,
-) -> Result<()> {
+) -> Result<u64> {
     check_refresh_ixs!(ctx, repay_reserve);
     lending_checks::repay_obligation_liquidity_checks(&ctx)?;
 
@@ -45,14 +49,44 @@ pub fn process_repay_obligation_liquidity(
         liquidity_amount
     );
 
+    // The vault has to receive the full repay amount, so a Token-2022 transfer fee is paid on
+    // top of it by the user
+    let repay_amount_with_fee = token_2022::amount_with_transfer_fee(
+        &ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        repay_amount,
+    )?;
+
+    // Without a source token account the repayment is paid in lamports, wrapped into a
+    // temporary wSOL account for the duration of the instruction
+    let (user_source_liquidity, native_sol) = match &ctx.accounts.user_source_liquidity {
+        Some(user_source_liquidity) => (user_source_liquidity.to_account_info(), None),
+        None => {
+            let native_sol = NativeSolAccounts::new(
+                ctx.accounts.owner.to_account_info(),
+                ctx.accounts.user_wsol.as_ref(),
+                ctx.accounts.reserve_liquidity_mint.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.as_ref(),
+            )?;
+            native_sol.open(repay_amount_with_fee)?;
+            (native_sol.user_wsol(), Some(native_sol))
+        }
+    };
+
     token_transfer::repay_obligation_liquidity_transfer(
         ctx.accounts.token_program.to_account_info(),
-        ctx.accounts.user_source_liquidity.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
+        user_source_liquidity,
         ctx.accounts.reserve_destination_liquidity.to_account_info(),
         ctx.accounts.owner.to_account_info(),
-        repay_amount,
+        repay_amount_with_fee,
     )?;
 
+    if let Some(native_sol) = &native_sol {
+        native_sol.close()?;
+    }
+
     lending_checks::post_transfer_vault_balance_liquidity_reserve_checks(
         token::accessor::amount(&ctx.accounts.reserve_destination_liquidity.to_account_info())
             .unwrap(),
@@ -62,11 +96,20 @@ pub fn process_repay_obligation_liquidity(
         LendingAction::Additive(repay_amount),
     )?;
 
-    Ok(())
+    emit!(RepayObligationLiquidityEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        reserve: ctx.accounts.repay_reserve.key(),
+        obligation: ctx.accounts.obligation.key(),
+        owner: ctx.accounts.owner.key(),
+        repay_amount,
+    });
+
+    Ok(repay_amount)
 }
 
 #[derive(Accounts)]
 pub struct RepayObligationLiquidityCtx<'info> {
+    #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(mut,
@@ -82,17 +125,26 @@ pub struct RepayObligationLiquidityCtx<'info> {
     )]
     pub repay_reserve: AccountLoader<'info, Reserve>,
 
+    #[account(address = repay_reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(mut,
         address = repay_reserve.load()?.liquidity.supply_vault
     )]
-    pub reserve_destination_liquidity: Box<Account<'info, TokenAccount>>,
+    pub reserve_destination_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Omitted to repay native SOL into a wrapped SOL reserve
     #[account(mut,
         token::mint = repay_reserve.load()?.liquidity.mint_pubkey
     )]
-    pub user_source_liquidity: Box<Account<'info, TokenAccount>>,
-
-    pub token_program: Program<'info, Token>,
+    pub user_source_liquidity: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// CHECK: temporary wSOL PDA of the owner, checked in NativeSolAccounts
+    #[account(mut)]
+    pub user_wsol: Option<UncheckedAccount<'info>>,
+
+    #[account(address = repay_reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Option<Program<'info, System>>,
 
     /// CHECK: instruction_sysvar account
     #[account(address = SysInstructions::id())]
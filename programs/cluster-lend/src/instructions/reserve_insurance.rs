@@ -0,0 +1,323 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::LendingError,
+    events::{DepositReserveInsuranceEvent, WithdrawReserveInsuranceEvent},
+    gen_signer_seeds,
+    instructions::{check_ready, queue_update},
+    state::{LendingMarket, QueuedUpdate, QueuedUpdateKind, Reserve},
+    utils::{seeds, token_transfer},
+};
+
+/// Creates the reserve insurance vault. From then on `insurance_fee_pct` of the redeemed
+/// interest fees and of the liquidation fees is paid into it.
+pub fn process_initialize_reserve_insurance(
+    ctx: Context<InitializeReserveInsuranceCtx>,
+) -> Result<()> {
+    let reserve = &mut ctx.accounts.reserve.load_mut()?;
+    reserve.liquidity.insurance_vault = ctx.accounts.insurance_vault.key();
+
+    Ok(())
+}
+
+pub fn process_deposit_reserve_insurance(
+    ctx: Context<ReserveInsuranceCtx>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return err!(LendingError::InvalidAmount);
+    }
+
+    token_transfer::repay_obligation_liquidity_transfer(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
+        ctx.accounts.owner_token_account.to_account_info(),
+        ctx.accounts.insurance_vault.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        amount,
+    )?;
+
+    emit!(DepositReserveInsuranceEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        reserve: ctx.accounts.reserve.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Capped at the vault balance, like `withdraw_protocol_fees`. Once the market has a config
+/// timelock, withdrawals have to go through `queue_insurance_withdrawal` instead.
+pub fn process_withdraw_reserve_insurance(
+    ctx: Context<ReserveInsuranceCtx>,
+    amount: u64,
+) -> Result<()> {
+    if ctx.accounts.lending_market.load()?.has_config_timelock() {
+        msg!("Insurance withdrawals have to go through queue_insurance_withdrawal");
+        return err!(LendingError::UpdateRequiresTimelock);
+    }
+
+    withdraw_insurance(
+        &ctx.accounts.lending_market,
+        &ctx.accounts.lending_market_authority,
+        &ctx.accounts.reserve,
+        &ctx.accounts.reserve_liquidity_mint,
+        &ctx.accounts.insurance_vault,
+        &ctx.accounts.owner_token_account,
+        &ctx.accounts.token_program,
+        amount,
+    )
+}
+
+pub fn process_queue_insurance_withdrawal(
+    ctx: Context<QueueInsuranceWithdrawalCtx>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return err!(LendingError::InvalidAmount);
+    }
+
+    let timelock_secs = ctx.accounts.lending_market.load()?.config_timelock_secs;
+    queue_update(
+        &mut ctx.accounts.queued_update,
+        ctx.accounts.lending_market.key(),
+        ctx.accounts.owner.key(),
+        timelock_secs,
+        QueuedUpdateKind::InsuranceWithdrawal {
+            reserve: ctx.accounts.reserve.key(),
+            destination: ctx.accounts.destination_token_account.key(),
+            amount,
+        },
+    )
+}
+
+pub fn process_execute_insurance_withdrawal(
+    ctx: Context<ExecuteInsuranceWithdrawalCtx>,
+) -> Result<()> {
+    check_ready(&ctx.accounts.queued_update)?;
+
+    let (reserve, destination, amount) = match &ctx.accounts.queued_update.update {
+        QueuedUpdateKind::InsuranceWithdrawal {
+            reserve,
+            destination,
+            amount,
+        } => (*reserve, *destination, *amount),
+        QueuedUpdateKind::Market(_) | QueuedUpdateKind::Reserve { .. } => {
+            return err!(LendingError::QueuedUpdateMismatch)
+        }
+    };
+    require_keys_eq!(
+        reserve,
+        ctx.accounts.reserve.key(),
+        LendingError::QueuedUpdateMismatch
+    );
+    require_keys_eq!(
+        destination,
+        ctx.accounts.destination_token_account.key(),
+        LendingError::QueuedUpdateMismatch
+    );
+
+    withdraw_insurance(
+        &ctx.accounts.lending_market,
+        &ctx.accounts.lending_market_authority,
+        &ctx.accounts.reserve,
+        &ctx.accounts.reserve_liquidity_mint,
+        &ctx.accounts.insurance_vault,
+        &ctx.accounts.destination_token_account,
+        &ctx.accounts.token_program,
+        amount,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn withdraw_insurance<'info>(
+    lending_market: &AccountLoader<'info, LendingMarket>,
+    lending_market_authority: &AccountInfo<'info>,
+    reserve: &AccountLoader<'info, Reserve>,
+    reserve_liquidity_mint: &InterfaceAccount<'info, Mint>,
+    insurance_vault: &InterfaceAccount<'info, TokenAccount>,
+    destination_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let lending_market_key = lending_market.key();
+    let bump = lending_market.load()?.bump as u8;
+
+    let amount = amount.min(insurance_vault.amount);
+    let authority_signer_seeds = gen_signer_seeds!(lending_market_key, bump);
+
+    msg!("Withdrawing insurance: {}", amount);
+
+    token_transfer::withdraw_fees_from_reserve(
+        token_program.to_account_info(),
+        reserve_liquidity_mint.to_account_info(),
+        reserve_liquidity_mint.decimals,
+        insurance_vault.to_account_info(),
+        destination_token_account.to_account_info(),
+        lending_market_authority.clone(),
+        authority_signer_seeds,
+        amount,
+    )?;
+
+    emit!(WithdrawReserveInsuranceEvent {
+        lending_market: lending_market_key,
+        reserve: reserve.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeReserveInsuranceCtx<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ LendingError::InvalidMarketOwner,
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    /// CHECK: market authority PDA
+    #[account(
+        seeds = [seeds::LENDING_MARKET_AUTH, lending_market.key().as_ref()],
+        bump = lending_market.load()?.bump as u8,
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(init,
+        seeds = [seeds::RESERVE_INSURANCE, lending_market.key().as_ref(), reserve_liquidity_mint.key().as_ref()],
+        bump,
+        payer = owner,
+        token::mint = reserve_liquidity_mint,
+        token::authority = lending_market_authority,
+        token::token_program = token_program,
+    )]
+    pub insurance_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReserveInsuranceCtx<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ LendingError::InvalidMarketOwner,
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    /// CHECK: market authority PDA
+    #[account(
+        seeds = [seeds::LENDING_MARKET_AUTH, lending_market.key().as_ref()],
+        bump = lending_market.load()?.bump as u8,
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    #[account(
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut,
+        address = reserve.load()?.liquidity.insurance_vault,
+        token::authority = lending_market_authority,
+    )]
+    pub insurance_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Source of a top-up, destination of a withdrawal.
+    #[account(mut,
+        token::mint = reserve.load()?.liquidity.mint_pubkey,
+    )]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct QueueInsuranceWithdrawalCtx<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ LendingError::InvalidMarketOwner,
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(
+        token::mint = reserve.load()?.liquidity.mint_pubkey,
+    )]
+    pub destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init,
+        payer = owner,
+        space = QueuedUpdate::SPACE,
+    )]
+    pub queued_update: Account<'info, QueuedUpdate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteInsuranceWithdrawalCtx<'info> {
+    /// CHECK: rent receiver recorded in the queued update
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    /// CHECK: market authority PDA
+    #[account(
+        seeds = [seeds::LENDING_MARKET_AUTH, lending_market.key().as_ref()],
+        bump = lending_market.load()?.bump as u8,
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    #[account(
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut,
+        address = reserve.load()?.liquidity.insurance_vault,
+        token::authority = lending_market_authority,
+    )]
+    pub insurance_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut,
+        has_one = lending_market,
+        has_one = payer,
+        close = payer,
+    )]
+    pub queued_update: Account<'info, QueuedUpdate>,
+
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::LendingError,
+    events::UpdateFeeDistributionEvent,
+    state::{FeeDistribution, FeeShare, LendingMarket},
+    utils::seeds,
+};
+
+/// Creates the market's fee distribution with its first set of recipients.
+pub fn process_initialize_fee_distribution(
+    ctx: Context<InitializeFeeDistributionCtx>,
+    shares: Vec<FeeShare>,
+) -> Result<()> {
+    let lending_market_key = ctx.accounts.lending_market.key();
+    let fee_distribution = &mut ctx.accounts.fee_distribution.load_init()?;
+    fee_distribution.init(lending_market_key);
+    fee_distribution.set_recipients(&shares)?;
+
+    emit!(UpdateFeeDistributionEvent {
+        lending_market: lending_market_key,
+        shares,
+    });
+
+    Ok(())
+}
+
+/// Replaces every fee recipient. Not subject to the config timelock: the owner can already
+/// withdraw fees to any account at once.
+pub fn process_update_fee_distribution(
+    ctx: Context<UpdateFeeDistributionCtx>,
+    shares: Vec<FeeShare>,
+) -> Result<()> {
+    ctx.accounts
+        .fee_distribution
+        .load_mut()?
+        .set_recipients(&shares)?;
+
+    emit!(UpdateFeeDistributionEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        shares,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeeDistributionCtx<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ LendingError::InvalidMarketOwner,
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(init,
+        seeds = [seeds::FEE_DISTRIBUTION, lending_market.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + std::mem::size_of::<FeeDistribution>()
+    )]
+    pub fee_distribution: AccountLoader<'info, FeeDistribution>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeDistributionCtx<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ LendingError::InvalidMarketOwner,
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    #[account(mut,
+        seeds = [seeds::FEE_DISTRIBUTION, lending_market.key().as_ref()],
+        bump,
+    )]
+    pub fee_distribution: AccountLoader<'info, FeeDistribution>,
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{Token, TokenAccount},
+    token_interface::{self, TokenInterface},
+};
+
+use crate::{
+    errors::LendingError,
+    events::SkimReserveExcessEvent,
+    gen_signer_seeds,
+    state::{LendingMarket, Reserve},
+    utils::{seeds, token_transfer},
+};
+
+/// Moves tokens that were sent straight to the reserve vaults out of them.
+///
+/// Such tokens are outside `available_amount` and `obligation_collateral_amount`, so no
+/// depositor or obligation has a claim on them and they would otherwise stay locked forever.
+/// They belong to the market owner, who either keeps them as protocol revenue by passing the
+/// fee receiver as `liquidity_destination`, or refunds the sender.
+pub fn process_skim_reserve_excess(ctx: Context<SkimReserveExcessCtx>) -> Result<()> {
+    let lending_market = ctx.accounts.lending_market.load()?;
+    let lending_market_key = ctx.accounts.lending_market.key();
+    let reserve = ctx.accounts.reserve.load()?;
+
+    let (liquidity_amount, collateral_amount) = reserve.vault_excess(
+        ctx.accounts.reserve_liquidity_supply.amount,
+        ctx.accounts.reserve_collateral_supply.amount,
+    );
+
+    if liquidity_amount == 0 && collateral_amount == 0 {
+        msg!("Reserve vaults hold no unaccounted tokens");
+        return err!(LendingError::InvalidAmount);
+    }
+
+    msg!(
+        "Skimming liquidity: {}, collateral: {}",
+        liquidity_amount,
+        collateral_amount
+    );
+
+    let authority_signer_seeds = gen_signer_seeds!(lending_market_key, lending_market.bump as u8);
+
+    if liquidity_amount > 0 {
+        token_transfer::withdraw_fees_from_reserve(
+            ctx.accounts.liquidity_token_program.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.decimals,
+            ctx.accounts.reserve_liquidity_supply.to_account_info(),
+            ctx.accounts.liquidity_destination.to_account_info(),
+            ctx.accounts.lending_market_authority.to_account_info(),
+            authority_signer_seeds,
+            liquidity_amount,
+        )?;
+    }
+
+    if collateral_amount > 0 {
+        token_transfer::withdraw_obligation_collateral_transfer(
+            ctx.accounts.collateral_token_program.to_account_info(),
+            ctx.accounts.collateral_destination.to_account_info(),
+            ctx.accounts.reserve_collateral_supply.to_account_info(),
+            ctx.accounts.lending_market_authority.to_account_info(),
+            authority_signer_seeds,
+            collateral_amount,
+        )?;
+    }
+
+    emit!(SkimReserveExcessEvent {
+        lending_market: lending_market_key,
+        reserve: ctx.accounts.reserve.key(),
+        liquidity_amount,
+        collateral_amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SkimReserveExcessCtx<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ LendingError::InvalidMarketOwner,
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    /// CHECK: market authority PDA
+    #[account(
+        seeds = [seeds::LENDING_MARKET_AUTH, lending_market.key().as_ref()],
+        bump = lending_market.load()?.bump as u8,
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    #[account(
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    #[account(mut,
+        address = reserve.load()?.liquidity.supply_vault,
+    )]
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(mut,
+        address = reserve.load()?.collateral.supply_vault,
+    )]
+    pub reserve_collateral_supply: Box<Account<'info, TokenAccount>>,
+
+    /// The reserve fee receiver, or any other account of the liquidity mint.
+    #[account(mut,
+        token::mint = reserve.load()?.liquidity.mint_pubkey,
+    )]
+    pub liquidity_destination: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(mut,
+        token::mint = reserve.load()?.collateral.mint_pubkey,
+    )]
+    pub collateral_destination: Box<Account<'info, TokenAccount>>,
+
+    pub collateral_token_program: Program<'info, Token>,
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub liquidity_token_program: Interface<'info, TokenInterface>,
+}
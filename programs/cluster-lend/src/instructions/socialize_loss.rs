@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::LendingError,
+    events::SocializeLossEvent,
+    gen_signer_seeds,
+    lending_market::lending_operations,
+    state::{LendingMarket, Obligation, Reserve},
+    utils::{seeds, token_2022, token_transfer},
+    SocializeLossResult,
+};
+
+/// Writes off the remaining debt of an obligation whose collateral was fully liquidated. The
+/// reserve insurance vault repays as much of it as it holds, and only the rest is socialized
+/// to depositors.
+pub fn process_socialize_loss(ctx: Context<SocializeLossCtx>) -> Result<()> {
+    let clock = Clock::get()?;
+    let lending_market = ctx.accounts.lending_market.load()?;
+    let lending_market_key = ctx.accounts.lending_market.key();
+    let reserve = &mut ctx.accounts.reserve.load_mut()?;
+    let obligation = &mut ctx.accounts.obligation.load_mut()?;
+
+    let insurance_balance = match &ctx.accounts.reserve_insurance_vault {
+        Some(vault) => vault.amount,
+        None if reserve.liquidity.insurance_vault == Pubkey::default() => 0,
+        None => return err!(LendingError::InsuranceVaultMissing),
+    };
+    // What reaches the supply vault once the mint has withheld its transfer fee
+    let insurance_available = insurance_balance
+        - token_2022::transfer_fee(
+            &ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            insurance_balance,
+        )?;
+
+    let SocializeLossResult {
+        insurance_amount,
+        socialized_amount_f,
+    } = lending_operations::socialize_loss(
+        reserve,
+        obligation,
+        ctx.accounts.reserve.key(),
+        insurance_available,
+        clock.slot,
+    )?;
+
+    msg!(
+        "Bad debt repaid by insurance: {}, socialized: {}",
+        insurance_amount,
+        socialized_amount_f
+    );
+
+    if insurance_amount > 0 {
+        let insurance_vault = ctx
+            .accounts
+            .reserve_insurance_vault
+            .as_ref()
+            .ok_or_else(|| error!(LendingError::InsuranceVaultMissing))?;
+        let authority_signer_seeds =
+            gen_signer_seeds!(lending_market_key, lending_market.bump as u8);
+
+        // The repaid debt is credited in full, so the transfer covers the fee on top of it
+        let transfer_amount = if insurance_amount == insurance_available {
+            insurance_balance
+        } else {
+            token_2022::amount_with_transfer_fee(
+                &ctx.accounts.reserve_liquidity_mint.to_account_info(),
+                insurance_amount,
+            )?
+        };
+
+        token_transfer::withdraw_fees_from_reserve(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.to_account_info(),
+            ctx.accounts.reserve_liquidity_mint.decimals,
+            insurance_vault.to_account_info(),
+            ctx.accounts.reserve_liquidity_supply.to_account_info(),
+            ctx.accounts.lending_market_authority.to_account_info(),
+            authority_signer_seeds,
+            transfer_amount,
+        )?;
+    }
+
+    emit!(SocializeLossEvent {
+        lending_market: lending_market_key,
+        reserve: ctx.accounts.reserve.key(),
+        obligation: ctx.accounts.obligation.key(),
+        insurance_amount,
+        socialized_amount_sf: socialized_amount_f.to_bits(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SocializeLossCtx<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ LendingError::InvalidMarketOwner,
+    )]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+
+    /// CHECK: market authority PDA
+    #[account(
+        seeds = [seeds::LENDING_MARKET_AUTH, lending_market.key().as_ref()],
+        bump = lending_market.load()?.bump as u8,
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub obligation: AccountLoader<'info, Obligation>,
+
+    #[account(mut,
+        has_one = lending_market
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut,
+        address = reserve.load()?.liquidity.supply_vault
+    )]
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Only omitted for reserves without an insurance vault.
+    #[account(mut,
+        address = reserve.load()?.liquidity.insurance_vault
+    )]
+    pub reserve_insurance_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::{AcceptMarketRoleEvent, CancelMarketRoleEvent, ProposeMarketRoleEvent},
+    instructions::UpdateMarketCtx,
+    state::{LendingMarket, MarketRole},
+};
+
+pub fn process_propose_market_role(
+    ctx: Context<UpdateMarketCtx>,
+    role: MarketRole,
+    new_holder: Pubkey,
+) -> Result<()> {
+    let market = &mut ctx.accounts.lending_market.load_mut()?;
+    market.propose_role(role, new_holder)?;
+
+    emit!(ProposeMarketRoleEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        role,
+        pending_holder: new_holder,
+    });
+
+    Ok(())
+}
+
+pub fn process_accept_market_role(
+    ctx: Context<AcceptMarketRoleCtx>,
+    role: MarketRole,
+) -> Result<()> {
+    let market = &mut ctx.accounts.lending_market.load_mut()?;
+    let new_holder = ctx.accounts.pending_holder.key();
+    let previous_holder = market.accept_role(role, new_holder)?;
+
+    emit!(AcceptMarketRoleEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        role,
+        previous_holder,
+        new_holder,
+    });
+
+    Ok(())
+}
+
+pub fn process_cancel_market_role(ctx: Context<UpdateMarketCtx>, role: MarketRole) -> Result<()> {
+    let market = &mut ctx.accounts.lending_market.load_mut()?;
+    let cancelled_holder = market.cancel_role(role)?;
+
+    emit!(CancelMarketRoleEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        role,
+        cancelled_holder,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptMarketRoleCtx<'info> {
+    pub pending_holder: Signer<'info>,
+
+    #[account(mut)]
+    pub lending_market: AccountLoader<'info, LendingMarket>,
+}
@@ -3,8 +3,9 @@ use anchor_lang::prelude::*;
 use crate::{
     constants::VALUE_BYTE_MAX_ARRAY_LEN_MARKET_UPDATE,
     errors::LendingError,
-    state::{LendingMarket, UpdateLendingMarketMode},
-    utils::{validate_numerical_bool, Fraction},
+    events::UpdateMarketConfigEvent,
+    lending_market::lending_operations,
+    state::{LendingMarket, LendingMarketUpdate, UpdateLendingMarketMode},
 };
 
 pub fn process_update_market(
@@ -15,116 +16,35 @@ pub fn process_update_market(
     let mode = UpdateLendingMarketMode::try_from(mode)
         .map_err(|_| ProgramError::InvalidInstructionData)?;
 
-    let market = &mut ctx.accounts.lending_market.load_mut()?;
-
     msg!(
         "Updating lending market with mode {:?} and value {:?}",
         mode,
         &value[0..32]
     );
 
-    match mode {
-        UpdateLendingMarketMode::UpdateEmergencyMode => {
-            let emergency_mode = value[0];
-            msg!("Value is {:?}", emergency_mode);
-            if emergency_mode == 0 {
-                market.emergency_mode = 0
-            } else if emergency_mode == 1 {
-                market.emergency_mode = 1;
-            } else {
-                return err!(LendingError::InvalidFlag);
-            }
-        }
-        UpdateLendingMarketMode::UpdateLiquidationCloseFactor => {
-            let liquidation_close_factor = value[0];
-            msg!("Value is {:?}", liquidation_close_factor);
-            if !(5..=100).contains(&liquidation_close_factor) {
-                return err!(LendingError::InvalidFlag);
-            }
-            market.liquidation_max_debt_close_factor_pct = liquidation_close_factor;
-        }
-        UpdateLendingMarketMode::UpdateLiquidationMaxValue => {
-            let value = u64::from_le_bytes(value[..8].try_into().unwrap());
-            msg!("Value is {:?}", value);
-            if value == 0 {
-                return err!(LendingError::InvalidFlag);
-            }
-            market.max_liquidatable_debt_market_value_at_once = value;
-        }
-        UpdateLendingMarketMode::UpdateGlobalAllowedBorrow => {
-            let value = u64::from_le_bytes(value[..8].try_into().unwrap());
-            msg!("Value is {:?}", value);
-            market.global_allowed_borrow_value = value;
-        }
-        UpdateLendingMarketMode::UpdateGlobalUnhealthyBorrow => {
-            let value = u64::from_le_bytes(value[..8].try_into().unwrap());
-            msg!("Value is {:?}", value);
-            market.global_unhealthy_borrow_value = value;
-        }
-        UpdateLendingMarketMode::UpdateMinFullLiquidationThreshold => {
-            let value = u64::from_le_bytes(value[..8].try_into().unwrap());
-            msg!("Value is {:?}", value);
-            if value == 0 {
-                return err!(LendingError::InvalidFlag);
-            }
-            market.min_full_liquidation_value_threshold = value;
-        }
-        UpdateLendingMarketMode::UpdateInsolvencyRiskLtv => {
-            let insolvency_risk_ltv = value[0];
-            msg!("Value is {:?}", insolvency_risk_ltv);
+    let update = LendingMarketUpdate::from_mode_bytes(mode, &value)?;
+
+    process_update_market_config(ctx, update)
+}
+
+pub fn process_update_market_config(
+    ctx: Context<UpdateMarketCtx>,
+    update: LendingMarketUpdate,
+) -> Result<()> {
+    let market = &mut ctx.accounts.lending_market.load_mut()?;
 
-            if !(5..=100).contains(&insolvency_risk_ltv) {
-                return err!(LendingError::InvalidFlag);
-            }
-            market.insolvency_risk_unhealthy_ltv_pct = insolvency_risk_ltv;
-        }
-        UpdateLendingMarketMode::UpdatePriceRefreshTriggerToMaxAgePct => {
-            let value = value[0];
-            msg!("Value is {:?}", value);
-            if value > 100 {
-                msg!("Price refresh trigger to max age pct must be in range [0, 100]");
-                return err!(LendingError::InvalidConfig);
-            }
-            market.price_refresh_trigger_to_max_age_pct = value;
-        }
-        UpdateLendingMarketMode::UpdateAutodeleverageEnabled => {
-            let autodeleverage_enabled = value[0];
-            msg!("Prev Value is {:?}", market.autodeleverage_enabled);
-            msg!("New Value is {:?}", autodeleverage_enabled);
-            if autodeleverage_enabled == 0 {
-                market.autodeleverage_enabled = 0
-            } else if autodeleverage_enabled == 1 {
-                market.autodeleverage_enabled = 1;
-            } else {
-                msg!(
-                    "Autodeleverage enabled flag must be 0 or 1, got {:?}",
-                    autodeleverage_enabled
-                );
-                return err!(LendingError::InvalidFlag);
-            }
-        }
-        UpdateLendingMarketMode::UpdateBorrowingDisabled => {
-            let borrow_disabled = value[0];
-            msg!("Prev Value is {:?}", market.borrow_disabled);
-            msg!("New Value is {:?}", borrow_disabled);
-            validate_numerical_bool(borrow_disabled)?;
-            market.borrow_disabled = borrow_disabled;
-        }
-        UpdateLendingMarketMode::UpdateMinNetValueObligationPostAction => {
-            let min_net_value_in_obligation_sf =
-                u128::from_le_bytes(value[..16].try_into().unwrap());
-            msg!(
-                "Prev Value is {}",
-                Fraction::from_bits(market.min_net_value_in_obligation_sf)
-            );
-            msg!(
-                "New Value is {}",
-                Fraction::from_bits(min_net_value_in_obligation_sf)
-            );
-            market.min_net_value_in_obligation_sf = min_net_value_in_obligation_sf;
-        }
+    if market.has_config_timelock() && !update.is_timelock_exempt(market) {
+        msg!("{:?} has to go through queue_market_update", update);
+        return err!(LendingError::UpdateRequiresTimelock);
     }
 
+    lending_operations::update_lending_market_config(market, &update)?;
+
+    emit!(UpdateMarketConfigEvent {
+        lending_market: ctx.accounts.lending_market.key(),
+        update,
+    });
+
     Ok(())
 }
 
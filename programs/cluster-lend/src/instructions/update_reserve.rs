@@ -1,12 +1,12 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::VALUE_BYTE_MAX_ARRAY_LEN_MARKET_UPDATE,
     errors::LendingError,
+    events::UpdateReserveConfigEvent,
+    instructions::registry_to_update,
     lending_market::lending_operations,
-    state::{LendingMarket, UpdateLendingMarketMode},
-    utils::{validate_numerical_bool, Fraction},
-    Reserve, UpdateConfigMode,
+    state::{LendingMarket, ReserveRegistry},
+    Reserve, ReserveConfigUpdate, UpdateConfigMode,
 };
 
 pub fn process_update_reserve(
@@ -17,24 +17,76 @@ pub fn process_update_reserve(
     let mode =
         UpdateConfigMode::try_from(mode).map_err(|_| ProgramError::InvalidInstructionData)?;
 
-    let reserve = &mut ctx.accounts.reserve.load_mut()?;
-    let market = ctx.accounts.lending_market.load()?;
+    match ReserveConfigUpdate::from_mode_bytes(mode, value)? {
+        Some(update) => process_update_reserve_config(ctx, update),
+        None => {
+            msg!("ReferralFee moved to lending_market");
+            Ok(())
+        }
+    }
+}
+
+pub fn process_update_reserve_config(
+    ctx: Context<UpdateReserveCtx>,
+    update: ReserveConfigUpdate,
+) -> Result<()> {
+    if ctx.accounts.lending_market.load()?.has_config_timelock()
+        && !update.is_timelock_exempt(&ctx.accounts.reserve.load()?.config)
+    {
+        msg!("{:?} has to go through queue_reserve_update", update);
+        return err!(LendingError::UpdateRequiresTimelock);
+    }
+
+    apply_reserve_config_update(
+        &ctx.accounts.lending_market,
+        &ctx.accounts.reserve,
+        &ctx.accounts.reserve_registry,
+        update,
+    )
+}
+
+pub(crate) fn apply_reserve_config_update(
+    lending_market: &AccountLoader<LendingMarket>,
+    reserve_loader: &AccountLoader<Reserve>,
+    reserve_registry: &Option<AccountLoader<ReserveRegistry>>,
+    update: ReserveConfigUpdate,
+) -> Result<()> {
+    let reserve = &mut reserve_loader.load_mut()?;
     let name = reserve.config.token_info.symbol();
 
     msg!(
-        "Updating reserve {:?} {} config with mode {:?}",
-        ctx.accounts.reserve.key(),
+        "Updating reserve {:?} {} config with {:?}",
+        reserve_loader.key(),
         name,
-        mode,
+        update,
     );
 
     let clock = Clock::get()?;
     lending_operations::refresh_reserve(reserve, &clock, None)?;
 
-    lending_operations::update_reserve_config(reserve, mode, &value);
+    let previous_status = reserve.config.status();
+
+    lending_operations::update_reserve_config(reserve, &update)?;
 
     lending_operations::utils::validate_reserve_config(&reserve.config)?;
 
+    // Replacing the entire config can change the status as well
+    let status = reserve.config.status();
+    if status != previous_status {
+        let market = &lending_market.load()?;
+        if let Some(reserve_registry) = registry_to_update(market, reserve_registry)? {
+            reserve_registry
+                .load_mut()?
+                .set_status(&reserve_loader.key(), status)?;
+        }
+    }
+
+    emit!(UpdateReserveConfigEvent {
+        lending_market: lending_market.key(),
+        reserve: reserve_loader.key(),
+        update,
+    });
+
     Ok(())
 }
 
@@ -50,5 +102,11 @@ pub struct UpdateReserveCtx<'info> {
     #[account(mut,
         has_one = lending_market
     )]
-    reserve: AccountLoader<'info, Reserve>,
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    /// Only omitted on markets without a reserve registry.
+    #[account(mut,
+        address = lending_market.load()?.reserve_registry
+    )]
+    pub reserve_registry: Option<AccountLoader<'info, ReserveRegistry>>,
 }
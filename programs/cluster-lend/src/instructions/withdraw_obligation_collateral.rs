@@ -6,7 +6,9 @@ use anchor_lang::{
 use anchor_spl::token::{Token, TokenAccount};
 
 use crate::{
-    check_refresh_ixs, gen_signer_seeds,
+    check_refresh_ixs,
+    events::WithdrawObligationCollateralEvent,
+    gen_signer_seeds,
     lending_market::{lending_checks, lending_operations},
     state::{LendingMarket, Reserve},
     utils::{close_account_loader, seeds, token_transfer},
@@ -16,8 +18,8 @@ use crate::{
 pub fn process_withdraw_obligation_collateral(
     ctx: Context<WithdrawObligationCollateralCtx>,
     collateral_amount: u64,
-) -> Result<()> {
-    let close_obligation = {
+) -> Result<u64> {
+    let (close_obligation, withdraw_amount) = {
         check_refresh_ixs!(ctx, withdraw_reserve);
 
         lending_checks::withdraw_obligation_collateral_checks(
@@ -34,7 +36,7 @@ pub fn process_withdraw_obligation_collateral(
         )?;
         let clock = &Clock::get()?;
 
-        let withdraw_reserve = &mut ctx.accounts.withdraw_reserve.load()?;
+        let withdraw_reserve = &mut ctx.accounts.withdraw_reserve.load_mut()?;
         let obligation = &mut ctx.accounts.obligation.load_mut()?;
         let lending_market = &mut ctx.accounts.lending_market.load()?;
         let lending_market_key = ctx.accounts.lending_market.key();
@@ -62,7 +64,18 @@ pub fn process_withdraw_obligation_collateral(
 
         msg!("pnl: Withdraw obligation collateral {}", withdraw_amount);
 
-        obligation.deposits_empty() && obligation.borrows_empty()
+        emit!(WithdrawObligationCollateralEvent {
+            lending_market: lending_market_key,
+            reserve: ctx.accounts.withdraw_reserve.key(),
+            obligation: ctx.accounts.obligation.key(),
+            owner: ctx.accounts.owner.key(),
+            collateral_amount: withdraw_amount,
+        });
+
+        (
+            obligation.deposits_empty() && obligation.borrows_empty(),
+            withdraw_amount,
+        )
     };
 
     close_account_loader(
@@ -71,7 +84,7 @@ pub fn process_withdraw_obligation_collateral(
         &ctx.accounts.obligation,
     )?;
 
-    Ok(())
+    Ok(withdraw_amount)
 }
 
 #[derive(Accounts)]
@@ -92,7 +105,7 @@ pub struct WithdrawObligationCollateralCtx<'info> {
     )]
     pub lending_market_authority: AccountInfo<'info>,
 
-    #[account(
+    #[account(mut,
         has_one = lending_market
     )]
     pub withdraw_reserve: AccountLoader<'info, Reserve>,
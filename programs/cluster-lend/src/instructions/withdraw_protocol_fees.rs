@@ -1,7 +1,8 @@
 use anchor_lang::{prelude::*, Accounts};
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
+    events::WithdrawProtocolFeesEvent,
     gen_signer_seeds,
     state::{LendingMarket, Reserve},
     utils::{seeds, token_transfer},
@@ -22,6 +23,8 @@ pub fn process_withdraw_protocol_fees(
 
     token_transfer::withdraw_fees_from_reserve(
         ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.to_account_info(),
+        ctx.accounts.reserve_liquidity_mint.decimals,
         ctx.accounts.fee_vault.to_account_info(),
         ctx.accounts.lending_market_owner_ata.to_account_info(),
         ctx.accounts.lending_market_authority.to_account_info(),
@@ -29,6 +32,19 @@ pub fn process_withdraw_protocol_fees(
         amount,
     )?;
 
+    ctx.accounts
+        .reserve
+        .load_mut()?
+        .liquidity
+        .fee_ledger
+        .record_withdrawn_fees(amount);
+
+    emit!(WithdrawProtocolFeesEvent {
+        lending_market: lending_market_key,
+        reserve: ctx.accounts.reserve.key(),
+        amount,
+    });
+
     Ok(())
 }
 
@@ -39,7 +55,7 @@ pub struct WithdrawProtocolFeesCtx<'info> {
     #[account(has_one = owner)]
     pub lending_market: AccountLoader<'info, LendingMarket>,
 
-    #[account(
+    #[account(mut,
         has_one = lending_market
     )]
     pub reserve: AccountLoader<'info, Reserve>,
@@ -51,16 +67,20 @@ pub struct WithdrawProtocolFeesCtx<'info> {
     )]
     pub lending_market_authority: AccountInfo<'info>,
 
+    #[account(address = reserve.load()?.liquidity.mint_pubkey)]
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(mut,
         address = reserve.load()?.liquidity.fee_vault,
         token::authority = lending_market_authority,
     )]
-    pub fee_vault: Account<'info, TokenAccount>,
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut,
         token::mint = reserve.load()?.liquidity.mint_pubkey,
     )]
-    pub lending_market_owner_ata: Account<'info, TokenAccount>,
+    pub lending_market_owner_ata: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(address = reserve.load()?.liquidity.token_program())]
+    pub token_program: Interface<'info, TokenInterface>,
 }
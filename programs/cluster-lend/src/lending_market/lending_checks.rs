@@ -1,10 +1,13 @@
 use crate::constants::PROGRAM_VERSION;
-use crate::state::{LendingAction, RedeemReserveCollateralAccounts, ReserveStatus};
+use crate::state::{
+    LendingAction, RedeemReserveCollateralAccounts, ReservePauseFlags, ReserveStatus,
+};
 use crate::{errors::LendingError, state::Reserve};
 use crate::{
     BorrowObligationLiquidityCtx, DepositObligationCollateralAccounts,
     DepositReserveLiquidityAccounts, FlashBorrowReserveCtx, FlashRepayReserveCtx,
-    LiquidateObligationCtx, RepayObligationLiquidityCtx, WithdrawObligationCollateralAccounts,
+    LiquidateObligationCtx, LiquidateObligationNoRedeemCtx, RepayObligationLiquidityCtx,
+    WithdrawObligationCollateralAccounts,
 };
 use anchor_lang::prelude::*;
 
@@ -67,7 +70,9 @@ pub fn post_transfer_vault_balance_liquidity_reserve_checks(
 pub fn deposit_reserve_liquidity_checks(accounts: &DepositReserveLiquidityAccounts) -> Result<()> {
     let reserve = accounts.reserve.load()?;
 
-    if reserve.liquidity.supply_vault == accounts.user_source_liquidity.key() {
+    if accounts.user_source_liquidity.as_ref().map(|a| a.key())
+        == Some(reserve.liquidity.supply_vault)
+    {
         msg!("Reserve liquidity supply cannot be used as the source liquidity provided");
         return err!(LendingError::InvalidAccountInput);
     }
@@ -76,10 +81,9 @@ pub fn deposit_reserve_liquidity_checks(accounts: &DepositReserveLiquidityAccoun
         return err!(LendingError::InvalidAccountInput);
     }
 
-    if reserve.config.status() == ReserveStatus::Obsolete {
-        msg!("Reserve is not active");
-        return err!(LendingError::ReserveObsolete);
-    }
+    accepts_new_exposure(&reserve)?;
+
+    operation_not_paused(&reserve, ReservePauseFlags::DEPOSIT)?;
 
     if reserve.version != PROGRAM_VERSION as u64 {
         msg!("Reserve version does not match the program version");
@@ -95,11 +99,18 @@ pub fn redeem_reserve_collateral_checks(accounts: &RedeemReserveCollateralAccoun
         msg!("Reserve collateral supply cannot be used as the source collateral provided");
         return err!(LendingError::InvalidAccountInput);
     }
-    if reserve.liquidity.supply_vault == accounts.user_destination_liquidity.key() {
+    if accounts
+        .user_destination_liquidity
+        .as_ref()
+        .map(|a| a.key())
+        == Some(reserve.liquidity.supply_vault)
+    {
         msg!("Reserve liquidity supply cannot be used as the destination liquidity provided");
         return err!(LendingError::InvalidAccountInput);
     }
 
+    operation_not_paused(reserve, ReservePauseFlags::REDEEM)?;
+
     if reserve.version != PROGRAM_VERSION as u64 {
         msg!("Reserve version does not match the program version");
         return err!(LendingError::ReserveDeprecated);
@@ -113,17 +124,22 @@ pub fn borrow_obligation_liquidity_checks(
 ) -> Result<()> {
     let borrow_reserve = &ctx.accounts.borrow_reserve.load()?;
 
-    if borrow_reserve.liquidity.supply_vault == ctx.accounts.user_destination_liquidity.key() {
+    if ctx
+        .accounts
+        .user_destination_liquidity
+        .as_ref()
+        .map(|a| a.key())
+        == Some(borrow_reserve.liquidity.supply_vault)
+    {
         msg!(
             "Borrow reserve liquidity supply cannot be used as the destination liquidity provided"
         );
         return err!(LendingError::InvalidAccountInput);
     }
 
-    if borrow_reserve.config.status() == ReserveStatus::Obsolete {
-        msg!("Reserve is not active");
-        return err!(LendingError::ReserveObsolete);
-    }
+    accepts_new_exposure(borrow_reserve)?;
+
+    operation_not_paused(borrow_reserve, ReservePauseFlags::BORROW)?;
 
     if borrow_reserve.version != PROGRAM_VERSION as u64 {
         msg!("Reserve version does not match the program version");
@@ -143,10 +159,9 @@ pub fn deposit_obligation_collateral_checks(
         return err!(LendingError::InvalidAccountInput);
     }
 
-    if deposit_reserve.config.status() == ReserveStatus::Obsolete {
-        msg!("Reserve is not active");
-        return err!(LendingError::ReserveObsolete);
-    }
+    accepts_new_exposure(deposit_reserve)?;
+
+    operation_not_paused(deposit_reserve, ReservePauseFlags::DEPOSIT_COLLATERAL)?;
 
     if deposit_reserve.version != PROGRAM_VERSION as u64 {
         msg!("Reserve version does not match the program version");
@@ -171,17 +186,23 @@ pub fn withdraw_obligation_collateral_checks(
         return err!(LendingError::InvalidAccountInput);
     }
 
+    operation_not_paused(&withdraw_reserve, ReservePauseFlags::WITHDRAW_COLLATERAL)?;
+
     Ok(())
 }
 
 pub fn repay_obligation_liquidity_checks(ctx: &Context<RepayObligationLiquidityCtx>) -> Result<()> {
     let repay_reserve = ctx.accounts.repay_reserve.load()?;
 
-    if repay_reserve.liquidity.supply_vault == ctx.accounts.user_source_liquidity.key() {
+    if ctx.accounts.user_source_liquidity.as_ref().map(|a| a.key())
+        == Some(repay_reserve.liquidity.supply_vault)
+    {
         msg!("Repay reserve liquidity supply cannot be used as the source liquidity provided");
         return err!(LendingError::InvalidAccountInput);
     }
 
+    operation_not_paused(&repay_reserve, ReservePauseFlags::REPAY)?;
+
     if repay_reserve.version != PROGRAM_VERSION as u64 {
         msg!("Reserve version does not match the program version");
         return err!(LendingError::ReserveDeprecated);
@@ -191,14 +212,42 @@ pub fn repay_obligation_liquidity_checks(ctx: &Context<RepayObligationLiquidityC
 }
 
 pub fn liquidate_obligation_checks(ctx: &Context<LiquidateObligationCtx>) -> Result<()> {
-    let repay_reserve = ctx.accounts.repay_reserve.load()?;
-    let withdraw_reserve = ctx.accounts.withdraw_reserve.load()?;
+    let repay_reserve = &ctx.accounts.repay_reserve.load()?;
+    let withdraw_reserve = &ctx.accounts.withdraw_reserve.load()?;
+
+    liquidation_accounts_checks(
+        repay_reserve,
+        withdraw_reserve,
+        &ctx.accounts.user_source_liquidity.key(),
+        &ctx.accounts.user_destination_collateral.key(),
+    )
+}
 
-    if repay_reserve.liquidity.supply_vault == ctx.accounts.user_source_liquidity.key() {
+pub fn liquidate_obligation_no_redeem_checks(
+    ctx: &Context<LiquidateObligationNoRedeemCtx>,
+) -> Result<()> {
+    let repay_reserve = &ctx.accounts.repay_reserve.load()?;
+    let withdraw_reserve = &ctx.accounts.withdraw_reserve.load()?;
+
+    liquidation_accounts_checks(
+        repay_reserve,
+        withdraw_reserve,
+        &ctx.accounts.user_source_liquidity.key(),
+        &ctx.accounts.user_destination_collateral.key(),
+    )
+}
+
+fn liquidation_accounts_checks(
+    repay_reserve: &Reserve,
+    withdraw_reserve: &Reserve,
+    user_source_liquidity: &Pubkey,
+    user_destination_collateral: &Pubkey,
+) -> Result<()> {
+    if repay_reserve.liquidity.supply_vault == *user_source_liquidity {
         msg!("Repay reserve liquidity supply cannot be used as the source liquidity provided");
         return err!(LendingError::InvalidAccountInput);
     }
-    if repay_reserve.collateral.supply_vault == ctx.accounts.user_destination_collateral.key() {
+    if repay_reserve.collateral.supply_vault == *user_destination_collateral {
         msg!(
             "Repay reserve collateral supply cannot be used as the destination collateral provided"
         );
@@ -210,11 +259,11 @@ pub fn liquidate_obligation_checks(ctx: &Context<LiquidateObligationCtx>) -> Res
         return err!(LendingError::ReserveDeprecated);
     }
 
-    if withdraw_reserve.liquidity.supply_vault == ctx.accounts.user_source_liquidity.key() {
+    if withdraw_reserve.liquidity.supply_vault == *user_source_liquidity {
         msg!("Withdraw reserve liquidity supply cannot be used as the source liquidity provided");
         return err!(LendingError::InvalidAccountInput);
     }
-    if withdraw_reserve.collateral.supply_vault == ctx.accounts.user_destination_collateral.key() {
+    if withdraw_reserve.collateral.supply_vault == *user_destination_collateral {
         msg!("Withdraw reserve collateral supply cannot be used as the destination collateral provided");
         return err!(LendingError::InvalidAccountInput);
     }
@@ -224,6 +273,9 @@ pub fn liquidate_obligation_checks(ctx: &Context<LiquidateObligationCtx>) -> Res
         return err!(LendingError::ReserveDeprecated);
     }
 
+    operation_not_paused(repay_reserve, ReservePauseFlags::LIQUIDATION)?;
+    operation_not_paused(withdraw_reserve, ReservePauseFlags::LIQUIDATION)?;
+
     Ok(())
 }
 
@@ -259,6 +311,8 @@ pub fn flash_borrow_reserve_liquidity_checks(ctx: &Context<FlashBorrowReserveCtx
         return err!(LendingError::ReserveObsolete);
     }
 
+    operation_not_paused(&reserve, ReservePauseFlags::FLASH_LOAN)?;
+
     if reserve.config.fees.flash_loan_fee_sf == u64::MAX {
         msg!("Flash loans are disabled for this reserve");
         return err!(LendingError::FlashLoansDisabled);
@@ -277,3 +331,26 @@ pub fn flash_repay_reserve_liquidity_checks(ctx: &Context<FlashRepayReserveCtx>)
 
     Ok(())
 }
+
+fn operation_not_paused(reserve: &Reserve, operation: ReservePauseFlags) -> Result<()> {
+    if reserve.config.paused_operations().intersects(operation) {
+        msg!("{:?} is paused on this reserve", operation);
+        return err!(LendingError::ReserveOperationPaused);
+    }
+
+    Ok(())
+}
+
+fn accepts_new_exposure(reserve: &Reserve) -> Result<()> {
+    match reserve.config.status() {
+        ReserveStatus::Obsolete => {
+            msg!("Reserve is not active");
+            err!(LendingError::ReserveObsolete)
+        }
+        ReserveStatus::ReduceOnly => {
+            msg!("Reserve is reduce-only");
+            err!(LendingError::ReserveReduceOnly)
+        }
+        ReserveStatus::Active | ReserveStatus::Hidden => Ok(()),
+    }
+}
@@ -3,13 +3,17 @@ use std::{
     ops::{Add, Div, Mul},
 };
 
-use crate::{borsh::BorshDeserialize, utils::BorrowRateCurve};
 use crate::{
+    constants::MAX_CONFIG_TIMELOCK_SECS,
     errors::LendingError,
     lending_market::liquidation_operations,
-    state::{LendingMarket, PriceStatusFlags, Reserve},
+    state::{
+        LendingMarket, LendingMarketUpdate, ParameterRamp, PriceStatusFlags, Reserve,
+        ReservePauseFlags,
+    },
     utils::GetPriceResult,
-    CalculateLiquidationResult, LiquidateAndRedeemResult, ReserveConfig, UpdateConfigMode,
+    CalculateLiquidationResult, LiquidateAndRedeemResult, LiquidateWithoutRedeemResult,
+    ReserveConfigUpdate,
 };
 use crate::{
     state::{
@@ -20,7 +24,7 @@ use crate::{
 };
 use crate::{
     utils::{fraction::Fraction, AnyAccountLoader},
-    CalculateRepayResult,
+    CalculateRepayResult, SocializeLossResult,
 };
 use crate::{
     utils::{BigFraction, FractionExtra},
@@ -46,6 +50,9 @@ pub fn refresh_reserve(
     let slot = clock.slot;
 
     reserve.accrue_interest(slot)?;
+    reserve.config.apply_ramps(
+        u64::try_from(clock.unix_timestamp).map_err(|_| error!(LendingError::MathOverflow))?,
+    );
 
     let price_status = if let Some(GetPriceResult {
         price,
@@ -185,7 +192,7 @@ where
             .get()
             .map_err(|_| LendingError::InvalidAccountInput)?;
 
-        if deposit_reserve.config.status() == ReserveStatus::Obsolete {
+        if deposit_reserve.config.status().is_winding_down() {
             num_of_obsolete_reserves += 1;
         }
 
@@ -362,6 +369,7 @@ where
 
     obligation.lowest_reserve_deposit_ltv = lowest_deposit_ltv_accumulator.into();
     obligation.num_of_obsolete_reserves = num_of_obsolete_reserves;
+    obligation.update_unhealthy_since_slot(slot);
 
     let prices_state = deposits_prices_state.intersection(borrows_prices_state);
     obligation.last_update.update_slot(slot, Some(prices_state));
@@ -448,6 +456,10 @@ pub fn borrow_obligation_liquidity(
     }
 
     borrow_reserve.liquidity.borrow(borrow_amount_f)?;
+    borrow_reserve
+        .liquidity
+        .fee_ledger
+        .record_origination_fees(borrow_fee);
     borrow_reserve.last_update.mark_stale();
 
     let cumulative_borrow_rate_bf =
@@ -507,6 +519,7 @@ pub fn deposit_obligation_collateral(
     collateral.deposit(collateral_amount)?;
     obligation.last_update.mark_stale();
 
+    deposit_reserve.deposit_obligation_collateral(collateral_amount)?;
     deposit_reserve.last_update.mark_stale();
 
     post_deposit_obligation_invariants(
@@ -524,7 +537,7 @@ pub fn deposit_obligation_collateral(
 
 pub fn withdraw_obligation_collateral(
     lending_market: &LendingMarket,
-    withdraw_reserve: &Reserve,
+    withdraw_reserve: &mut Reserve,
     obligation: &mut Obligation,
     collateral_amount: u64,
     slot: Slot,
@@ -632,6 +645,8 @@ pub fn withdraw_obligation_collateral(
     obligation.withdraw(withdraw_amount, collateral_index)?;
     obligation.last_update.mark_stale();
 
+    withdraw_reserve.withdraw_obligation_collateral(withdraw_amount)?;
+
     post_withdraw_obligation_invariants(
         withdraw_reserve
             .collateral_exchange_rate()?
@@ -714,6 +729,55 @@ pub fn repay_obligation_liquidity(
     Ok(repay_amount)
 }
 
+/// Writes off the whole `reserve_pk` borrow of an obligation that has no collateral left.
+/// Up to `insurance_available` of it is repaid by the insurance vault, the rest is forgiven
+/// and lowers the reserve exchange rate.
+pub fn socialize_loss(
+    reserve: &mut Reserve,
+    obligation: &mut Obligation,
+    reserve_pk: Pubkey,
+    insurance_available: u64,
+    slot: Slot,
+) -> Result<SocializeLossResult> {
+    if !obligation.deposits_empty() {
+        msg!("Obligation still has collateral to liquidate");
+        return err!(LendingError::CannotSocializeObligationWithCollateral);
+    }
+
+    if reserve.last_update.is_stale(slot, PriceStatusFlags::NONE)? {
+        msg!("Reserve is stale and must be refreshed in the current slot");
+        return err!(LendingError::ReserveStale);
+    }
+
+    let (liquidity, liquidity_index) = obligation.find_liquidity_in_borrows_mut(reserve_pk)?;
+    if liquidity.borrowed_amount_sf == 0 {
+        msg!("Liquidity borrowed amount is zero");
+        return err!(LendingError::ObligationLiquidityEmpty);
+    }
+
+    liquidity.accrue_interest(BigFraction::from(
+        reserve.liquidity.cumulative_borrow_rate_bsf,
+    ))?;
+    let bad_debt_f = Fraction::from_bits(liquidity.borrowed_amount_sf);
+
+    let insurance_amount = min(insurance_available, bad_debt_f.to_ceil());
+    let insured_f = min(Fraction::from(insurance_amount), bad_debt_f);
+    let socialized_amount_f = bad_debt_f - insured_f;
+
+    reserve.liquidity.repay(insurance_amount, insured_f)?;
+    reserve.liquidity.forgive_debt(socialized_amount_f)?;
+    reserve.last_update.mark_stale();
+
+    obligation.repay(bad_debt_f, liquidity_index)?;
+    obligation.update_has_debt();
+    obligation.last_update.mark_stale();
+
+    Ok(SocializeLossResult {
+        insurance_amount,
+        socialized_amount_f,
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn liquidate_and_redeem(
     lending_market: &LendingMarket,
@@ -743,6 +807,7 @@ pub fn liquidate_and_redeem(
     )?;
 
     let withdraw_reserve = &mut withdraw_reserve.get_mut()?;
+    withdraw_reserve.withdraw_obligation_collateral(withdraw_amount)?;
 
     let total_withdraw_liquidity_amount = post_liquidate_redeem(
         withdraw_reserve,
@@ -757,6 +822,87 @@ pub fn liquidate_and_redeem(
         withdraw_amount,
         total_withdraw_liquidity_amount,
         withdraw_collateral_amount,
+        liquidation_bonus_rate,
+    })
+}
+
+/// Like `liquidate_and_redeem`, but the liquidator keeps the seized cTokens so the liquidation
+/// does not depend on the liquidity available in the withdraw reserve. The protocol fee is taken
+/// by burning part of those cTokens, and the slippage check applies to the cTokens kept.
+#[allow(clippy::too_many_arguments)]
+pub fn liquidate_without_redeem(
+    lending_market: &LendingMarket,
+    repay_reserve: &dyn AnyAccountLoader<Reserve>,
+    withdraw_reserve: &dyn AnyAccountLoader<Reserve>,
+    obligation: &mut Obligation,
+    clock: &Clock,
+    liquidity_amount: u64,
+    min_acceptable_received_collateral_amount: u64,
+    max_allowed_ltv_override_pct_opt: Option<u64>,
+) -> Result<LiquidateWithoutRedeemResult> {
+    let LiquidateObligationResult {
+        repay_amount,
+        withdraw_amount,
+        liquidation_bonus_rate,
+        ..
+    } = liquidate_obligation(
+        lending_market,
+        repay_reserve,
+        withdraw_reserve,
+        obligation,
+        clock,
+        liquidity_amount,
+        0,
+        max_allowed_ltv_override_pct_opt,
+    )?;
+
+    let withdraw_reserve = &mut withdraw_reserve.get_mut()?;
+    withdraw_reserve.withdraw_obligation_collateral(withdraw_amount)?;
+
+    let protocol_fee_collateral_amount = min(
+        liquidation_operations::calculate_protocol_liquidation_fee(
+            withdraw_amount,
+            liquidation_bonus_rate,
+            withdraw_reserve.config.protocol_liquidation_fee_pct,
+        ),
+        withdraw_amount,
+    );
+
+    if withdraw_amount - protocol_fee_collateral_amount < min_acceptable_received_collateral_amount
+    {
+        msg!("Withdraw amount below minimum acceptable collateral amount");
+        return err!(LendingError::LiquidationSlippageError);
+    }
+
+    let protocol_fee =
+        withdraw_reserve.burn_collateral_as_protocol_fees(protocol_fee_collateral_amount)?;
+
+    // The insurance share leaves the reserve at once, as in a redeeming liquidation. Without
+    // enough available liquidity it stays in the accumulated fees and `redeem_fees` pays it.
+    let insurance_fee = match withdraw_reserve.insurance_fee(protocol_fee) {
+        insurance_fee if insurance_fee <= withdraw_reserve.liquidity.available_amount => {
+            withdraw_reserve.liquidity.redeem_fees(insurance_fee)?;
+            insurance_fee
+        }
+        _ => 0,
+    };
+    withdraw_reserve.last_update.mark_stale();
+
+    msg!(
+        "pnl: Liquidator repaid {} and withdrew {} cTokens with fees {}, to insurance: {}",
+        repay_amount,
+        withdraw_amount - protocol_fee_collateral_amount,
+        protocol_fee,
+        insurance_fee
+    );
+
+    Ok(LiquidateWithoutRedeemResult {
+        repay_amount,
+        withdraw_amount,
+        protocol_fee_collateral_amount,
+        protocol_fee,
+        insurance_fee,
+        liquidation_bonus_rate,
     })
 }
 
@@ -888,6 +1034,10 @@ pub(crate) fn post_liquidate_redeem(
             withdraw_liquidity_amount.checked_sub(protocol_fee).unwrap(),
             protocol_fee
         );
+        withdraw_reserve
+            .liquidity
+            .fee_ledger
+            .record_liquidation_fees(protocol_fee);
         Ok(Some((withdraw_liquidity_amount, protocol_fee)))
     } else {
         Ok(None)
@@ -908,7 +1058,7 @@ pub fn flash_borrow_reserve_liquidity(reserve: &mut Reserve, liquidity_amount: u
     Ok(())
 }
 
-pub fn flash_repay_reserve_liquidity<'info>(
+pub fn flash_repay_reserve_liquidity(
     reserve: &mut Reserve,
     liquidity_amount: u64,
     slot: Slot,
@@ -924,6 +1074,10 @@ pub fn flash_repay_reserve_liquidity<'info>(
     reserve
         .liquidity
         .repay(flash_loan_amount, flash_loan_amount_f)?;
+    reserve
+        .liquidity
+        .fee_ledger
+        .record_flash_loan_fees(protocol_fee);
     refresh_reserve_limit_timestamps(reserve, slot)?;
     reserve.last_update.mark_stale();
 
@@ -976,245 +1130,287 @@ pub fn redeem_fees(reserve: &mut Reserve, slot: Slot) -> Result<u64> {
     Ok(withdraw_amount)
 }
 
-pub fn update_reserve_config(reserve: &mut Reserve, mode: UpdateConfigMode, value: &[u8]) {
-    match mode {
-        UpdateConfigMode::UpdateLoanToValuePct => {
-            let new = value[0];
-            let prv = reserve.config.loan_to_value_pct;
-            reserve.config.loan_to_value_pct = new;
+pub fn update_reserve_config(reserve: &mut Reserve, update: &ReserveConfigUpdate) -> Result<()> {
+    macro_rules! set_field {
+        ($($field:ident).+, $new:expr) => {{
+            let new = $new;
+            let prv = reserve.config.$($field).+;
+            reserve.config.$($field).+ = new;
             msg!("Prv Value is {:?}", prv);
             msg!("New Value is {:?}", new);
+        }};
+    }
+
+    match *update {
+        ReserveConfigUpdate::LoanToValuePct(new) => {
+            set_field!(loan_to_value_pct, new);
+            reserve.config.loan_to_value_ramp = ParameterRamp::default();
         }
-        UpdateConfigMode::UpdateMaxLiquidationBonusBps => {
-            let new: u16 = u16::from_le_bytes(value[..2].try_into().unwrap());
-            let prv = reserve.config.max_liquidation_bonus_bps;
-            reserve.config.max_liquidation_bonus_bps = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::MaxLiquidationBonusBps(new) => {
+            set_field!(max_liquidation_bonus_bps, new)
         }
-        UpdateConfigMode::UpdateLiquidationThresholdPct => {
-            let new = value[0];
-            let prv = reserve.config.liquidation_threshold_pct;
-            reserve.config.liquidation_threshold_pct = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::LiquidationThresholdPct(new) => {
+            set_field!(liquidation_threshold_pct, new);
+            reserve.config.liquidation_threshold_ramp = ParameterRamp::default();
         }
-        UpdateConfigMode::UpdateProtocolLiquidationFee => {
-            let new = value[0];
-            let prv = reserve.config.protocol_liquidation_fee_pct;
-            reserve.config.protocol_liquidation_fee_pct = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::ProtocolLiquidationFeePct(new) => {
+            set_field!(protocol_liquidation_fee_pct, new)
         }
-        UpdateConfigMode::UpdateProtocolTakeRate => {
-            let new = value[0];
-            let prv = reserve.config.protocol_take_rate_pct;
-            reserve.config.protocol_take_rate_pct = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
-        }
-        UpdateConfigMode::UpdateFeesBorrowFee => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.fees.borrow_fee_sf;
-            reserve.config.fees.borrow_fee_sf = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
-        }
-        UpdateConfigMode::UpdateFeesFlashLoanFee => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
+        ReserveConfigUpdate::ProtocolTakeRatePct(new) => set_field!(protocol_take_rate_pct, new),
+        ReserveConfigUpdate::BorrowFeeSf(new) => set_field!(fees.borrow_fee_sf, new),
+        ReserveConfigUpdate::FlashLoanFeeSf(new) => {
             let prv = reserve.config.fees.flash_loan_fee_sf;
             reserve.config.fees.flash_loan_fee_sf = new;
             msg!("Prv Value is {}", Fraction::from_bits(prv.into()));
             msg!("New Value is {}", Fraction::from_bits(new.into()));
         }
-        UpdateConfigMode::UpdateFeesReferralFeeBps => {
-            msg!("ReferralFee moved to lending_market");
-        }
-        UpdateConfigMode::UpdateDepositLimit => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.deposit_limit;
-            reserve.config.deposit_limit = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
-        }
-        UpdateConfigMode::UpdateBorrowLimit => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.borrow_limit;
-            reserve.config.borrow_limit = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
-        }
-        UpdateConfigMode::UpdateTokenInfoTwapDivergence => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.token_info.max_twap_divergence_bps;
-            reserve.config.token_info.max_twap_divergence_bps = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
-        }
-        UpdateConfigMode::UpdateTokenInfoName => {
-            let value: [u8; 32] = value[0..32].try_into().unwrap();
-            let str_name = std::str::from_utf8(&value).unwrap();
-            let cached = reserve.config.token_info.name;
-            let cached_name = std::str::from_utf8(&cached).unwrap();
-            msg!("Prev token name was {}", cached_name);
+        ReserveConfigUpdate::DepositLimit(new) => set_field!(deposit_limit, new),
+        ReserveConfigUpdate::BorrowLimit(new) => set_field!(borrow_limit, new),
+        ReserveConfigUpdate::TokenInfoTwapDivergenceBps(new) => {
+            set_field!(token_info.max_twap_divergence_bps, new)
+        }
+        ReserveConfigUpdate::TokenInfoName(new) => {
+            let Ok(str_name) = std::str::from_utf8(&new) else {
+                msg!("Token name must be valid utf-8");
+                return err!(LendingError::InvalidConfig);
+            };
+            msg!("Prev token name was {}", reserve.config.token_info.symbol());
             msg!("Setting token name to {}", str_name);
-            reserve.config.token_info.name = value;
+            reserve.config.token_info.name = new;
         }
-        UpdateConfigMode::UpdateTokenInfoPriceMaxAge => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.token_info.max_age_price_seconds;
-            reserve.config.token_info.max_age_price_seconds = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
-        }
-        UpdateConfigMode::UpdateTokenInfoTwapMaxAge => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.token_info.max_age_twap_seconds;
-            reserve.config.token_info.max_age_twap_seconds = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::TokenInfoPriceMaxAgeSeconds(new) => {
+            set_field!(token_info.max_age_price_seconds, new)
         }
-
-        UpdateConfigMode::UpdatePythPrice => {
-            let new: [u8; 32] = value[0..32].try_into().unwrap();
-            let new = Pubkey::new_from_array(new);
-            let prv = reserve.config.token_info.pyth_configuration.price;
-            reserve.config.token_info.pyth_configuration.price = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::TokenInfoTwapMaxAgeSeconds(new) => {
+            set_field!(token_info.max_age_twap_seconds, new)
         }
-        UpdateConfigMode::UpdateBorrowRateCurve => {
-            let new: BorrowRateCurve = BorshDeserialize::deserialize(&mut &value[..]).unwrap();
-            let prv = reserve.config.borrow_rate_curve;
-            reserve.config.borrow_rate_curve = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::PythPrice(new) => {
+            set_field!(token_info.pyth_configuration.price, new)
         }
-        UpdateConfigMode::UpdateEntireReserveConfig => {
-            let new: ReserveConfig = BorshDeserialize::deserialize(&mut &value[..]).unwrap();
+        ReserveConfigUpdate::BorrowRateCurve(new) => set_field!(borrow_rate_curve, new),
+        ReserveConfigUpdate::EntireReserveConfig(new) => {
             reserve.config = new;
-            msg!("New Value is {:?}", value);
+            msg!("New Value is {:?}", new);
         }
-        UpdateConfigMode::UpdateDebtWithdrawalCap => {
-            let capacity = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let interval_length_seconds = u64::from_le_bytes(value[8..16].try_into().unwrap());
-
-            let prev_capacity = reserve.config.debt_withdrawal_cap.config_capacity;
-            let prev_length = reserve
-                .config
-                .debt_withdrawal_cap
-                .config_interval_length_seconds;
-
-            reserve.config.debt_withdrawal_cap.config_capacity = capacity.try_into().unwrap();
-            reserve
-                .config
-                .debt_withdrawal_cap
-                .config_interval_length_seconds = interval_length_seconds;
-
+        ReserveConfigUpdate::DebtWithdrawalCap {
+            capacity,
+            interval_length_seconds,
+        } => {
+            let cap = &mut reserve.config.debt_withdrawal_cap;
+            msg!(
+                "Prv capacity is {:?}, interval_length_seconds is {:?}",
+                cap.config_capacity,
+                cap.config_interval_length_seconds
+            );
+            cap.config_capacity = capacity;
+            cap.config_interval_length_seconds = interval_length_seconds;
             msg!(
                 "New capacity is {:?}, interval_length_seconds is {:?}",
                 capacity,
                 interval_length_seconds
             );
-            msg!(
-                "Prv capacity is {:?}, interval_length_seconds is {:?}",
-                prev_capacity,
-                prev_length
-            );
         }
-        UpdateConfigMode::UpdateDepositWithdrawalCap => {
-            let capacity = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let interval_length_seconds = u64::from_le_bytes(value[8..16].try_into().unwrap());
-
-            let prev_capacity = reserve.config.deposit_withdrawal_cap.config_capacity;
-            let prev_length = reserve
-                .config
-                .deposit_withdrawal_cap
-                .config_interval_length_seconds;
-
-            reserve.config.deposit_withdrawal_cap.config_capacity = capacity.try_into().unwrap();
-            reserve
-                .config
-                .deposit_withdrawal_cap
-                .config_interval_length_seconds = interval_length_seconds;
-
+        ReserveConfigUpdate::DepositWithdrawalCap {
+            capacity,
+            interval_length_seconds,
+        } => {
+            let cap = &mut reserve.config.deposit_withdrawal_cap;
             msg!(
                 "Prv capacity is {:?}, interval_length_seconds is {:?}",
-                prev_capacity,
-                prev_length
+                cap.config_capacity,
+                cap.config_interval_length_seconds
             );
+            cap.config_capacity = capacity;
+            cap.config_interval_length_seconds = interval_length_seconds;
             msg!(
                 "New capacity is {:?}, interval_length_seconds is {:?}",
                 capacity,
                 interval_length_seconds
             );
         }
-        UpdateConfigMode::UpdateDebtWithdrawalCapCurrentTotal => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.debt_withdrawal_cap.current_total;
-            reserve.config.debt_withdrawal_cap.current_total = new.try_into().unwrap();
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::DebtWithdrawalCapCurrentTotal(new) => {
+            set_field!(debt_withdrawal_cap.current_total, new)
         }
-        UpdateConfigMode::UpdateDepositWithdrawalCapCurrentTotal => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.deposit_withdrawal_cap.current_total;
-            reserve.config.deposit_withdrawal_cap.current_total = new.try_into().unwrap();
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::DepositWithdrawalCapCurrentTotal(new) => {
+            set_field!(deposit_withdrawal_cap.current_total, new)
         }
-        UpdateConfigMode::UpdateBadDebtLiquidationBonusBps => {
-            let new: u16 = u16::from_le_bytes(value[..2].try_into().unwrap());
-            let prv = reserve.config.bad_debt_liquidation_bonus_bps;
-            reserve.config.bad_debt_liquidation_bonus_bps = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::BadDebtLiquidationBonusBps(new) => {
+            set_field!(bad_debt_liquidation_bonus_bps, new)
         }
-        UpdateConfigMode::UpdateMinLiquidationBonusBps => {
-            let new: u16 = u16::from_le_bytes(value[..2].try_into().unwrap());
-            let prv = reserve.config.min_liquidation_bonus_bps;
-            reserve.config.min_liquidation_bonus_bps = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::MinLiquidationBonusBps(new) => {
+            set_field!(min_liquidation_bonus_bps, new)
         }
-        UpdateConfigMode::DeleveragingMarginCallPeriod => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.deleveraging_margin_call_period_secs;
-            reserve.config.deleveraging_margin_call_period_secs = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::DeleveragingMarginCallPeriodSecs(new) => {
+            set_field!(deleveraging_margin_call_period_secs, new)
         }
-        UpdateConfigMode::UpdateBorrowFactor => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.borrow_factor_pct;
-            reserve.config.borrow_factor_pct = new;
+        ReserveConfigUpdate::BorrowFactorPct(new) => set_field!(borrow_factor_pct, new),
+        ReserveConfigUpdate::AssetTier(new) => set_field!(asset_tier, u8::from(new)),
+        ReserveConfigUpdate::PausedOperations(new) => {
+            let flags = ReservePauseFlags::from_bits(new).ok_or_else(|| {
+                msg!("Unknown pause flags {:#b}", new);
+                error!(LendingError::InvalidConfig)
+            })?;
+            let prv = reserve.config.paused_operations();
+            reserve.config.paused_operations = flags.bits();
             msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+            msg!("New Value is {:?}", flags);
         }
-        UpdateConfigMode::UpdateAssetTier => {
-            let new = value[0];
-            let prv = reserve.config.asset_tier;
-            reserve.config.asset_tier = new;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::InsuranceFeePct(new) => set_field!(insurance_fee_pct, new),
+        ReserveConfigUpdate::DeleveragingThresholdSlotsPerBps(new) => {
+            set_field!(deleveraging_threshold_slots_per_bps, new)
         }
-        UpdateConfigMode::DeleveragingThresholdSlotsPerBps => {
-            let new = u64::from_le_bytes(value[..8].try_into().unwrap());
-            let prv = reserve.config.deleveraging_threshold_slots_per_bps;
-            reserve.config.deleveraging_threshold_slots_per_bps = new;
+        ReserveConfigUpdate::ReserveStatus(new) => {
+            let prv = reserve.config.status();
+            reserve.config.status = new.into();
             msg!("Prv Value is {:?}", prv);
             msg!("New Value is {:?}", new);
         }
-        UpdateConfigMode::UpdateReserveStatus => {
-            let new = ReserveStatus::try_from(value[0]).unwrap();
-            let prv = ReserveStatus::try_from(reserve.config.status).unwrap();
-            reserve.config.status = new as u8;
-            msg!("Prv Value is {:?}", prv);
-            msg!("New Value is {:?}", new);
+        ReserveConfigUpdate::LoanToValueRamp {
+            target_pct,
+            start_ts,
+            duration_secs,
+        } => {
+            if duration_secs == 0 {
+                msg!("Ramp duration must be positive, use LoanToValuePct for a step change");
+                return err!(LendingError::InvalidConfig);
+            }
+            let ramp = ParameterRamp::new(
+                reserve.config.loan_to_value_pct,
+                target_pct,
+                start_ts,
+                duration_secs,
+            );
+            set_field!(loan_to_value_ramp, ramp);
+        }
+        ReserveConfigUpdate::LiquidationThresholdRamp {
+            target_pct,
+            start_ts,
+            duration_secs,
+        } => {
+            if duration_secs == 0 {
+                msg!(
+                    "Ramp duration must be positive, use LiquidationThresholdPct for a step change"
+                );
+                return err!(LendingError::InvalidConfig);
+            }
+            let ramp = ParameterRamp::new(
+                reserve.config.liquidation_threshold_pct,
+                target_pct,
+                start_ts,
+                duration_secs,
+            );
+            set_field!(liquidation_threshold_ramp, ramp);
         }
     }
 
     reserve.last_update.mark_stale();
+
+    Ok(())
+}
+
+pub fn update_lending_market_config(
+    market: &mut LendingMarket,
+    update: &LendingMarketUpdate,
+) -> Result<()> {
+    msg!("Updating lending market with {:?}", update);
+
+    match *update {
+        LendingMarketUpdate::EmergencyMode(emergency_mode) => {
+            msg!("Prev Value is {:?}", market.emergency_mode);
+            market.emergency_mode = emergency_mode.into();
+        }
+        LendingMarketUpdate::LiquidationCloseFactorPct(liquidation_close_factor) => {
+            if !(5..=100).contains(&liquidation_close_factor) {
+                return err!(LendingError::InvalidFlag);
+            }
+            msg!(
+                "Prev Value is {:?}",
+                market.liquidation_max_debt_close_factor_pct
+            );
+            market.liquidation_max_debt_close_factor_pct = liquidation_close_factor;
+        }
+        LendingMarketUpdate::LiquidationMaxValue(value) => {
+            if value == 0 {
+                return err!(LendingError::InvalidFlag);
+            }
+            msg!(
+                "Prev Value is {:?}",
+                market.max_liquidatable_debt_market_value_at_once
+            );
+            market.max_liquidatable_debt_market_value_at_once = value;
+        }
+        LendingMarketUpdate::GlobalAllowedBorrowValue(value) => {
+            msg!("Prev Value is {:?}", market.global_allowed_borrow_value);
+            market.global_allowed_borrow_value = value;
+        }
+        LendingMarketUpdate::GlobalUnhealthyBorrowValue(value) => {
+            msg!("Prev Value is {:?}", market.global_unhealthy_borrow_value);
+            market.global_unhealthy_borrow_value = value;
+        }
+        LendingMarketUpdate::MinFullLiquidationThreshold(value) => {
+            if value == 0 {
+                return err!(LendingError::InvalidFlag);
+            }
+            msg!(
+                "Prev Value is {:?}",
+                market.min_full_liquidation_value_threshold
+            );
+            market.min_full_liquidation_value_threshold = value;
+        }
+        LendingMarketUpdate::InsolvencyRiskLtvPct(insolvency_risk_ltv) => {
+            if !(5..=100).contains(&insolvency_risk_ltv) {
+                return err!(LendingError::InvalidFlag);
+            }
+            msg!(
+                "Prev Value is {:?}",
+                market.insolvency_risk_unhealthy_ltv_pct
+            );
+            market.insolvency_risk_unhealthy_ltv_pct = insolvency_risk_ltv;
+        }
+        LendingMarketUpdate::PriceRefreshTriggerToMaxAgePct(value) => {
+            if value > 100 {
+                msg!("Price refresh trigger to max age pct must be in range [0, 100]");
+                return err!(LendingError::InvalidConfig);
+            }
+            msg!(
+                "Prev Value is {:?}",
+                market.price_refresh_trigger_to_max_age_pct
+            );
+            market.price_refresh_trigger_to_max_age_pct = value;
+        }
+        LendingMarketUpdate::AutodeleverageEnabled(autodeleverage_enabled) => {
+            msg!("Prev Value is {:?}", market.autodeleverage_enabled);
+            market.autodeleverage_enabled = autodeleverage_enabled.into();
+        }
+        LendingMarketUpdate::BorrowingDisabled(borrow_disabled) => {
+            msg!("Prev Value is {:?}", market.borrow_disabled);
+            market.borrow_disabled = borrow_disabled.into();
+        }
+        LendingMarketUpdate::MinNetValueObligationPostActionSf(min_net_value_in_obligation_sf) => {
+            msg!(
+                "Prev Value is {}",
+                Fraction::from_bits(market.min_net_value_in_obligation_sf)
+            );
+            market.min_net_value_in_obligation_sf = min_net_value_in_obligation_sf;
+        }
+        LendingMarketUpdate::LiquidationBonusRampSlots(value) => {
+            msg!("Prev Value is {:?}", market.liquidation_bonus_ramp_slots);
+            market.liquidation_bonus_ramp_slots = value;
+        }
+        LendingMarketUpdate::ConfigTimelockSecs(value) => {
+            if value > MAX_CONFIG_TIMELOCK_SECS {
+                msg!(
+                    "Config timelock must be at most {} seconds",
+                    MAX_CONFIG_TIMELOCK_SECS
+                );
+                return err!(LendingError::InvalidConfig);
+            }
+            msg!("Prev Value is {:?}", market.config_timelock_secs);
+            market.config_timelock_secs = value;
+        }
+    }
+
+    Ok(())
 }
 
 pub mod utils {
@@ -1401,15 +1597,13 @@ pub mod utils {
             return err!(LendingError::NetValueRemainingTooSmall);
         }
 
-        if obligation.deposited_value_sf != 0 {
-            if new_ltv > obligation.loan_to_value() {
-                msg!(
-                    "Obligation new LTV after deposit {} of {}",
-                    new_ltv.to_display(),
-                    reserve.token_symbol()
-                );
-                return err!(LendingError::WorseLTVBlocked);
-            }
+        if obligation.deposited_value_sf != 0 && new_ltv > obligation.loan_to_value() {
+            msg!(
+                "Obligation new LTV after deposit {} of {}",
+                new_ltv.to_display(),
+                reserve.token_symbol()
+            );
+            return err!(LendingError::WorseLTVBlocked);
         }
 
         Ok(())
@@ -1658,6 +1852,25 @@ pub mod utils {
             msg!("Liquidation threshold must be in range [LTV, 100]");
             return err!(LendingError::InvalidConfig);
         }
+        let final_value = |current: u8, ramp: &ParameterRamp| {
+            if ramp.is_active() {
+                ramp.target_value
+            } else {
+                current
+            }
+        };
+        let final_ltv_pct = final_value(config.loan_to_value_pct, &config.loan_to_value_ramp);
+        let final_liquidation_threshold_pct = final_value(
+            config.liquidation_threshold_pct,
+            &config.liquidation_threshold_ramp,
+        );
+        if final_ltv_pct >= 100
+            || final_liquidation_threshold_pct < final_ltv_pct
+            || final_liquidation_threshold_pct > 100
+        {
+            msg!("Ramp targets must keep LTV in [0, 100) and the liquidation threshold in [LTV, 100]");
+            return err!(LendingError::InvalidConfig);
+        }
         if u128::from(config.fees.borrow_fee_sf) >= FRACTION_ONE_SCALED {
             msg!("Borrow fee must be in range [0, 100%]");
             return err!(LendingError::InvalidConfig);
@@ -1670,6 +1883,10 @@ pub mod utils {
             msg!("Protocol take rate must be in range [0, 100]");
             return err!(LendingError::InvalidConfig);
         }
+        if config.insurance_fee_pct > 100 {
+            msg!("Insurance fee must be in range [0, 100]");
+            return err!(LendingError::InvalidConfig);
+        }
         if !config.token_info.is_valid() {
             msg!("Invalid reserve token info");
             return err!(LendingError::InvalidOracleConfig);
@@ -137,9 +137,11 @@ pub fn get_liquidation_params(
     max_allowed_ltv_override_pct_opt: Option<u64>,
 ) -> Result<LiquidationParams> {
     if let Some(params) = check_liquidate_obligation(
+        lending_market,
         collateral_reserve,
         debt_reserve,
         obligation,
+        slot,
         max_allowed_ltv_override_pct_opt,
     ) {
         xmsg!(
@@ -169,9 +171,11 @@ pub fn get_liquidation_params(
 }
 
 pub fn check_liquidate_obligation(
+    lending_market: &LendingMarket,
     collateral_reserve: &Reserve,
     debt_reserve: &Reserve,
     obligation: &Obligation,
+    slot: Slot,
     max_allowed_ltv_override_pct_opt: Option<u64>,
 ) -> Option<LiquidationParams> {
     let user_ltv = obligation.loan_to_value();
@@ -189,6 +193,9 @@ pub fn check_liquidate_obligation(
             max_allowed_ltv_override_pct_opt,
         );
 
+        let auction_progress =
+            get_liquidation_bonus_auction_progress(lending_market, obligation, slot);
+
         return Some(LiquidationParams {
             user_ltv,
             liquidation_bonus_rate: calculate_liquidation_bonus(
@@ -196,6 +203,7 @@ pub fn check_liquidate_obligation(
                 &debt_reserve.config,
                 max_allowed_ltv,
                 user_ltv,
+                auction_progress,
             )
             .unwrap(),
         });
@@ -251,11 +259,30 @@ fn calculate_liquidation_amounts(
     }
 }
 
+fn get_liquidation_bonus_auction_progress(
+    lending_market: &LendingMarket,
+    obligation: &Obligation,
+    slot: Slot,
+) -> Option<Fraction> {
+    let ramp_slots = lending_market.liquidation_bonus_ramp_slots;
+    if ramp_slots == 0 || obligation.unhealthy_since_slot == 0 {
+        return None;
+    }
+
+    let slots_since_unhealthy = slot.saturating_sub(obligation.unhealthy_since_slot);
+    let progress =
+        Fraction::from_num(min(slots_since_unhealthy, ramp_slots)) / Fraction::from_num(ramp_slots);
+
+    xmsg!("Liquidation bonus auction: {slots_since_unhealthy}/{ramp_slots} slots since unhealthy");
+    Some(progress)
+}
+
 fn calculate_liquidation_bonus(
     collateral_reserve_config: &ReserveConfig,
     debt_reserve_config: &ReserveConfig,
     max_allowed_ltv: Fraction,
     user_ltv: Fraction,
+    auction_progress: Option<Fraction>,
 ) -> Result<Fraction> {
     let bad_debt_ltv = Fraction::ONE;
 
@@ -294,7 +321,12 @@ fn calculate_liquidation_bonus(
 
     let min_reserve_bonus = Fraction::from_bps(min_reserve_bonus_bps);
 
-    let min_bonus = max(min_reserve_bonus, unhealthy_factor);
+    let min_bonus = match auction_progress {
+        Some(progress) => {
+            min_reserve_bonus + (max(max_bonus, min_reserve_bonus) - min_reserve_bonus) * progress
+        }
+        None => max(min_reserve_bonus, unhealthy_factor),
+    };
 
     let collared_bonus = min(min_bonus, max_bonus);
 
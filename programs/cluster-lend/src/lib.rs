@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod lending_market;
 pub mod state;
@@ -34,8 +35,60 @@ pub mod cluster_lend {
         process_update_market(ctx, mode, value)
     }
 
-    pub fn update_market_owner(ctx: Context<UpdateMarketOwnerCtx>) -> Result<()> {
-        process_update_market_owner(ctx)
+    pub fn update_market_config(
+        ctx: Context<UpdateMarketCtx>,
+        update: LendingMarketUpdate,
+    ) -> Result<()> {
+        process_update_market_config(ctx, update)
+    }
+
+    pub fn propose_market_role(
+        ctx: Context<UpdateMarketCtx>,
+        role: MarketRole,
+        new_holder: Pubkey,
+    ) -> Result<()> {
+        process_propose_market_role(ctx, role, new_holder)
+    }
+
+    pub fn accept_market_role(ctx: Context<AcceptMarketRoleCtx>, role: MarketRole) -> Result<()> {
+        process_accept_market_role(ctx, role)
+    }
+
+    pub fn cancel_market_role(ctx: Context<UpdateMarketCtx>, role: MarketRole) -> Result<()> {
+        process_cancel_market_role(ctx, role)
+    }
+
+    pub fn guardian_update_market(
+        ctx: Context<GuardianUpdateMarketCtx>,
+        update: LendingMarketUpdate,
+    ) -> Result<()> {
+        process_guardian_update_market(ctx, update)
+    }
+
+    pub fn queue_market_update(
+        ctx: Context<QueueMarketUpdateCtx>,
+        update: LendingMarketUpdate,
+    ) -> Result<()> {
+        process_queue_market_update(ctx, update)
+    }
+
+    pub fn queue_reserve_update(
+        ctx: Context<QueueReserveUpdateCtx>,
+        update: ReserveConfigUpdate,
+    ) -> Result<()> {
+        process_queue_reserve_update(ctx, update)
+    }
+
+    pub fn execute_market_update(ctx: Context<ExecuteMarketUpdateCtx>) -> Result<()> {
+        process_execute_market_update(ctx)
+    }
+
+    pub fn execute_reserve_update(ctx: Context<ExecuteReserveUpdateCtx>) -> Result<()> {
+        process_execute_reserve_update(ctx)
+    }
+
+    pub fn cancel_queued_update(ctx: Context<CancelQueuedUpdateCtx>) -> Result<()> {
+        process_cancel_queued_update(ctx)
     }
 
     pub fn redeem_fees(ctx: Context<RedeemFeesCtx>) -> Result<()> {
@@ -49,6 +102,66 @@ pub mod cluster_lend {
         process_withdraw_protocol_fees(ctx, amount)
     }
 
+    pub fn initialize_reserve_insurance(ctx: Context<InitializeReserveInsuranceCtx>) -> Result<()> {
+        process_initialize_reserve_insurance(ctx)
+    }
+
+    pub fn deposit_reserve_insurance(ctx: Context<ReserveInsuranceCtx>, amount: u64) -> Result<()> {
+        process_deposit_reserve_insurance(ctx, amount)
+    }
+
+    pub fn withdraw_reserve_insurance(
+        ctx: Context<ReserveInsuranceCtx>,
+        amount: u64,
+    ) -> Result<()> {
+        process_withdraw_reserve_insurance(ctx, amount)
+    }
+
+    pub fn queue_insurance_withdrawal(
+        ctx: Context<QueueInsuranceWithdrawalCtx>,
+        amount: u64,
+    ) -> Result<()> {
+        process_queue_insurance_withdrawal(ctx, amount)
+    }
+
+    pub fn execute_insurance_withdrawal(ctx: Context<ExecuteInsuranceWithdrawalCtx>) -> Result<()> {
+        process_execute_insurance_withdrawal(ctx)
+    }
+
+    pub fn socialize_loss(ctx: Context<SocializeLossCtx>) -> Result<()> {
+        process_socialize_loss(ctx)
+    }
+
+    pub fn initialize_fee_distribution(
+        ctx: Context<InitializeFeeDistributionCtx>,
+        shares: Vec<FeeShare>,
+    ) -> Result<()> {
+        process_initialize_fee_distribution(ctx, shares)
+    }
+
+    pub fn update_fee_distribution(
+        ctx: Context<UpdateFeeDistributionCtx>,
+        shares: Vec<FeeShare>,
+    ) -> Result<()> {
+        process_update_fee_distribution(ctx, shares)
+    }
+
+    pub fn distribute_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeFeesCtx<'info>>,
+    ) -> Result<()> {
+        process_distribute_fees(ctx)
+    }
+
+    pub fn get_reserve_fee_ledger(
+        ctx: Context<GetReserveFeeLedgerCtx>,
+    ) -> Result<ReserveFeeLedger> {
+        process_get_reserve_fee_ledger(ctx)
+    }
+
+    pub fn initialize_reserve_registry(ctx: Context<InitializeReserveRegistryCtx>) -> Result<()> {
+        process_initialize_reserve_registry(ctx)
+    }
+
     // Reserve instructions
     pub fn initialize_reserve(ctx: Context<InitializeReserveCtx>) -> Result<()> {
         process_initialize_reserve(ctx)
@@ -69,16 +182,53 @@ pub mod cluster_lend {
         process_update_reserve(ctx, mode, &value)
     }
 
+    pub fn update_reserve_config(
+        ctx: Context<UpdateReserveCtx>,
+        update: ReserveConfigUpdate,
+    ) -> Result<()> {
+        process_update_reserve_config(ctx, update)
+    }
+
+    pub fn guardian_update_reserve(
+        ctx: Context<GuardianUpdateReserveCtx>,
+        update: ReserveConfigUpdate,
+    ) -> Result<()> {
+        process_guardian_update_reserve(ctx, update)
+    }
+
+    pub fn close_reserve(ctx: Context<CloseReserveCtx>) -> Result<()> {
+        process_close_reserve(ctx)
+    }
+
+    pub fn skim_reserve_excess(ctx: Context<SkimReserveExcessCtx>) -> Result<()> {
+        process_skim_reserve_excess(ctx)
+    }
+
+    pub fn audit_reserve(ctx: Context<AuditReserveCtx>) -> Result<AuditReserveReport> {
+        process_audit_reserve(ctx)
+    }
+
+    pub fn audit_reserve_and_pause(
+        ctx: Context<AuditReserveAndPauseCtx>,
+    ) -> Result<AuditReserveReport> {
+        process_audit_reserve_and_pause(ctx)
+    }
+
     #[access_control(emergency_mode_disabled(&ctx.accounts.lending_market))]
     pub fn refresh_reserve(ctx: Context<RefreshReserveCtx>) -> Result<()> {
         process_refresh_reserve(ctx)
     }
 
+    #[access_control(emergency_mode_disabled(&ctx.accounts.lending_market))]
+    pub fn refresh_reserves_batch(ctx: Context<RefreshReservesBatchCtx>) -> Result<()> {
+        process_refresh_reserves_batch(ctx)
+    }
+
     #[access_control(emergency_mode_disabled(&ctx.accounts.lending_market))]
     pub fn deposit_reserve_liquidity(
         ctx: Context<DepositReserveLiquidityCtx>,
         liquidity_amount: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         process_deposit_reserve_liquidity(ctx, liquidity_amount)
     }
 
@@ -86,7 +236,7 @@ pub mod cluster_lend {
     pub fn redeem_reserve_collateral(
         ctx: Context<RedeemReserveCollateralCtx>,
         collateral_amount: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         process_redeem_reserve_collateral(ctx, collateral_amount)
     }
 
@@ -106,7 +256,7 @@ pub mod cluster_lend {
     pub fn deposit_obligation_collateral(
         ctx: Context<DepositObligationCollateralCtx>,
         collateral_amount: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         process_deposit_obligation_collateral(ctx, collateral_amount)
     }
 
@@ -114,7 +264,7 @@ pub mod cluster_lend {
     pub fn withdraw_obligation_collateral(
         ctx: Context<WithdrawObligationCollateralCtx>,
         collateral_amount: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         process_withdraw_obligation_collateral(ctx, collateral_amount)
     }
 
@@ -122,7 +272,7 @@ pub mod cluster_lend {
     pub fn borrow_obligation_liquidity(
         ctx: Context<BorrowObligationLiquidityCtx>,
         liquidity_amount: u64,
-    ) -> Result<()> {
+    ) -> Result<BorrowObligationLiquidityReturn> {
         process_borrow_obligation_liquidity(ctx, liquidity_amount)
     }
 
@@ -130,7 +280,7 @@ pub mod cluster_lend {
     pub fn repay_obligation_liquidity(
         ctx: Context<RepayObligationLiquidityCtx>,
         liquidity_amount: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         process_repay_obligation_liquidity(ctx, liquidity_amount)
     }
 
@@ -140,7 +290,7 @@ pub mod cluster_lend {
         liquidity_amount: u64,
         min_acceptable_received_collateral_amount: u64,
         max_allowed_ltv_override_percent: u64,
-    ) -> Result<()> {
+    ) -> Result<LiquidateObligationReturn> {
         process_liquidate_obligation(
             ctx,
             liquidity_amount,
@@ -149,13 +299,28 @@ pub mod cluster_lend {
         )
     }
 
+    #[access_control(emergency_mode_disabled(&ctx.accounts.lending_market))]
+    pub fn liquidate_obligation_no_redeem(
+        ctx: Context<LiquidateObligationNoRedeemCtx>,
+        liquidity_amount: u64,
+        min_acceptable_received_collateral_amount: u64,
+        max_allowed_ltv_override_percent: u64,
+    ) -> Result<LiquidateObligationReturn> {
+        process_liquidate_obligation_no_redeem(
+            ctx,
+            liquidity_amount,
+            min_acceptable_received_collateral_amount,
+            max_allowed_ltv_override_percent,
+        )
+    }
+
     // Flash Loan
     #[access_control(emergency_mode_disabled(&ctx.accounts.lending_market))]
     pub fn flash_repay_reserve_liquidity(
         ctx: Context<FlashRepayReserveCtx>,
         liquidity_amount: u64,
         borrow_instruction_index: u8,
-    ) -> Result<()> {
+    ) -> Result<FlashRepayReserveLiquidityReturn> {
         process_flash_repay_reserve(ctx, liquidity_amount, borrow_instruction_index)
     }
 
@@ -163,7 +328,7 @@ pub mod cluster_lend {
     pub fn flash_borrow_reserve_liquidity(
         ctx: Context<FlashBorrowReserveCtx>,
         liquidity_amount: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         process_flash_borrow_reserve(ctx, liquidity_amount)
     }
 }
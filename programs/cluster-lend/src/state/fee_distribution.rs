@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FULL_BPS, MAX_FEE_RECIPIENTS},
+    errors::LendingError,
+};
+
+static_assertions::const_assert_eq!(0, std::mem::size_of::<FeeDistribution>() % 8);
+/// How `distribute_fees` splits reserve fee vaults between recipients. Lives at the
+/// `[FEE_DISTRIBUTION, lending_market]` PDA.
+#[derive(Debug, PartialEq, Eq)]
+#[account(zero_copy)]
+#[repr(C)]
+pub struct FeeDistribution {
+    pub lending_market: Pubkey,
+    pub recipients_count: u64,
+    pub recipients: [FeeRecipient; MAX_FEE_RECIPIENTS],
+    pub padding: [u64; 8],
+}
+
+impl Default for FeeDistribution {
+    fn default() -> Self {
+        Self {
+            lending_market: Pubkey::default(),
+            recipients_count: 0,
+            recipients: [FeeRecipient::default(); MAX_FEE_RECIPIENTS],
+            padding: [0; 8],
+        }
+    }
+}
+
+impl FeeDistribution {
+    pub fn init(&mut self, lending_market: Pubkey) {
+        *self = Self::default();
+        self.lending_market = lending_market;
+    }
+
+    pub fn recipients(&self) -> &[FeeRecipient] {
+        &self.recipients[..self.recipients_count as usize]
+    }
+
+    /// Replaces every recipient. Shares are in bps and must add up to exactly [`FULL_BPS`].
+    pub fn set_recipients(&mut self, shares: &[FeeShare]) -> Result<()> {
+        if shares.is_empty() || shares.len() > MAX_FEE_RECIPIENTS {
+            msg!(
+                "Expected 1 to {} fee recipients, got {}",
+                MAX_FEE_RECIPIENTS,
+                shares.len()
+            );
+            return err!(LendingError::InvalidFeeDistribution);
+        }
+
+        let mut total_bps: u64 = 0;
+        for (index, share) in shares.iter().enumerate() {
+            if share.recipient == Pubkey::default()
+                || share.share_bps == 0
+                || shares[..index]
+                    .iter()
+                    .any(|other| other.recipient == share.recipient)
+            {
+                msg!("Invalid fee recipient {}", share.recipient);
+                return err!(LendingError::InvalidFeeDistribution);
+            }
+            total_bps += u64::from(share.share_bps);
+        }
+        if total_bps != u64::from(FULL_BPS) {
+            msg!("Fee shares add up to {} bps", total_bps);
+            return err!(LendingError::InvalidFeeDistribution);
+        }
+
+        self.recipients = [FeeRecipient::default(); MAX_FEE_RECIPIENTS];
+        for (slot, share) in self.recipients.iter_mut().zip(shares) {
+            *slot = FeeRecipient {
+                recipient: share.recipient,
+                share_bps: share.share_bps,
+                padding: [0; 6],
+            };
+        }
+        self.recipients_count = shares.len() as u64;
+
+        Ok(())
+    }
+
+    /// Amount of `total` owed to each recipient, in order. Rounding dust is not assigned and
+    /// stays in the fee vault for the next distribution.
+    pub fn split(&self, total: u64) -> Vec<u64> {
+        self.recipients()
+            .iter()
+            .map(|recipient| {
+                (u128::from(total) * u128::from(recipient.share_bps) / u128::from(FULL_BPS)) as u64
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+#[zero_copy]
+#[repr(C)]
+pub struct FeeRecipient {
+    /// Wallet receiving the share, paid into any of its token accounts for the fee mint.
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+    pub padding: [u8; 6],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeShare {
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+}
@@ -3,7 +3,7 @@ use derivative::Derivative;
 use num_enum::TryFromPrimitive;
 use strum::EnumString;
 
-use crate::constants::*;
+use crate::{constants::*, errors::LendingError};
 
 // static_assertions::const_assert_eq!(LENDING_MARKET_SIZE, std::mem::size_of::<LendingMarket>());
 static_assertions::const_assert_eq!(0, std::mem::size_of::<LendingMarket>() % 8);
@@ -39,8 +39,26 @@ pub struct LendingMarket {
 
     pub min_net_value_in_obligation_sf: u128,
 
+    /// Reserve registry PDA, zero for markets created before the registry that never set it up
+    pub reserve_registry: Pubkey,
+
+    /// Proposed owner, zero when no transfer is pending
+    pub pending_owner: Pubkey,
+
+    /// Can only tighten market and reserve settings, see [`LendingMarketUpdate::is_tightening`]
+    /// and [`ReserveConfigUpdate::is_tightening`]
+    pub guardian: Pubkey,
+    pub pending_guardian: Pubkey,
+
+    /// Delay for config updates that are not tightening, zero to apply every update at once
+    pub config_timelock_secs: u64,
+
+    /// Slots over which the liquidation bonus of an unhealthy obligation rises from the min to
+    /// the max bonus, zero to derive the bonus from the LTV instead
+    pub liquidation_bonus_ramp_slots: u64,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u64; 32],
+    pub reserved: [u64; 14],
 }
 
 impl Default for LendingMarket {
@@ -63,7 +81,13 @@ impl Default for LendingMarket {
             borrow_disabled: 0,
             min_net_value_in_obligation_sf: MIN_NET_VALUE_IN_OBLIGATION.to_bits(),
             padding: 0,
-            reserved: [0; 32],
+            reserve_registry: Pubkey::default(),
+            pending_owner: Pubkey::default(),
+            guardian: Pubkey::default(),
+            pending_guardian: Pubkey::default(),
+            config_timelock_secs: 0,
+            liquidation_bonus_ramp_slots: 0,
+            reserved: [0; 14],
         }
     }
 }
@@ -80,6 +104,79 @@ impl LendingMarket {
     pub fn is_borrowing_disabled(&self) -> bool {
         self.borrow_disabled != false as u8
     }
+
+    pub fn has_config_timelock(&self) -> bool {
+        self.config_timelock_secs > 0
+    }
+
+    pub fn has_reserve_registry(&self) -> bool {
+        self.reserve_registry != Pubkey::default()
+    }
+
+    pub fn role_holder(&self, role: MarketRole) -> Pubkey {
+        match role {
+            MarketRole::Owner => self.owner,
+            MarketRole::Guardian => self.guardian,
+        }
+    }
+
+    pub fn pending_role_holder(&self, role: MarketRole) -> Pubkey {
+        match role {
+            MarketRole::Owner => self.pending_owner,
+            MarketRole::Guardian => self.pending_guardian,
+        }
+    }
+
+    /// Records `new_holder` as the pending holder of `role`. The role only changes hands once
+    /// the pending holder accepts it.
+    pub fn propose_role(&mut self, role: MarketRole, new_holder: Pubkey) -> Result<()> {
+        require_keys_neq!(
+            new_holder,
+            Pubkey::default(),
+            LendingError::InvalidPendingRoleHolder
+        );
+        *self.role_slots_mut(role).1 = new_holder;
+        Ok(())
+    }
+
+    /// Hands `role` over to its pending holder, who must be `signer`. Returns the previous
+    /// holder.
+    pub fn accept_role(&mut self, role: MarketRole, signer: Pubkey) -> Result<Pubkey> {
+        let (holder, pending) = self.role_slots_mut(role);
+        if *pending == Pubkey::default() {
+            return err!(LendingError::NoPendingRoleTransfer);
+        }
+        require_keys_eq!(*pending, signer, LendingError::InvalidPendingRoleHolder);
+
+        let previous_holder = *holder;
+        *holder = signer;
+        *pending = Pubkey::default();
+        Ok(previous_holder)
+    }
+
+    /// Drops the pending transfer of `role`. Returns the holder that was proposed.
+    pub fn cancel_role(&mut self, role: MarketRole) -> Result<Pubkey> {
+        let pending = self.role_slots_mut(role).1;
+        if *pending == Pubkey::default() {
+            return err!(LendingError::NoPendingRoleTransfer);
+        }
+        Ok(std::mem::take(pending))
+    }
+
+    fn role_slots_mut(&mut self, role: MarketRole) -> (&mut Pubkey, &mut Pubkey) {
+        match role {
+            MarketRole::Owner => (&mut self.owner, &mut self.pending_owner),
+            MarketRole::Guardian => (&mut self.guardian, &mut self.pending_guardian),
+        }
+    }
+}
+
+/// Admin roles of a market. Each role changes hands in two steps: the owner proposes a new
+/// holder, then that holder accepts.
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MarketRole {
+    Owner,
+    Guardian,
 }
 
 pub struct InitLendingMarketParams {
@@ -112,4 +209,107 @@ pub enum UpdateLendingMarketMode {
     UpdateAutodeleverageEnabled = 13,
     UpdateBorrowingDisabled = 14,
     UpdateMinNetValueObligationPostAction = 15,
+    UpdateConfigTimelockSecs = 16,
+    UpdateLiquidationBonusRampSlots = 17,
+}
+
+/// Typed counterpart of [`UpdateLendingMarketMode`], one variant per market field.
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LendingMarketUpdate {
+    EmergencyMode(bool),
+    LiquidationCloseFactorPct(u8),
+    LiquidationMaxValue(u64),
+    GlobalUnhealthyBorrowValue(u64),
+    GlobalAllowedBorrowValue(u64),
+    MinFullLiquidationThreshold(u64),
+    InsolvencyRiskLtvPct(u8),
+    PriceRefreshTriggerToMaxAgePct(u8),
+    AutodeleverageEnabled(bool),
+    BorrowingDisabled(bool),
+    MinNetValueObligationPostActionSf(u128),
+    ConfigTimelockSecs(u64),
+    LiquidationBonusRampSlots(u64),
+}
+
+impl LendingMarketUpdate {
+    /// Whether the guardian may apply this update, i.e. it can only make `market` safer.
+    pub fn is_tightening(&self, market: &LendingMarket) -> bool {
+        match *self {
+            Self::EmergencyMode(enabled) => enabled || market.emergency_mode == false as u8,
+            Self::BorrowingDisabled(disabled) => disabled || !market.is_borrowing_disabled(),
+            _ => false,
+        }
+    }
+
+    /// Whether the owner may apply this update without going through the config timelock.
+    /// Broader than [`Self::is_tightening`], which only covers what the guardian may do: the
+    /// owner can also lift the emergency mode and re-enable borrowing at once.
+    pub fn is_timelock_exempt(&self, market: &LendingMarket) -> bool {
+        if self.is_tightening(market) {
+            return true;
+        }
+        match *self {
+            Self::EmergencyMode(_) | Self::BorrowingDisabled(_) => true,
+            Self::GlobalAllowedBorrowValue(value) => value <= market.global_allowed_borrow_value,
+            Self::ConfigTimelockSecs(secs) => secs >= market.config_timelock_secs,
+            _ => false,
+        }
+    }
+
+    /// Decodes the legacy `mode` + byte buffer encoding used by `update_market`.
+    pub fn from_mode_bytes(
+        mode: UpdateLendingMarketMode,
+        value: &[u8; VALUE_BYTE_MAX_ARRAY_LEN_MARKET_UPDATE],
+    ) -> Result<Self> {
+        let bool_value = || match value[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => err!(LendingError::InvalidFlag),
+        };
+        let u64_value = || u64::from_le_bytes(value[..8].try_into().unwrap());
+
+        let update = match mode {
+            UpdateLendingMarketMode::UpdateEmergencyMode => Self::EmergencyMode(bool_value()?),
+            UpdateLendingMarketMode::UpdateLiquidationCloseFactor => {
+                Self::LiquidationCloseFactorPct(value[0])
+            }
+            UpdateLendingMarketMode::UpdateLiquidationMaxValue => {
+                Self::LiquidationMaxValue(u64_value())
+            }
+            UpdateLendingMarketMode::UpdateGlobalUnhealthyBorrow => {
+                Self::GlobalUnhealthyBorrowValue(u64_value())
+            }
+            UpdateLendingMarketMode::UpdateGlobalAllowedBorrow => {
+                Self::GlobalAllowedBorrowValue(u64_value())
+            }
+            UpdateLendingMarketMode::UpdateMinFullLiquidationThreshold => {
+                Self::MinFullLiquidationThreshold(u64_value())
+            }
+            UpdateLendingMarketMode::UpdateInsolvencyRiskLtv => {
+                Self::InsolvencyRiskLtvPct(value[0])
+            }
+            UpdateLendingMarketMode::UpdatePriceRefreshTriggerToMaxAgePct => {
+                Self::PriceRefreshTriggerToMaxAgePct(value[0])
+            }
+            UpdateLendingMarketMode::UpdateAutodeleverageEnabled => {
+                Self::AutodeleverageEnabled(bool_value()?)
+            }
+            UpdateLendingMarketMode::UpdateBorrowingDisabled => {
+                Self::BorrowingDisabled(bool_value()?)
+            }
+            UpdateLendingMarketMode::UpdateMinNetValueObligationPostAction => {
+                Self::MinNetValueObligationPostActionSf(u128::from_le_bytes(
+                    value[..16].try_into().unwrap(),
+                ))
+            }
+            UpdateLendingMarketMode::UpdateConfigTimelockSecs => {
+                Self::ConfigTimelockSecs(u64_value())
+            }
+            UpdateLendingMarketMode::UpdateLiquidationBonusRampSlots => {
+                Self::LiquidationBonusRampSlots(u64_value())
+            }
+        };
+
+        Ok(update)
+    }
 }
@@ -1,15 +1,21 @@
+pub mod fee_distribution;
 pub mod last_update;
 pub mod lending_market;
 pub mod nested_accounts;
 pub mod obligation;
+pub mod queued_update;
 pub mod reserve;
+pub mod reserve_registry;
 pub mod token_info;
 pub mod types;
 
+pub use fee_distribution::*;
 pub use last_update::*;
 pub use lending_market::*;
 pub use nested_accounts::*;
 pub use obligation::*;
+pub use queued_update::*;
 pub use reserve::*;
+pub use reserve_registry::*;
 pub use token_info::*;
 pub use types::*;
@@ -1,21 +1,26 @@
 use anchor_lang::{prelude::*, Accounts};
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::{
+    token::{Mint, Token, TokenAccount},
+    token_interface::{self, TokenInterface},
+};
 
 use super::{obligation::Obligation, LendingMarket, Reserve};
 
 #[derive(Accounts)]
 pub struct DepositReserveLiquidityAccounts<'info> {
     pub owner: Signer<'info>,
-    pub user_source_liquidity: Box<Account<'info, TokenAccount>>,
+    pub user_source_liquidity: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
     pub user_destination_collateral: Box<Account<'info, TokenAccount>>,
     pub reserve: AccountLoader<'info, Reserve>,
-    pub reserve_liquidity_supply: Box<Account<'info, TokenAccount>>,
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     pub reserve_collateral_mint: Box<Account<'info, Mint>>,
     pub lending_market: AccountLoader<'info, LendingMarket>,
 
     /// CHECK: market authority PDA
     pub lending_market_authority: AccountInfo<'info>,
-    pub token_program: Program<'info, Token>,
+    pub collateral_token_program: Program<'info, Token>,
+    pub liquidity_token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
@@ -30,7 +35,7 @@ pub struct DepositObligationCollateralAccounts<'info> {
 
 #[derive(Accounts)]
 pub struct DepositReserveLiquidityAndObligationCollateralAccounts<'info> {
-    pub user_source_liquidity: Box<Account<'info, TokenAccount>>,
+    pub user_source_liquidity: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     pub reserve: AccountLoader<'info, Reserve>,
 }
 
@@ -51,7 +56,7 @@ pub struct WithdrawObligationCollateralAccounts<'info> {
 #[derive(Accounts)]
 pub struct WithdrawObligationCollateralAndRedeemReserveCollateralAccounts<'info> {
     pub withdraw_reserve: AccountLoader<'info, Reserve>,
-    pub user_destination_liquidity: Box<Account<'info, TokenAccount>>,
+    pub user_destination_liquidity: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 }
 
 #[derive(Accounts)]
@@ -60,16 +65,19 @@ pub struct RedeemReserveCollateralAccounts<'info> {
     #[account(mut)]
     pub user_source_collateral: Box<Account<'info, TokenAccount>>,
     #[account(mut)]
-    pub user_destination_liquidity: Box<Account<'info, TokenAccount>>,
+    pub user_destination_liquidity:
+        Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
     #[account(mut)]
     pub reserve: AccountLoader<'info, Reserve>,
     #[account(mut)]
     pub reserve_collateral_mint: Box<Account<'info, Mint>>,
+    pub reserve_liquidity_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
     #[account(mut)]
-    pub reserve_liquidity_supply: Box<Account<'info, TokenAccount>>,
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
     pub lending_market: AccountLoader<'info, LendingMarket>,
 
     /// CHECK: market authority PDA
     pub lending_market_authority: AccountInfo<'info>,
-    pub token_program: Program<'info, Token>,
+    pub collateral_token_program: Program<'info, Token>,
+    pub liquidity_token_program: Interface<'info, TokenInterface>,
 }
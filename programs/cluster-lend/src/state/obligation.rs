@@ -44,7 +44,10 @@ pub struct Obligation {
     pub has_debt: u8,
     pub padding_2: u8,
 
-    pub reserved: [u64; 32],
+    /// Slot of the first refresh that found the obligation unhealthy, zero while it is healthy
+    pub unhealthy_since_slot: u64,
+
+    pub reserved: [u64; 31],
 }
 
 impl Default for Obligation {
@@ -69,7 +72,8 @@ impl Default for Obligation {
             padding_0: 0,
             padding_1: 0,
             padding_2: 0,
-            reserved: [0; 32],
+            unhealthy_since_slot: 0,
+            reserved: [0; 31],
         }
     }
 }
@@ -164,6 +168,18 @@ impl Obligation {
         Ok(())
     }
 
+    /// Starts the liquidation bonus auction on the first refresh that finds the obligation
+    /// unhealthy, and resets it once the obligation is healthy again.
+    pub fn update_unhealthy_since_slot(&mut self, slot: Slot) {
+        let unhealthy = self.borrow_factor_adjusted_debt_value_sf > 0
+            && self.borrow_factor_adjusted_debt_value_sf >= self.unhealthy_borrow_value_sf;
+        if !unhealthy {
+            self.unhealthy_since_slot = 0;
+        } else if self.unhealthy_since_slot == 0 {
+            self.unhealthy_since_slot = slot;
+        }
+    }
+
     pub fn max_withdraw_value(&self, withdraw_collateral_ltv_pct: u8) -> LendingResult<Fraction> {
         let allowed_borrow_value = Fraction::from_bits(self.allowed_borrow_value_sf);
         let borrow_factor_adjusted_debt_value =
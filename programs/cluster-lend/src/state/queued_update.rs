@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{LendingMarketUpdate, ReserveConfig, ReserveConfigUpdate};
+
+/// A config update or insurance withdrawal waiting out the market's `config_timelock_secs`.
+/// Anyone can execute it once `earliest_execution_ts` has passed; the market owner can cancel it
+/// before that.
+#[account]
+#[derive(Debug)]
+pub struct QueuedUpdate {
+    pub lending_market: Pubkey,
+    /// Receives the account rent back once the update is executed or cancelled
+    pub payer: Pubkey,
+    pub earliest_execution_ts: i64,
+    pub update: QueuedUpdateKind,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Clone, Debug)]
+pub enum QueuedUpdateKind {
+    Market(LendingMarketUpdate),
+    Reserve {
+        reserve: Pubkey,
+        update: ReserveConfigUpdate,
+    },
+    /// Withdrawal of `amount` out of the `reserve` insurance vault, capped at the vault balance
+    /// on execution
+    InsuranceWithdrawal {
+        reserve: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+    },
+}
+
+impl QueuedUpdate {
+    /// Borsh size of the largest update, `ReserveConfigUpdate::EntireReserveConfig`. The
+    /// zero-copy `ReserveConfig` has no padding, so it serializes to its in-memory size.
+    pub const SPACE: usize = 8 // discriminator
+        + 32 // lending_market
+        + 32 // payer
+        + 8 // earliest_execution_ts
+        + 1 // QueuedUpdateKind variant
+        + 32 // reserve
+        + 1 // ReserveConfigUpdate variant
+        + std::mem::size_of::<ReserveConfig>();
+}
@@ -9,6 +9,7 @@ use anchor_lang::{
     solana_program::clock::Slot,
     Result,
 };
+use bitflags::bitflags;
 use borsh::{BorshDeserialize, BorshSerialize};
 use derivative::Derivative;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -18,7 +19,7 @@ use super::{LastUpdate, TokenInfo};
 use crate::{
     constants::{INITIAL_COLLATERAL_RATE, PROGRAM_VERSION, RESERVE_CONFIG_SIZE, SLOTS_PER_YEAR},
     errors::{LendingError, LendingResult},
-    state::{CalculateBorrowResult, CalculateRepayResult},
+    state::{AuditReserveReport, CalculateBorrowResult, CalculateRepayResult},
     utils::{borrow_rate_curve::BorrowRateCurve, BigFraction, Fraction, FractionExtra},
 };
 
@@ -47,7 +48,7 @@ impl From<BigFractionBytes> for BigFraction {
 
 // static_assertions::const_assert_eq!(RESERVE_SIZE, std::mem::size_of::<Reserve>());
 static_assertions::const_assert_eq!(0, std::mem::size_of::<Reserve>() % 8);
-#[derive(PartialEq, Derivative)]
+#[derive(PartialEq, Derivative, Default)]
 #[derivative(Debug)]
 #[account(zero_copy)]
 #[repr(C)]
@@ -67,22 +68,16 @@ pub struct Reserve {
 
     pub config: ReserveConfig,
 
-    pub reserved: [u64; 32],
-}
+    /// cTokens obligations deposited into the collateral supply vault. Anything above it in
+    /// the vault was sent there directly.
+    pub obligation_collateral_amount: u64,
+    /// Set for reserves created since `obligation_collateral_amount` is tracked, older reserves
+    /// cannot tell deposits from stray cTokens.
+    pub obligation_collateral_tracked: u8,
+    #[derivative(Debug = "ignore")]
+    pub padding_1: [u8; 7],
 
-impl Default for Reserve {
-    fn default() -> Self {
-        Self {
-            version: 0,
-            last_update: LastUpdate::default(),
-            lending_market: Pubkey::default(),
-            liquidity: ReserveLiquidity::default(),
-            collateral: ReserveCollateral::default(),
-            config: ReserveConfig::default(),
-            padding: 0,
-            reserved: [0; 32],
-        }
-    }
+    pub reserved: [u64; 30],
 }
 
 impl Reserve {
@@ -94,6 +89,7 @@ impl Reserve {
         self.liquidity = *params.liquidity;
         self.collateral = *params.collateral;
         self.config = *params.config;
+        self.obligation_collateral_tracked = 1;
     }
 
     pub fn current_borrow_rate(&self) -> Result<Fraction> {
@@ -112,6 +108,14 @@ impl Reserve {
         self.config.token_info.symbol()
     }
 
+    /// Part of a protocol fee paid into the insurance vault, zero until the reserve has one.
+    pub fn insurance_fee(&self, fee: u64) -> u64 {
+        if self.liquidity.insurance_vault == Pubkey::default() {
+            return 0;
+        }
+        (u128::from(fee) * u128::from(self.config.insurance_fee_pct) / 100) as u64
+    }
+
     pub fn deposit_liquidity(&mut self, liquidity_amount: u64) -> Result<u64> {
         let collateral_amount = self
             .collateral_exchange_rate()?
@@ -135,6 +139,108 @@ impl Reserve {
         Ok(liquidity_amount)
     }
 
+    /// Takes a protocol fee in cTokens: they are burnt and the liquidity they stand for moves
+    /// to the accumulated protocol fees, so the exchange rate does not change. Returns that
+    /// liquidity amount.
+    pub fn burn_collateral_as_protocol_fees(&mut self, collateral_amount: u64) -> Result<u64> {
+        let collateral_exchange_rate = self.collateral_exchange_rate()?;
+        let liquidity_amount_f = collateral_exchange_rate
+            .fraction_collateral_to_liquidity(Fraction::from(collateral_amount));
+
+        self.collateral.burn(collateral_amount)?;
+        self.liquidity.accumulated_protocol_fees_sf =
+            (Fraction::from_bits(self.liquidity.accumulated_protocol_fees_sf) + liquidity_amount_f)
+                .to_bits();
+
+        let liquidity_amount = liquidity_amount_f.to_floor();
+        self.liquidity
+            .fee_ledger
+            .record_liquidation_fees(liquidity_amount);
+
+        Ok(liquidity_amount)
+    }
+
+    pub fn deposit_obligation_collateral(&mut self, collateral_amount: u64) -> Result<()> {
+        if self.obligation_collateral_tracked != 0 {
+            self.obligation_collateral_amount = self
+                .obligation_collateral_amount
+                .checked_add(collateral_amount)
+                .ok_or(LendingError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    pub fn withdraw_obligation_collateral(&mut self, collateral_amount: u64) -> Result<()> {
+        if self.obligation_collateral_tracked != 0 {
+            self.obligation_collateral_amount = self
+                .obligation_collateral_amount
+                .checked_sub(collateral_amount)
+                .ok_or(LendingError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Tokens in the supply vaults that the reserve does not account for, as
+    /// `(liquidity, collateral)`. Collateral is never reported as excess on untracked reserves.
+    pub fn vault_excess(
+        &self,
+        liquidity_vault_balance: u64,
+        collateral_vault_balance: u64,
+    ) -> (u64, u64) {
+        let liquidity_excess =
+            liquidity_vault_balance.saturating_sub(self.liquidity.available_amount);
+        let collateral_excess = if self.obligation_collateral_tracked != 0 {
+            collateral_vault_balance.saturating_sub(self.obligation_collateral_amount)
+        } else {
+            0
+        };
+        (liquidity_excess, collateral_excess)
+    }
+
+    /// Checks the reserve accounting against the balances of its vaults and collateral mint.
+    /// `check_fee_vault` is false for mints with a transfer fee, whose fee vault receives less
+    /// than the fee ledger books.
+    pub fn audit(
+        &self,
+        liquidity_vault_balance: u64,
+        collateral_vault_balance: u64,
+        collateral_mint_supply: u64,
+        fee_vault_balance: u64,
+        check_fee_vault: bool,
+    ) -> AuditReserveReport {
+        let accumulated_protocol_fees_f =
+            Fraction::from_bits(self.liquidity.accumulated_protocol_fees_sf);
+        let total_liquidity_f = Fraction::from(self.liquidity.available_amount)
+            + Fraction::from_bits(self.liquidity.borrowed_amount_sf);
+
+        // Every fee the ledger booked is either still owed to the protocol, sitting in the fee
+        // vault, or already paid out of it or to the insurance vault
+        let fee_ledger = &self.liquidity.fee_ledger;
+        let booked_protocol_fees_f = fee_ledger.booked_fees();
+        let accounted_protocol_fees_f = accumulated_protocol_fees_f
+            + Fraction::from(fee_vault_balance)
+            + Fraction::from(fee_ledger.withdrawn_fees)
+            + Fraction::from(fee_ledger.insurance_fees);
+
+        AuditReserveReport {
+            liquidity_vault_balance,
+            available_amount: self.liquidity.available_amount,
+            collateral_vault_balance,
+            obligation_collateral_amount: self.obligation_collateral_amount,
+            collateral_mint_supply,
+            collateral_total_supply: self.collateral.mint_total_supply,
+            fee_vault_balance,
+            accumulated_protocol_fees: accumulated_protocol_fees_f.to_ceil(),
+            liquidity_vault_ok: liquidity_vault_balance >= self.liquidity.available_amount,
+            collateral_vault_ok: self.obligation_collateral_tracked == 0
+                || collateral_vault_balance >= self.obligation_collateral_amount,
+            collateral_supply_ok: collateral_mint_supply == self.collateral.mint_total_supply,
+            booked_protocol_fees: booked_protocol_fees_f.to_floor(),
+            protocol_fees_ok: accumulated_protocol_fees_f <= total_liquidity_f,
+            fee_vault_ok: !check_fee_vault || accounted_protocol_fees_f >= booked_protocol_fees_f,
+        }
+    }
+
     pub fn collateral_exchange_rate(&self) -> LendingResult<CollateralExchangeRate> {
         let total_liquidity = self.liquidity.total_supply()?;
         self.collateral.exchange_rate(total_liquidity)
@@ -260,6 +366,14 @@ impl Reserve {
         ))
     }
 
+    /// No debt, no collateral tokens besides the locked seed deposit and no protocol fees left
+    /// to redeem. Whatever is still in the vaults at this point belongs to the protocol.
+    pub fn is_empty(&self, locked_collateral: u64) -> Result<bool> {
+        Ok(self.liquidity.borrowed_amount_sf == 0
+            && self.collateral.mint_total_supply == locked_collateral
+            && self.calculate_redeem_fees()? == 0)
+    }
+
     pub fn deposit_limit_crossed(&self) -> Result<bool> {
         let crossed = self.liquidity.total_supply()? > Fraction::from(self.config.deposit_limit);
         Ok(crossed)
@@ -299,7 +413,16 @@ pub struct ReserveLiquidity {
     pub cumulative_borrow_rate_bsf: BigFractionBytes,
     pub accumulated_protocol_fees_sf: u128,
 
-    pub padding2: [u128; 32],
+    /// Token program owning the liquidity mint, either SPL Token or Token-2022.
+    pub token_program: Pubkey,
+
+    pub fee_ledger: ReserveFeeLedger,
+
+    /// Token account covering bad debt before it is socialized, default until
+    /// `initialize_reserve_insurance`.
+    pub insurance_vault: Pubkey,
+
+    pub padding2: [u128; 24],
 }
 
 impl Default for ReserveLiquidity {
@@ -317,8 +440,11 @@ impl Default for ReserveLiquidity {
             deposit_limit_crossed_slot: 0,
             borrow_limit_crossed_slot: 0,
             market_price_last_updated_ts: 0,
+            token_program: Pubkey::default(),
+            fee_ledger: ReserveFeeLedger::default(),
+            insurance_vault: Pubkey::default(),
             padding: 0,
-            padding2: [0; 32],
+            padding2: [0; 24],
         }
     }
 }
@@ -338,8 +464,21 @@ impl ReserveLiquidity {
             deposit_limit_crossed_slot: 0,
             borrow_limit_crossed_slot: 0,
             market_price_last_updated_ts: 0,
+            token_program: params.token_program,
+            fee_ledger: ReserveFeeLedger::default(),
+            insurance_vault: Pubkey::default(),
             padding: 0,
-            padding2: [0; 32],
+            padding2: [0; 24],
+        }
+    }
+
+    /// Reserves created before Token-2022 support have no token program stored and always
+    /// use SPL Token.
+    pub fn token_program(&self) -> Pubkey {
+        if self.token_program == Pubkey::default() {
+            anchor_spl::token::ID
+        } else {
+            self.token_program
         }
     }
 
@@ -422,6 +561,7 @@ impl ReserveLiquidity {
                 error!(LendingError::MathOverflow)
             })?
             .to_bits();
+        self.fee_ledger.record_redeemed_fees(withdraw_amount);
 
         Ok(())
     }
@@ -459,14 +599,17 @@ impl ReserveLiquidity {
 
         self.cumulative_borrow_rate_bsf = new_cumulative_borrow_rate.into();
         self.accumulated_protocol_fees_sf = new_acc_protocol_fees_f.to_bits();
+        self.fee_ledger.record_interest_fees(total_protocol_fee_f);
         self.borrowed_amount_sf = new_debt_f.to_bits();
 
         Ok(())
     }
 
+    /// Drops debt that will never be repaid. Depositors take the loss through a lower
+    /// exchange rate, since the total supply shrinks by the same amount.
     pub fn forgive_debt(&mut self, liquidity_amount: Fraction) -> LendingResult<()> {
         let amt = Fraction::from_bits(self.borrowed_amount_sf);
-        let new_amt = amt - liquidity_amount;
+        let new_amt = amt - min(liquidity_amount, amt);
         self.borrowed_amount_sf = new_amt.to_bits();
 
         Ok(())
@@ -480,11 +623,70 @@ impl ReserveLiquidity {
 pub struct NewReserveLiquidityParams {
     pub mint_pubkey: Pubkey,
     pub mint_decimals: u8,
+    pub token_program: Pubkey,
     pub supply_vault: Pubkey,
     pub fee_vault: Pubkey,
     pub market_price_sf: u128,
 }
 
+/// Lifetime protocol fees of a reserve by source, and how much of them left the reserve, all in
+/// liquidity tokens. The counters only ever grow.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, PartialEq, Eq)]
+#[zero_copy]
+#[repr(C)]
+pub struct ReserveFeeLedger {
+    /// Protocol take rate on accrued interest, scaled like `accumulated_protocol_fees_sf`.
+    pub interest_fees_sf: u128,
+    pub origination_fees: u64,
+    pub flash_loan_fees: u64,
+    pub liquidation_fees: u64,
+    /// Interest fees moved from the supply vault to the fee vault by `redeem_fees`.
+    pub redeemed_fees: u64,
+    /// Fees sent out of the fee vault by `withdraw_protocol_fees`.
+    pub withdrawn_fees: u64,
+    /// Part of the redeemed interest and liquidation fees paid into the insurance vault, net of
+    /// the mint's transfer fee.
+    pub insurance_fees: u64,
+}
+
+impl ReserveFeeLedger {
+    /// Every protocol fee recorded so far, redeemed or not.
+    pub fn booked_fees(&self) -> Fraction {
+        Fraction::from_bits(self.interest_fees_sf)
+            + Fraction::from(self.origination_fees)
+            + Fraction::from(self.flash_loan_fees)
+            + Fraction::from(self.liquidation_fees)
+    }
+
+    pub fn record_interest_fees(&mut self, fees: Fraction) {
+        self.interest_fees_sf = self.interest_fees_sf.saturating_add(fees.to_bits());
+    }
+
+    pub fn record_origination_fees(&mut self, fees: u64) {
+        self.origination_fees = self.origination_fees.saturating_add(fees);
+    }
+
+    pub fn record_flash_loan_fees(&mut self, fees: u64) {
+        self.flash_loan_fees = self.flash_loan_fees.saturating_add(fees);
+    }
+
+    pub fn record_liquidation_fees(&mut self, fees: u64) {
+        self.liquidation_fees = self.liquidation_fees.saturating_add(fees);
+    }
+
+    pub fn record_redeemed_fees(&mut self, amount: u64) {
+        self.redeemed_fees = self.redeemed_fees.saturating_add(amount);
+    }
+
+    pub fn record_withdrawn_fees(&mut self, amount: u64) {
+        self.withdrawn_fees = self.withdrawn_fees.saturating_add(amount);
+    }
+
+    pub fn record_insurance_fees(&mut self, amount: u64) {
+        self.insurance_fees = self.insurance_fees.saturating_add(amount);
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 #[zero_copy]
 #[repr(C)]
@@ -582,9 +784,12 @@ pub struct ReserveConfig {
     pub min_liquidation_bonus_bps: u16,
     pub max_liquidation_bonus_bps: u16,
     pub bad_debt_liquidation_bonus_bps: u16,
+    /// Share of the protocol interest and liquidation fees paid into the insurance vault
+    /// instead of the fee vault, once the reserve has one.
+    pub insurance_fee_pct: u8,
 
     #[derivative(Debug = "ignore")]
-    pub padding_0: [u8; 4],
+    pub padding_0: [u8; 3],
 
     pub deleveraging_margin_call_period_secs: u64,
     pub deleveraging_threshold_slots_per_bps: u64,
@@ -603,7 +808,16 @@ pub struct ReserveConfig {
     #[derivative(Debug = "ignore")]
     pub padding_2: [u8; 7],
 
-    pub reserved: [u64; 32],
+    pub loan_to_value_ramp: ParameterRamp,
+    pub liquidation_threshold_ramp: ParameterRamp,
+
+    /// [`ReservePauseFlags`] of the operations currently paused on this reserve
+    pub paused_operations: u16,
+
+    #[derivative(Debug = "ignore")]
+    pub padding_3: [u8; 6],
+
+    pub reserved: [u64; 25],
 }
 
 impl ReserveConfig {
@@ -621,6 +835,111 @@ impl ReserveConfig {
     pub fn status(&self) -> ReserveStatus {
         ReserveStatus::try_from(self.status).unwrap()
     }
+
+    pub fn paused_operations(&self) -> ReservePauseFlags {
+        ReservePauseFlags::from_bits_retain(self.paused_operations)
+    }
+
+    /// Moves `loan_to_value_pct` and `liquidation_threshold_pct` along their active ramps.
+    /// LTV is capped by the liquidation threshold while either ramp runs, so ramps with
+    /// different schedules cannot cross.
+    pub fn apply_ramps(&mut self, timestamp: u64) {
+        if !self.loan_to_value_ramp.is_active() && !self.liquidation_threshold_ramp.is_active() {
+            return;
+        }
+
+        if let Some(value) = self.loan_to_value_ramp.advance(timestamp) {
+            self.loan_to_value_pct = value;
+        }
+        if let Some(value) = self.liquidation_threshold_ramp.advance(timestamp) {
+            self.liquidation_threshold_pct = value;
+        }
+        self.loan_to_value_pct = min(self.loan_to_value_pct, self.liquidation_threshold_pct);
+    }
+}
+
+/// Operations that can be paused on a single reserve. Liquidations redeem the seized
+/// collateral, so pausing `REDEEM` on a reserve also stops liquidations seizing from it.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[repr(transparent)]
+pub struct ReservePauseFlags(pub u16);
+
+#[rustfmt::skip]
+bitflags! {
+    impl ReservePauseFlags: u16 {
+        const DEPOSIT =             0b_0000_0001;
+        const REDEEM =              0b_0000_0010;
+        const BORROW =              0b_0000_0100;
+        const REPAY =               0b_0000_1000;
+        const DEPOSIT_COLLATERAL =  0b_0001_0000;
+        const WITHDRAW_COLLATERAL = 0b_0010_0000;
+        const LIQUIDATION =         0b_0100_0000;
+        const FLASH_LOAN =          0b_1000_0000;
+    }
+}
+
+impl ReservePauseFlags {
+    /// Operations that let users and liquidators reduce exposure. The guardian cannot pause
+    /// them, only the owner can.
+    pub const DELEVERAGING: Self = Self::REPAY.union(Self::LIQUIDATION);
+}
+
+/// Linear move of a percentage parameter from `start_value` to `target_value` over
+/// `duration_secs`, beginning at `start_ts`. Inactive while `duration_secs` is zero.
+#[derive(BorshDeserialize, BorshSerialize, PartialEq, Eq, Default, Debug)]
+#[zero_copy]
+#[repr(C)]
+pub struct ParameterRamp {
+    pub start_ts: u64,
+    pub duration_secs: u64,
+    pub start_value: u8,
+    pub target_value: u8,
+    pub padding: [u8; 6],
+}
+
+impl ParameterRamp {
+    pub fn new(start_value: u8, target_value: u8, start_ts: u64, duration_secs: u64) -> Self {
+        Self {
+            start_ts,
+            duration_secs,
+            start_value,
+            target_value,
+            padding: [0; 6],
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.duration_secs > 0
+    }
+
+    pub fn value_at(&self, timestamp: u64) -> u8 {
+        if !self.is_active() {
+            return self.target_value;
+        }
+
+        let elapsed = timestamp
+            .saturating_sub(self.start_ts)
+            .min(self.duration_secs);
+        let start = i128::from(self.start_value);
+        let delta = i128::from(self.target_value) - start;
+        let value = start + delta * i128::from(elapsed) / i128::from(self.duration_secs);
+
+        u8::try_from(value).unwrap()
+    }
+
+    /// Returns the value at `timestamp` for an active ramp, and deactivates the ramp once
+    /// it reached its target.
+    fn advance(&mut self, timestamp: u64) -> Option<u8> {
+        if !self.is_active() {
+            return None;
+        }
+
+        let value = self.value_at(timestamp);
+        if timestamp.saturating_sub(self.start_ts) >= self.duration_secs {
+            *self = Self::default();
+        }
+        Some(value)
+    }
 }
 
 #[repr(u8)]
@@ -639,6 +958,16 @@ pub enum ReserveStatus {
     Active = 0,
     Obsolete = 1,
     Hidden = 2,
+    /// Only repay, withdraw, redeem, liquidation and flash loans, for assets being retired
+    ReduceOnly = 3,
+}
+
+impl ReserveStatus {
+    /// Obsolete and reduce-only reserves take no new exposure, and obligations holding their
+    /// collateral have to withdraw it before any active collateral.
+    pub fn is_winding_down(self) -> bool {
+        matches!(self, Self::Obsolete | Self::ReduceOnly)
+    }
 }
 
 #[derive(BorshDeserialize, BorshSerialize, PartialEq, Eq, Default, Debug)]
@@ -651,6 +980,19 @@ pub struct WithdrawalCaps {
     pub config_interval_length_seconds: u64,
 }
 
+impl WithdrawalCaps {
+    /// Whether switching to `capacity` per `interval_length_seconds` allows no more withdrawals
+    /// than the current config. A zero interval disables the cap.
+    pub fn is_tightened_by(&self, capacity: i64, interval_length_seconds: u64) -> bool {
+        if interval_length_seconds == 0 {
+            return self.config_interval_length_seconds == 0;
+        }
+        self.config_interval_length_seconds == 0
+            || (capacity <= self.config_capacity
+                && interval_length_seconds >= self.config_interval_length_seconds)
+    }
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Default, PartialEq, Eq, Derivative)]
 #[derivative(Debug)]
 #[zero_copy]
@@ -725,6 +1067,8 @@ pub enum FeeCalculation {
     Debug,
     PartialEq,
     Eq,
+    Clone,
+    Copy,
     num_enum::IntoPrimitive,
     num_enum::TryFromPrimitive,
 )]
@@ -807,3 +1151,217 @@ pub enum UpdateConfigMode {
     DeleveragingThresholdSlotsPerBps = 36,
     UpdateReserveStatus = 39,
 }
+
+/// Typed counterpart of [`UpdateConfigMode`], one variant per config field.
+#[allow(clippy::large_enum_variant)]
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Clone, Debug)]
+pub enum ReserveConfigUpdate {
+    LoanToValuePct(u8),
+    MaxLiquidationBonusBps(u16),
+    LiquidationThresholdPct(u8),
+    ProtocolLiquidationFeePct(u8),
+    ProtocolTakeRatePct(u8),
+    BorrowFeeSf(u64),
+    FlashLoanFeeSf(u64),
+    DepositLimit(u64),
+    BorrowLimit(u64),
+    TokenInfoTwapDivergenceBps(u64),
+    TokenInfoName([u8; 32]),
+    TokenInfoPriceMaxAgeSeconds(u64),
+    TokenInfoTwapMaxAgeSeconds(u64),
+    PythPrice(Pubkey),
+    BorrowRateCurve(BorrowRateCurve),
+    EntireReserveConfig(ReserveConfig),
+    DebtWithdrawalCap {
+        capacity: i64,
+        interval_length_seconds: u64,
+    },
+    DepositWithdrawalCap {
+        capacity: i64,
+        interval_length_seconds: u64,
+    },
+    DebtWithdrawalCapCurrentTotal(i64),
+    DepositWithdrawalCapCurrentTotal(i64),
+    BadDebtLiquidationBonusBps(u16),
+    MinLiquidationBonusBps(u16),
+    DeleveragingMarginCallPeriodSecs(u64),
+    BorrowFactorPct(u64),
+    AssetTier(AssetTier),
+    DeleveragingThresholdSlotsPerBps(u64),
+    ReserveStatus(ReserveStatus),
+    /// Replaces the set of paused operations, see [`ReservePauseFlags`]
+    PausedOperations(u16),
+    InsuranceFeePct(u8),
+    /// Moves `loan_to_value_pct` to `target_pct` linearly, see [`ParameterRamp`]
+    LoanToValueRamp {
+        target_pct: u8,
+        start_ts: u64,
+        duration_secs: u64,
+    },
+    /// Moves `liquidation_threshold_pct` to `target_pct` linearly, see [`ParameterRamp`]
+    LiquidationThresholdRamp {
+        target_pct: u8,
+        start_ts: u64,
+        duration_secs: u64,
+    },
+}
+
+impl ReserveConfigUpdate {
+    /// Whether the guardian may apply this update, i.e. it can only make `config` safer.
+    pub fn is_tightening(&self, config: &ReserveConfig) -> bool {
+        match *self {
+            Self::DepositLimit(limit) => limit <= config.deposit_limit,
+            Self::BorrowLimit(limit) => limit <= config.borrow_limit,
+            Self::ReserveStatus(status) => match status {
+                ReserveStatus::Obsolete => true,
+                ReserveStatus::ReduceOnly => config.status() != ReserveStatus::Obsolete,
+                _ => status == config.status(),
+            },
+            Self::PausedOperations(paused) => {
+                let newly_paused = paused & !config.paused_operations;
+                paused & config.paused_operations == config.paused_operations
+                    && newly_paused & ReservePauseFlags::DELEVERAGING.bits() == 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the owner may apply this update without going through the config timelock.
+    /// Broader than [`Self::is_tightening`]: lowering LTV or the liquidation threshold and
+    /// lowering caps make the reserve safer but are not left to the guardian, and resuming
+    /// paused operations must not wait.
+    pub fn is_timelock_exempt(&self, config: &ReserveConfig) -> bool {
+        if self.is_tightening(config) {
+            return true;
+        }
+        match *self {
+            Self::LoanToValuePct(pct) => pct <= config.loan_to_value_pct,
+            Self::LiquidationThresholdPct(pct) => pct <= config.liquidation_threshold_pct,
+            Self::LoanToValueRamp { target_pct, .. } => target_pct <= config.loan_to_value_pct,
+            Self::LiquidationThresholdRamp { target_pct, .. } => {
+                target_pct <= config.liquidation_threshold_pct
+            }
+            Self::PausedOperations(paused) => paused & config.paused_operations == paused,
+            Self::DebtWithdrawalCap {
+                capacity,
+                interval_length_seconds,
+            } => config
+                .debt_withdrawal_cap
+                .is_tightened_by(capacity, interval_length_seconds),
+            Self::DepositWithdrawalCap {
+                capacity,
+                interval_length_seconds,
+            } => config
+                .deposit_withdrawal_cap
+                .is_tightened_by(capacity, interval_length_seconds),
+            _ => false,
+        }
+    }
+
+    /// Decodes the legacy `mode` + byte buffer encoding used by `update_reserve_mode` and
+    /// `update_reserve`. Returns `None` for `UpdateFeesReferralFeeBps`, which is a no-op.
+    pub fn from_mode_bytes(mode: UpdateConfigMode, value: &[u8]) -> Result<Option<Self>> {
+        fn bytes<const N: usize>(value: &[u8]) -> Result<[u8; N]> {
+            value
+                .get(..N)
+                .and_then(|v| v.try_into().ok())
+                .ok_or_else(|| error!(LendingError::InvalidConfig))
+        }
+        let u8_value = || bytes::<1>(value).map(|v| v[0]);
+        let u16_value = || bytes::<2>(value).map(u16::from_le_bytes);
+        let u64_value = || bytes::<8>(value).map(u64::from_le_bytes);
+        let i64_value = || bytes::<8>(value).map(i64::from_le_bytes);
+        fn borsh_value<T: BorshDeserialize>(mut value: &[u8]) -> Result<T> {
+            T::deserialize(&mut value).map_err(|_| error!(LendingError::InvalidConfig))
+        }
+        let withdrawal_cap = || -> Result<(i64, u64)> {
+            let capacity = i64_value()?;
+            let interval_length_seconds = u64::from_le_bytes(
+                value
+                    .get(8..16)
+                    .and_then(|v| v.try_into().ok())
+                    .ok_or_else(|| error!(LendingError::InvalidConfig))?,
+            );
+            Ok((capacity, interval_length_seconds))
+        };
+
+        let update = match mode {
+            UpdateConfigMode::UpdateLoanToValuePct => Self::LoanToValuePct(u8_value()?),
+            UpdateConfigMode::UpdateMaxLiquidationBonusBps => {
+                Self::MaxLiquidationBonusBps(u16_value()?)
+            }
+            UpdateConfigMode::UpdateLiquidationThresholdPct => {
+                Self::LiquidationThresholdPct(u8_value()?)
+            }
+            UpdateConfigMode::UpdateProtocolLiquidationFee => {
+                Self::ProtocolLiquidationFeePct(u8_value()?)
+            }
+            UpdateConfigMode::UpdateProtocolTakeRate => Self::ProtocolTakeRatePct(u8_value()?),
+            UpdateConfigMode::UpdateFeesBorrowFee => Self::BorrowFeeSf(u64_value()?),
+            UpdateConfigMode::UpdateFeesFlashLoanFee => Self::FlashLoanFeeSf(u64_value()?),
+            UpdateConfigMode::UpdateFeesReferralFeeBps => return Ok(None),
+            UpdateConfigMode::UpdateDepositLimit => Self::DepositLimit(u64_value()?),
+            UpdateConfigMode::UpdateBorrowLimit => Self::BorrowLimit(u64_value()?),
+            UpdateConfigMode::UpdateTokenInfoTwapDivergence => {
+                Self::TokenInfoTwapDivergenceBps(u64_value()?)
+            }
+            UpdateConfigMode::UpdateTokenInfoName => Self::TokenInfoName(bytes::<32>(value)?),
+            UpdateConfigMode::UpdateTokenInfoPriceMaxAge => {
+                Self::TokenInfoPriceMaxAgeSeconds(u64_value()?)
+            }
+            UpdateConfigMode::UpdateTokenInfoTwapMaxAge => {
+                Self::TokenInfoTwapMaxAgeSeconds(u64_value()?)
+            }
+            UpdateConfigMode::UpdatePythPrice => {
+                Self::PythPrice(Pubkey::new_from_array(bytes::<32>(value)?))
+            }
+            UpdateConfigMode::UpdateBorrowRateCurve => Self::BorrowRateCurve(borsh_value(value)?),
+            UpdateConfigMode::UpdateEntireReserveConfig => {
+                Self::EntireReserveConfig(borsh_value(value)?)
+            }
+            UpdateConfigMode::UpdateDebtWithdrawalCap => {
+                let (capacity, interval_length_seconds) = withdrawal_cap()?;
+                Self::DebtWithdrawalCap {
+                    capacity,
+                    interval_length_seconds,
+                }
+            }
+            UpdateConfigMode::UpdateDepositWithdrawalCap => {
+                let (capacity, interval_length_seconds) = withdrawal_cap()?;
+                Self::DepositWithdrawalCap {
+                    capacity,
+                    interval_length_seconds,
+                }
+            }
+            UpdateConfigMode::UpdateDebtWithdrawalCapCurrentTotal => {
+                Self::DebtWithdrawalCapCurrentTotal(i64_value()?)
+            }
+            UpdateConfigMode::UpdateDepositWithdrawalCapCurrentTotal => {
+                Self::DepositWithdrawalCapCurrentTotal(i64_value()?)
+            }
+            UpdateConfigMode::UpdateBadDebtLiquidationBonusBps => {
+                Self::BadDebtLiquidationBonusBps(u16_value()?)
+            }
+            UpdateConfigMode::UpdateMinLiquidationBonusBps => {
+                Self::MinLiquidationBonusBps(u16_value()?)
+            }
+            UpdateConfigMode::DeleveragingMarginCallPeriod => {
+                Self::DeleveragingMarginCallPeriodSecs(u64_value()?)
+            }
+            UpdateConfigMode::UpdateBorrowFactor => Self::BorrowFactorPct(u64_value()?),
+            UpdateConfigMode::UpdateAssetTier => Self::AssetTier(
+                AssetTier::try_from(u8_value()?)
+                    .map_err(|_| error!(LendingError::InvalidConfig))?,
+            ),
+            UpdateConfigMode::DeleveragingThresholdSlotsPerBps => {
+                Self::DeleveragingThresholdSlotsPerBps(u64_value()?)
+            }
+            UpdateConfigMode::UpdateReserveStatus => Self::ReserveStatus(
+                ReserveStatus::try_from(u8_value()?)
+                    .map_err(|_| error!(LendingError::InvalidConfig))?,
+            ),
+        };
+
+        Ok(Some(update))
+    }
+}
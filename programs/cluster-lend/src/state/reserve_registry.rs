@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::MAX_RESERVES_PER_MARKET, errors::LendingError, ReserveStatus};
+
+static_assertions::const_assert_eq!(0, std::mem::size_of::<ReserveRegistry>() % 8);
+/// Every reserve of a lending market, in creation order. Lives at the
+/// `[RESERVE_REGISTRY, lending_market]` PDA so clients can list reserves without scanning
+/// program accounts.
+#[derive(Debug, PartialEq, Eq)]
+#[account(zero_copy)]
+#[repr(C)]
+pub struct ReserveRegistry {
+    pub lending_market: Pubkey,
+    pub reserves_count: u64,
+    pub entries: [ReserveRegistryEntry; MAX_RESERVES_PER_MARKET],
+    pub padding: [u64; 16],
+}
+
+impl Default for ReserveRegistry {
+    fn default() -> Self {
+        Self {
+            lending_market: Pubkey::default(),
+            reserves_count: 0,
+            entries: [ReserveRegistryEntry::default(); MAX_RESERVES_PER_MARKET],
+            padding: [0; 16],
+        }
+    }
+}
+
+impl ReserveRegistry {
+    pub fn init(&mut self, lending_market: Pubkey) {
+        *self = Self::default();
+        self.lending_market = lending_market;
+    }
+
+    pub fn reserves(&self) -> &[ReserveRegistryEntry] {
+        &self.entries[..self.reserves_count as usize]
+    }
+
+    pub fn add_reserve(
+        &mut self,
+        reserve: Pubkey,
+        mint: Pubkey,
+        status: ReserveStatus,
+        creation_slot: u64,
+    ) -> Result<()> {
+        if self.reserves().iter().any(|entry| entry.reserve == reserve) {
+            msg!("Reserve {} is already registered", reserve);
+            return err!(LendingError::InvalidAccountInput);
+        }
+
+        let index = self.reserves_count as usize;
+        let entry = self
+            .entries
+            .get_mut(index)
+            .ok_or_else(|| error!(LendingError::ReserveRegistryFull))?;
+        *entry = ReserveRegistryEntry {
+            reserve,
+            mint,
+            creation_slot,
+            status: status.into(),
+            padding: [0; 7],
+        };
+        self.reserves_count += 1;
+
+        Ok(())
+    }
+
+    pub fn set_status(&mut self, reserve: &Pubkey, status: ReserveStatus) -> Result<()> {
+        let count = self.reserves_count as usize;
+        let entry = self.entries[..count]
+            .iter_mut()
+            .find(|entry| entry.reserve == *reserve)
+            .ok_or_else(|| error!(LendingError::ReserveNotRegistered))?;
+        entry.status = status.into();
+
+        Ok(())
+    }
+
+    /// Drops a closed reserve, keeping the remaining entries in creation order.
+    pub fn remove_reserve(&mut self, reserve: &Pubkey) -> Result<()> {
+        let count = self.reserves_count as usize;
+        let index = self.entries[..count]
+            .iter()
+            .position(|entry| entry.reserve == *reserve)
+            .ok_or_else(|| error!(LendingError::ReserveNotRegistered))?;
+        self.entries.copy_within(index + 1..count, index);
+        self.entries[count - 1] = ReserveRegistryEntry::default();
+        self.reserves_count -= 1;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+#[zero_copy]
+#[repr(C)]
+pub struct ReserveRegistryEntry {
+    pub reserve: Pubkey,
+    pub mint: Pubkey,
+    pub creation_slot: u64,
+    pub status: u8,
+    pub padding: [u8; 7],
+}
+
+impl ReserveRegistryEntry {
+    pub fn status(&self) -> ReserveStatus {
+        ReserveStatus::try_from(self.status).unwrap()
+    }
+}
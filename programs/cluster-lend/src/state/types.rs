@@ -1,3 +1,5 @@
+use anchor_lang::prelude::*;
+
 use crate::utils::Fraction;
 
 use super::PriceStatusFlags;
@@ -14,6 +16,14 @@ pub struct CalculateRepayResult {
     pub repay_amount: u64,
 }
 
+#[derive(Debug)]
+pub struct SocializeLossResult {
+    /// Liquidity to move from the insurance vault to the supply vault
+    pub insurance_amount: u64,
+    /// Debt written off against depositors
+    pub socialized_amount_f: Fraction,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CalculateLiquidationResult {
     pub settle_amount_f: Fraction,
@@ -36,6 +46,129 @@ pub struct LiquidateAndRedeemResult {
     pub withdraw_amount: u64,
     pub withdraw_collateral_amount: u64,
     pub total_withdraw_liquidity_amount: Option<(u64, u64)>,
+    pub liquidation_bonus_rate: Fraction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidateWithoutRedeemResult {
+    pub repay_amount: u64,
+    pub withdraw_amount: u64,
+    pub protocol_fee_collateral_amount: u64,
+    pub protocol_fee: u64,
+    /// Part of `protocol_fee` to move from the supply vault to the insurance vault.
+    pub insurance_fee: u64,
+    pub liquidation_bonus_rate: Fraction,
+}
+
+/// Return data of `borrow_obligation_liquidity`.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BorrowObligationLiquidityReturn {
+    pub borrow_amount_sf: u128,
+    pub receive_amount: u64,
+    pub borrow_fee: u64,
+}
+
+impl From<&CalculateBorrowResult> for BorrowObligationLiquidityReturn {
+    fn from(result: &CalculateBorrowResult) -> Self {
+        Self {
+            borrow_amount_sf: result.borrow_amount_f.to_bits(),
+            receive_amount: result.receive_amount,
+            borrow_fee: result.borrow_fee,
+        }
+    }
+}
+
+/// Return data of `liquidate_obligation`.
+///
+/// `withdraw_liquidity_amount` and `protocol_fee` are zero when the seized collateral was
+/// not redeemed and the liquidator kept the cTokens instead.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LiquidateObligationReturn {
+    pub repay_amount: u64,
+    pub withdraw_amount: u64,
+    pub withdraw_collateral_amount: u64,
+    pub withdraw_liquidity_amount: u64,
+    pub protocol_fee: u64,
+    pub liquidation_bonus_rate_sf: u128,
+}
+
+impl From<&LiquidateAndRedeemResult> for LiquidateObligationReturn {
+    fn from(result: &LiquidateAndRedeemResult) -> Self {
+        let (withdraw_liquidity_amount, protocol_fee) =
+            result.total_withdraw_liquidity_amount.unwrap_or_default();
+        Self {
+            repay_amount: result.repay_amount,
+            withdraw_amount: result.withdraw_amount,
+            withdraw_collateral_amount: result.withdraw_collateral_amount,
+            withdraw_liquidity_amount,
+            protocol_fee,
+            liquidation_bonus_rate_sf: result.liquidation_bonus_rate.to_bits(),
+        }
+    }
+}
+
+/// Nothing is redeemed: `withdraw_collateral_amount` is what the liquidator keeps of the seized
+/// cTokens after the fee burn, and `protocol_fee` is the liquidity the burnt cTokens stood for.
+impl From<&LiquidateWithoutRedeemResult> for LiquidateObligationReturn {
+    fn from(result: &LiquidateWithoutRedeemResult) -> Self {
+        Self {
+            repay_amount: result.repay_amount,
+            withdraw_amount: result.withdraw_amount,
+            withdraw_collateral_amount: result.withdraw_amount
+                - result.protocol_fee_collateral_amount,
+            withdraw_liquidity_amount: 0,
+            protocol_fee: result.protocol_fee,
+            liquidation_bonus_rate_sf: result.liquidation_bonus_rate.to_bits(),
+        }
+    }
+}
+
+/// Return data of `flash_repay_reserve_liquidity`.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FlashRepayReserveLiquidityReturn {
+    pub flash_loan_amount: u64,
+    pub origination_fee: u64,
+}
+
+/// Return data of `audit_reserve`. The `*_ok` flags are the accounting invariants, the other
+/// fields the values they were checked on.
+///
+/// Unredeemed `accumulated_protocol_fees` must be backed by the reserve's total liquidity, and
+/// together with the fee vault they must cover every fee the reserve's fee ledger booked.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AuditReserveReport {
+    pub liquidity_vault_balance: u64,
+    pub available_amount: u64,
+    pub collateral_vault_balance: u64,
+    pub obligation_collateral_amount: u64,
+    pub collateral_mint_supply: u64,
+    pub collateral_total_supply: u64,
+    pub fee_vault_balance: u64,
+    pub accumulated_protocol_fees: u64,
+    /// Protocol fees recorded in the reserve's fee ledger.
+    pub booked_protocol_fees: u64,
+    /// Liquidity vault balance >= `available_amount`.
+    pub liquidity_vault_ok: bool,
+    /// Collateral vault balance >= `obligation_collateral_amount`. Always set on reserves that
+    /// do not track obligation collateral.
+    pub collateral_vault_ok: bool,
+    /// Collateral mint supply == `mint_total_supply`.
+    pub collateral_supply_ok: bool,
+    /// `accumulated_protocol_fees` <= `available_amount` + borrowed amount.
+    pub protocol_fees_ok: bool,
+    /// `fee_vault_balance` + `accumulated_protocol_fees` + fees withdrawn or paid to insurance
+    /// >= `booked_protocol_fees`. Always set on mints with a transfer fee.
+    pub fee_vault_ok: bool,
+}
+
+impl AuditReserveReport {
+    pub fn is_healthy(&self) -> bool {
+        self.liquidity_vault_ok
+            && self.collateral_vault_ok
+            && self.collateral_supply_ok
+            && self.protocol_fees_ok
+            && self.fee_vault_ok
+    }
 }
 
 pub struct LiquidationParams {
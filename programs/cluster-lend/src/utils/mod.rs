@@ -4,11 +4,13 @@ pub mod borrow_rate_curve;
 pub mod constraints;
 pub mod fraction;
 pub mod macros;
+pub mod native_sol;
 pub mod prices;
 pub mod refresh_ix_utils;
 pub mod seeds;
 pub mod slots;
 pub mod spltoken;
+pub mod token_2022;
 pub mod token_transfer;
 pub mod validation;
 
@@ -17,11 +19,12 @@ pub use account_ops::*;
 pub use borrow_rate_curve::*;
 pub use constraints::*;
 pub use fraction::*;
-pub use macros::*;
+pub use native_sol::*;
 pub use prices::*;
 pub use refresh_ix_utils::*;
 pub use seeds::*;
 pub use slots::*;
 pub use spltoken::*;
+pub use token_2022::*;
 pub use token_transfer::*;
 pub use validation::*;
@@ -0,0 +1,144 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, spl_token, TokenAccount};
+
+use crate::{errors::LendingError, utils::seeds};
+
+/// Temporary wrapped SOL account used when a user moves native lamports in or out of a wSOL
+/// reserve. The account is a PDA of the owner, created and closed within a single instruction.
+pub struct NativeSolAccounts<'info> {
+    owner: AccountInfo<'info>,
+    user_wsol: AccountInfo<'info>,
+    native_mint: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    bump: u8,
+}
+
+impl<'info> NativeSolAccounts<'info> {
+    pub fn new(
+        owner: AccountInfo<'info>,
+        user_wsol: Option<&UncheckedAccount<'info>>,
+        native_mint: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+        system_program: Option<&Program<'info, System>>,
+    ) -> Result<Self> {
+        let (Some(user_wsol), Some(system_program)) = (user_wsol, system_program) else {
+            msg!("Native SOL transfers need the user wSOL account and the system program");
+            return err!(LendingError::InvalidNativeSolAccounts);
+        };
+
+        if native_mint.key() != spl_token::native_mint::ID || token_program.key() != token::ID {
+            msg!("Native SOL can only be used with a wrapped SOL reserve");
+            return err!(LendingError::InvalidNativeSolAccounts);
+        }
+
+        let (expected_user_wsol, bump) =
+            Pubkey::find_program_address(&[seeds::USER_WSOL, owner.key.as_ref()], &crate::ID);
+        if user_wsol.key() != expected_user_wsol {
+            msg!("User wSOL account does not match the owner PDA");
+            return err!(LendingError::InvalidNativeSolAccounts);
+        }
+
+        Ok(Self {
+            owner,
+            user_wsol: user_wsol.to_account_info(),
+            native_mint,
+            token_program,
+            system_program: system_program.to_account_info(),
+            bump,
+        })
+    }
+
+    pub fn user_wsol(&self) -> AccountInfo<'info> {
+        self.user_wsol.clone()
+    }
+
+    /// Creates the wSOL account funded with `lamports` from the owner on top of rent, so its
+    /// token balance is at least `lamports` once initialized. The PDA is predictable, so it may
+    /// already hold lamports sent by anyone; it is then topped up, allocated and assigned
+    /// instead, since `create_account` refuses funded accounts.
+    pub fn open(&self, lamports: u64) -> Result<()> {
+        let rent = Rent::get()?.minimum_balance(TokenAccount::LEN);
+        let required_lamports = rent
+            .checked_add(lamports)
+            .ok_or_else(|| error!(LendingError::MathOverflow))?;
+        let owner_key = self.owner.key();
+        let signer_seeds: &[&[u8]] = &[seeds::USER_WSOL, owner_key.as_ref(), &[self.bump]];
+
+        let current_lamports = self.user_wsol.lamports();
+        if current_lamports == 0 {
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    self.system_program.clone(),
+                    system_program::CreateAccount {
+                        from: self.owner.clone(),
+                        to: self.user_wsol.clone(),
+                    },
+                    &[signer_seeds],
+                ),
+                required_lamports,
+                TokenAccount::LEN as u64,
+                &token::ID,
+            )?;
+        } else {
+            let top_up = required_lamports.saturating_sub(current_lamports);
+            if top_up > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        self.system_program.clone(),
+                        system_program::Transfer {
+                            from: self.owner.clone(),
+                            to: self.user_wsol.clone(),
+                        },
+                    ),
+                    top_up,
+                )?;
+            }
+
+            system_program::allocate(
+                CpiContext::new_with_signer(
+                    self.system_program.clone(),
+                    system_program::Allocate {
+                        account_to_allocate: self.user_wsol.clone(),
+                    },
+                    &[signer_seeds],
+                ),
+                TokenAccount::LEN as u64,
+            )?;
+
+            system_program::assign(
+                CpiContext::new_with_signer(
+                    self.system_program.clone(),
+                    system_program::Assign {
+                        account_to_assign: self.user_wsol.clone(),
+                    },
+                    &[signer_seeds],
+                ),
+                &token::ID,
+            )?;
+        }
+
+        token::initialize_account3(CpiContext::new(
+            self.token_program.clone(),
+            token::InitializeAccount3 {
+                account: self.user_wsol.clone(),
+                mint: self.native_mint.clone(),
+                authority: self.owner.clone(),
+            },
+        ))?;
+
+        Ok(())
+    }
+
+    /// Closes the wSOL account, returning its rent and any wrapped balance as lamports.
+    pub fn close(&self) -> Result<()> {
+        token::close_account(CpiContext::new(
+            self.token_program.clone(),
+            token::CloseAccount {
+                account: self.user_wsol.clone(),
+                destination: self.owner.clone(),
+                authority: self.owner.clone(),
+            },
+        ))
+    }
+}
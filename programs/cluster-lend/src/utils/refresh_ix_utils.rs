@@ -1,33 +1,24 @@
-use anchor_lang::{prelude::*, solana_program::log::sol_log_compute_units, Discriminator};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, log::sol_log_compute_units},
+    Discriminator,
+};
 
 use crate::{
     errors::LendingError,
-    instruction::{RefreshObligation, RefreshReserve},
+    instruction::{RefreshObligation, RefreshReserve, RefreshReservesBatch},
     lending_market::ix_utils::{BpfInstructionLoader, InstructionLoader},
     Reserve,
 };
 
-#[derive(Debug, Clone)]
-pub enum RequiredIxType {
-    RefreshReserve,
-    RefreshObligation,
-}
-
-#[derive(Debug, Clone)]
-pub struct RequiredIx {
-    pub kind: RequiredIxType,
-    pub accounts: Vec<(Pubkey, usize)>,
-}
-
-impl RequiredIx {
-    pub fn discriminator(&self) -> [u8; 8] {
-        match self.kind {
-            RequiredIxType::RefreshReserve => RefreshReserve::DISCRIMINATOR,
-            RequiredIxType::RefreshObligation => RefreshObligation::DISCRIMINATOR,
-        }
-    }
-}
-
+/// Checks that the obligation and the given reserves were refreshed earlier in the
+/// transaction, in the order `refresh_reserve`s, then `refresh_obligation`, then the current
+/// instruction.
+///
+/// The refreshes do not need to be adjacent to the current instruction. Any instruction in
+/// between is accepted as long as it does not list one of the refreshed accounts as writable,
+/// which rules out both this program's mutating instructions and other programs CPI-ing into
+/// it with those accounts.
 pub fn check_refresh(
     instruction_sysvar_account_info: &AccountInfo,
     reserves: &[(Pubkey, &Reserve)],
@@ -39,104 +30,123 @@ pub fn check_refresh(
     let ix_loader = BpfInstructionLoader {
         instruction_sysvar_account_info,
     };
+    let reserves = reserves
+        .iter()
+        .map(|(reserve, _)| *reserve)
+        .collect::<Vec<_>>();
 
-    let current_idx: usize = ix_loader.load_current_index().unwrap().into();
-    let check_ixns = |required_ixns: Vec<RequiredIx>, ix_type: AppendedIxType| -> Result<()> {
-        for (i, required_ix) in required_ixns.iter().enumerate() {
-            let offset = match ix_type {
-                AppendedIxType::PreIxs => current_idx.checked_sub(i + 1).ok_or_else(|| {
-                    msg!(
-                        "current_idx: {}, i: {}, required_ix {:?}",
-                        current_idx,
-                        i,
-                        required_ix
-                    );
-                    error!(LendingError::IncorrectInstructionInPosition)
-                })?,
-                AppendedIxType::PostIxs => current_idx.checked_add(i + 1).ok_or_else(|| {
-                    msg!(
-                        "current_idx: {}, i: {}, required_ix {:?}",
-                        current_idx,
-                        i,
-                        required_ix
-                    );
-                    error!(LendingError::IncorrectInstructionInPosition)
-                })?,
-            };
-
-            let ix = ix_loader
-                .load_instruction_at(offset)
-                .map_err(|_| LendingError::IncorrectInstructionInPosition)?;
-
-            let ix_discriminator: [u8; 8] = ix.data[0..8].try_into().unwrap();
-
-            require_keys_eq!(ix.program_id, crate::id());
-
-            let ix_discriminator_matches = ix_discriminator == required_ix.discriminator();
-            if !ix_discriminator_matches {
-                for (i, ix) in required_ixns.iter().enumerate() {
-                    msg!("Required ix: {} {:?}", i, ix);
-                }
-            }
-
-            require!(
-                ix_discriminator_matches,
-                LendingError::IncorrectInstructionInPosition
-            );
+    check_refresh_with_loader(&ix_loader, &reserves, obligation_address)?;
 
-            for (key, index) in required_ix.accounts.iter() {
-                require_keys_eq!(
-                    ix.accounts
-                        .get(*index)
-                        .ok_or(LendingError::IncorrectInstructionInPosition)?
-                        .pubkey,
-                    *key
-                );
-            }
-        }
+    msg!("Finished check_refresh");
+    sol_log_compute_units();
 
-        Ok(())
-    };
+    Ok(())
+}
+
+pub fn check_refresh_with_loader(
+    ix_loader: &impl InstructionLoader,
+    reserves: &[Pubkey],
+    obligation_address: &Pubkey,
+) -> Result<()> {
+    let current_idx: usize = ix_loader.load_current_index()?.into();
+
+    let mut pending_reserves = reserves.to_vec();
+    pending_reserves.sort();
+    pending_reserves.dedup();
+
+    // The obligation refresh has to be the latest write to the obligation, and no target
+    // reserve may be written to between it and the current instruction
+    let mut idx = current_idx;
+    let obligation_refresh_idx = loop {
+        idx = previous_ix_index(idx, obligation_address)?;
+        let ix = ix_loader.load_instruction_at(idx)?;
 
-    let refresh_reserve_ixs = if reserves.len() == 2 && reserves[0].0 == reserves[1].0 {
-        reserves.len() - 1
-    } else {
-        reserves.len()
+        if is_refresh_obligation(&ix, obligation_address) {
+            break idx;
+        }
+        if is_refresh_reserve(&ix) {
+            continue;
+        }
+        if writes_to(&ix, obligation_address) {
+            msg!(
+                "Obligation modified after its refresh at instruction {}",
+                idx
+            );
+            return err!(LendingError::IncorrectInstructionInPosition);
+        }
+        if let Some(reserve) = pending_reserves.iter().find(|r| writes_to(&ix, r)) {
+            msg!("Reserve {} modified at instruction {}", reserve, idx);
+            return err!(LendingError::IncorrectInstructionInPosition);
+        }
     };
 
-    let mut required_pre_ixs = Vec::with_capacity(refresh_reserve_ixs + 1 + refresh_reserve_ixs);
-    let required_post_ixs = Vec::with_capacity(refresh_reserve_ixs);
-    for reserve in reserves.iter().take(refresh_reserve_ixs) {
-        required_pre_ixs.push(RequiredIx {
-            kind: RequiredIxType::RefreshReserve,
-            accounts: vec![(reserve.0, 0)],
-        });
+    // Each target reserve then needs a refresh before the obligation refresh, with no other
+    // write to it in between
+    idx = obligation_refresh_idx;
+    while !pending_reserves.is_empty() {
+        idx = previous_ix_index(idx, &pending_reserves[0])?;
+        let ix = ix_loader.load_instruction_at(idx)?;
+
+        if is_refresh_reserve(&ix) {
+            pending_reserves.retain(|reserve| !refreshes_reserve(&ix, reserve));
+            continue;
+        }
+        if let Some(reserve) = pending_reserves.iter().find(|r| writes_to(&ix, r)) {
+            msg!(
+                "Reserve {} modified after its refresh at instruction {}",
+                reserve,
+                idx
+            );
+            return err!(LendingError::IncorrectInstructionInPosition);
+        }
     }
 
-    required_pre_ixs.push(RequiredIx {
-        kind: RequiredIxType::RefreshObligation,
-        accounts: vec![(*obligation_address, 1)],
-    });
+    Ok(())
+}
 
-    required_pre_ixs.reverse();
-    check_ixns(required_pre_ixs, AppendedIxType::PreIxs)?;
-    check_ixns(required_post_ixs, AppendedIxType::PostIxs)?;
+fn previous_ix_index(idx: usize, missing_account: &Pubkey) -> Result<usize> {
+    idx.checked_sub(1).ok_or_else(|| {
+        msg!("No refresh found for {}", missing_account);
+        error!(LendingError::IncorrectInstructionInPosition)
+    })
+}
 
-    msg!("Finished check_refresh");
-    sol_log_compute_units();
+fn discriminator(ix: &Instruction) -> Option<[u8; 8]> {
+    if ix.program_id != crate::id() {
+        return None;
+    }
+    ix.data.get(..8).and_then(|data| data.try_into().ok())
+}
 
-    Ok(())
+fn is_refresh_reserve(ix: &Instruction) -> bool {
+    matches!(
+        discriminator(ix),
+        Some(d) if d == RefreshReserve::DISCRIMINATOR || d == RefreshReservesBatch::DISCRIMINATOR
+    )
 }
 
-enum AppendedIxType {
-    PreIxs,
-    PostIxs,
+fn is_refresh_obligation(ix: &Instruction, obligation: &Pubkey) -> bool {
+    discriminator(ix) == Some(RefreshObligation::DISCRIMINATOR)
+        && ix.accounts.get(1).map(|account| account.pubkey) == Some(*obligation)
 }
 
-fn _discriminator_to_ix(discriminator: [u8; 8]) -> &'static str {
-    match discriminator {
-        x if x == RefreshReserve::discriminator() => "RefreshReserve",
-        x if x == RefreshObligation::discriminator() => "RefreshObligation",
-        _ => "unknown",
+fn refreshes_reserve(ix: &Instruction, reserve: &Pubkey) -> bool {
+    match discriminator(ix) {
+        Some(d) if d == RefreshReserve::DISCRIMINATOR => {
+            ix.accounts.first().map(|account| account.pubkey) == Some(*reserve)
+        }
+        Some(d) if d == RefreshReservesBatch::DISCRIMINATOR => ix
+            .accounts
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .any(|account| account.pubkey == *reserve),
+        _ => false,
     }
 }
+
+fn writes_to(ix: &Instruction, account: &Pubkey) -> bool {
+    ix.accounts
+        .iter()
+        .any(|meta| meta.is_writable && meta.pubkey == *account)
+}
@@ -3,6 +3,11 @@ pub const RESERVE_LIQ_SUPPLY: &[u8] = b"reserve_liq_supply";
 pub const FEE_RECEIVER: &[u8] = b"fee_receiver";
 pub const RESERVE_COLL_MINT: &[u8] = b"reserve_coll_mint";
 pub const RESERVE_COLL_SUPPLY: &[u8] = b"reserve_coll_supply";
+pub const RESERVE_LOCKED_COLL: &[u8] = b"reserve_locked_coll";
+pub const USER_WSOL: &[u8] = b"user_wsol";
+pub const RESERVE_REGISTRY: &[u8] = b"reserve_registry";
+pub const FEE_DISTRIBUTION: &[u8] = b"fee_distribution";
+pub const RESERVE_INSURANCE: &[u8] = b"reserve_insurance";
 
 pub mod pda {
     use anchor_lang::prelude::Pubkey;
@@ -22,11 +27,58 @@ pub mod pda {
         lending_market_authority
     }
 
+    pub fn reserve_registry(lending_market: &Pubkey) -> Pubkey {
+        reserve_registry_program_id(&ID, lending_market)
+    }
+
+    pub fn reserve_registry_program_id(program_id: &Pubkey, lending_market: &Pubkey) -> Pubkey {
+        let (reserve_registry, _reserve_registry_bump) =
+            Pubkey::find_program_address(&[RESERVE_REGISTRY, lending_market.as_ref()], program_id);
+        reserve_registry
+    }
+
+    pub fn fee_distribution(lending_market: &Pubkey) -> Pubkey {
+        fee_distribution_program_id(&ID, lending_market)
+    }
+
+    pub fn fee_distribution_program_id(program_id: &Pubkey, lending_market: &Pubkey) -> Pubkey {
+        let (fee_distribution, _fee_distribution_bump) =
+            Pubkey::find_program_address(&[FEE_DISTRIBUTION, lending_market.as_ref()], program_id);
+        fee_distribution
+    }
+
+    pub fn reserve_insurance_vault(market: &Pubkey, mint: &Pubkey) -> Pubkey {
+        reserve_insurance_vault_program_id(&ID, market, mint)
+    }
+
+    pub fn reserve_insurance_vault_program_id(
+        program_id: &Pubkey,
+        market: &Pubkey,
+        mint: &Pubkey,
+    ) -> Pubkey {
+        let (insurance_vault, _insurance_vault_bump) = Pubkey::find_program_address(
+            &[RESERVE_INSURANCE, market.as_ref(), mint.as_ref()],
+            program_id,
+        );
+        insurance_vault
+    }
+
+    pub fn user_wsol(owner: &Pubkey) -> Pubkey {
+        user_wsol_program_id(&ID, owner)
+    }
+
+    pub fn user_wsol_program_id(program_id: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let (user_wsol, _user_wsol_bump) =
+            Pubkey::find_program_address(&[USER_WSOL, owner.as_ref()], program_id);
+        user_wsol
+    }
+
     pub struct InitReservePdas {
         pub liquidity_supply_vault: Pubkey,
         pub collateral_ctoken_mint: Pubkey,
         pub collateral_supply_vault: Pubkey,
         pub fee_vault: Pubkey,
+        pub locked_collateral_vault: Pubkey,
     }
 
     pub fn init_reserve_pdas(market: &Pubkey, mint: &Pubkey) -> InitReservePdas {
@@ -54,12 +106,17 @@ pub mod pda {
             &[RESERVE_COLL_SUPPLY, market.as_ref(), mint.as_ref()],
             program_id,
         );
+        let (locked_collateral_vault, _locked_collateral_vault_bump) = Pubkey::find_program_address(
+            &[RESERVE_LOCKED_COLL, market.as_ref(), mint.as_ref()],
+            program_id,
+        );
 
         InitReservePdas {
             liquidity_supply_vault,
             collateral_ctoken_mint,
             collateral_supply_vault,
             fee_vault,
+            locked_collateral_vault,
         }
     }
 }
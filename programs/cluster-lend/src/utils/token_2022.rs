@@ -0,0 +1,117 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, program_pack::Pack},
+};
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{self, TransferFeeConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+    },
+};
+
+use crate::errors::LendingError;
+
+/// Extensions a reserve liquidity mint may carry. Anything that lets a third party move or
+/// freeze vault funds, or that needs extra accounts on every transfer, is rejected.
+const ALLOWED_MINT_EXTENSIONS: &[ExtensionType] = &[
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+];
+
+pub fn validate_liquidity_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner == spl_token::ID {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for extension in mint_state.get_extension_types()? {
+        if !ALLOWED_MINT_EXTENSIONS.contains(&extension) {
+            msg!("Liquidity mint extension {:?} is not supported", extension);
+            return err!(LendingError::UnsupportedTokenExtension);
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the mint carries a transfer fee config, even one that currently charges nothing.
+pub fn has_transfer_fee_config(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner == spl_token::ID || mint.data_len() == spl_token::state::Mint::LEN {
+        return Ok(false);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint_state.get_extension::<TransferFeeConfig>().is_ok())
+}
+
+/// Moves the transfer fees withheld on `accounts` to the mint, token-2022 only closes accounts
+/// that withhold nothing. No-op for mints without a transfer fee config.
+pub fn harvest_withheld_tokens_to_mint<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if !has_transfer_fee_config(&mint)? {
+        return Ok(());
+    }
+
+    let sources: Vec<&Pubkey> = accounts.iter().map(|account| account.key).collect();
+    let ix = transfer_fee::instruction::harvest_withheld_tokens_to_mint(
+        token_program.key,
+        mint.key,
+        &sources,
+    )?;
+
+    let mut account_infos = vec![mint, token_program];
+    account_infos.extend_from_slice(accounts);
+    invoke(&ix, &account_infos)?;
+
+    Ok(())
+}
+
+/// Fee withheld by the mint when `amount` is transferred, zero for mints without a transfer
+/// fee.
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner == spl_token::ID || mint.data_len() == spl_token::state::Mint::LEN {
+        return Ok(0);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let fee = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| error!(LendingError::MathOverflow))?,
+        Err(_) => 0,
+    };
+
+    Ok(fee)
+}
+
+/// Amount that has to be sent so that `net_amount` arrives after the mint's transfer fee.
+pub fn amount_with_transfer_fee(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    if *mint.owner == spl_token::ID || mint.data_len() == spl_token::state::Mint::LEN {
+        return Ok(net_amount);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let fee = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => fee_config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+            .ok_or_else(|| error!(LendingError::MathOverflow))?,
+        Err(_) => 0,
+    };
+
+    net_amount
+        .checked_add(fee)
+        .ok_or_else(|| error!(LendingError::MathOverflow))
+}
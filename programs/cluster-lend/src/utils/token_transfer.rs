@@ -2,7 +2,7 @@ use anchor_lang::{
     prelude::{AccountInfo, CpiContext},
     Result,
 };
-use anchor_spl::token;
+use anchor_spl::{token, token_interface};
 
 use super::spltoken;
 
@@ -33,7 +33,10 @@ pub fn deposit_reserve_liquidity_transfer<'a>(
     source_liquidity_deposit: AccountInfo<'a>,
     destination_liquidity_deposit: AccountInfo<'a>,
     user_authority: AccountInfo<'a>,
-    token_program: AccountInfo<'a>,
+    liquidity_token_program: AccountInfo<'a>,
+    liquidity_mint: AccountInfo<'a>,
+    liquidity_decimals: u8,
+    collateral_token_program: AccountInfo<'a>,
     collateral_mint: AccountInfo<'a>,
     destination_collateral: AccountInfo<'a>,
     mint_authority: AccountInfo<'a>,
@@ -41,20 +44,22 @@ pub fn deposit_reserve_liquidity_transfer<'a>(
     liquidity_deposit_amount: u64,
     collateral_mint_amount: u64,
 ) -> Result<()> {
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
-            token_program.clone(),
-            anchor_spl::token::Transfer {
+            liquidity_token_program,
+            token_interface::TransferChecked {
                 from: source_liquidity_deposit,
                 to: destination_liquidity_deposit,
                 authority: user_authority,
+                mint: liquidity_mint,
             },
         ),
         liquidity_deposit_amount,
+        liquidity_decimals,
     )?;
 
     spltoken::mint(
-        token_program,
+        collateral_token_program,
         collateral_mint,
         mint_authority,
         destination_collateral,
@@ -70,7 +75,10 @@ pub fn deposit_reserve_liquidity_and_obligation_collateral_transfer<'a>(
     source_liquidity_deposit: AccountInfo<'a>,
     destination_liquidity_deposit: AccountInfo<'a>,
     user_authority: AccountInfo<'a>,
-    token_program: AccountInfo<'a>,
+    liquidity_token_program: AccountInfo<'a>,
+    liquidity_mint: AccountInfo<'a>,
+    liquidity_decimals: u8,
+    collateral_token_program: AccountInfo<'a>,
     collateral_mint: AccountInfo<'a>,
     collateral_supply_vault: AccountInfo<'a>,
     mint_authority: AccountInfo<'a>,
@@ -82,7 +90,10 @@ pub fn deposit_reserve_liquidity_and_obligation_collateral_transfer<'a>(
         source_liquidity_deposit,
         destination_liquidity_deposit,
         user_authority,
-        token_program,
+        liquidity_token_program,
+        liquidity_mint,
+        liquidity_decimals,
+        collateral_token_program,
         collateral_mint,
         collateral_supply_vault,
         mint_authority,
@@ -118,10 +129,13 @@ pub fn withdraw_obligation_collateral_transfer<'a>(
 
 #[allow(clippy::too_many_arguments)]
 pub fn redeem_reserve_collateral_transfer<'a>(
-    token_program: AccountInfo<'a>,
+    collateral_token_program: AccountInfo<'a>,
     reserve_collateral_mint: AccountInfo<'a>,
     burn_source_collateral: AccountInfo<'a>,
     user_authority: AccountInfo<'a>,
+    liquidity_token_program: AccountInfo<'a>,
+    liquidity_mint: AccountInfo<'a>,
+    liquidity_decimals: u8,
     reserve_liquidity_supply: AccountInfo<'a>,
     destination_liquidity: AccountInfo<'a>,
     lending_market_authority: AccountInfo<'a>,
@@ -133,30 +147,35 @@ pub fn redeem_reserve_collateral_transfer<'a>(
         reserve_collateral_mint,
         burn_source_collateral,
         user_authority,
-        token_program.clone(),
+        collateral_token_program,
         collateral_amount,
     )?;
 
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
-            token_program,
-            anchor_spl::token::Transfer {
+            liquidity_token_program,
+            token_interface::TransferChecked {
                 from: reserve_liquidity_supply,
                 to: destination_liquidity,
                 authority: lending_market_authority,
+                mint: liquidity_mint,
             },
             &[authority_signer_seeds],
         ),
         liquidity_amount,
+        liquidity_decimals,
     )?;
     Ok(())
 }
 
 #[allow(clippy::too_many_arguments)]
 pub fn withdraw_and_redeem_reserve_collateral_transfer<'a>(
-    token_program: AccountInfo<'a>,
+    collateral_token_program: AccountInfo<'a>,
     reserve_collateral_mint: AccountInfo<'a>,
     burn_reserve_source_collateral: AccountInfo<'a>,
+    liquidity_token_program: AccountInfo<'a>,
+    liquidity_mint: AccountInfo<'a>,
+    liquidity_decimals: u8,
     reserve_liquidity_supply: AccountInfo<'a>,
     user_destination_liquidity: AccountInfo<'a>,
     lending_market_authority: AccountInfo<'a>,
@@ -168,67 +187,78 @@ pub fn withdraw_and_redeem_reserve_collateral_transfer<'a>(
         reserve_collateral_mint,
         burn_reserve_source_collateral,
         lending_market_authority.clone(),
-        token_program.clone(),
+        collateral_token_program,
         collateral_amount,
         &[authority_signer_seeds],
     )?;
 
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
-            token_program,
-            anchor_spl::token::Transfer {
+            liquidity_token_program,
+            token_interface::TransferChecked {
                 from: reserve_liquidity_supply,
                 to: user_destination_liquidity,
                 authority: lending_market_authority,
+                mint: liquidity_mint,
             },
             &[authority_signer_seeds],
         ),
         liquidity_amount,
+        liquidity_decimals,
     )?;
     Ok(())
 }
 
 pub fn repay_obligation_liquidity_transfer<'a>(
     token_program: AccountInfo<'a>,
+    liquidity_mint: AccountInfo<'a>,
+    liquidity_decimals: u8,
     user_liquidity: AccountInfo<'a>,
     reserve_liquidity: AccountInfo<'a>,
     user_authority: AccountInfo<'a>,
     repay_amount: u64,
 ) -> Result<()> {
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
             token_program,
-            anchor_spl::token::Transfer {
+            token_interface::TransferChecked {
                 from: user_liquidity,
                 to: reserve_liquidity,
                 authority: user_authority,
+                mint: liquidity_mint,
             },
         ),
         repay_amount,
+        liquidity_decimals,
     )?;
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn borrow_obligation_liquidity_transfer<'a>(
     token_program: AccountInfo<'a>,
+    liquidity_mint: AccountInfo<'a>,
+    liquidity_decimals: u8,
     reserve_liquidity: AccountInfo<'a>,
     user_liquidity: AccountInfo<'a>,
     lending_market_authority: AccountInfo<'a>,
     authority_signer_seeds: &[&[u8]],
     liquidity_amount: u64,
 ) -> Result<()> {
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             token_program,
-            anchor_spl::token::Transfer {
+            token_interface::TransferChecked {
                 from: reserve_liquidity,
                 to: user_liquidity,
                 authority: lending_market_authority,
+                mint: liquidity_mint,
             },
             &[authority_signer_seeds],
         ),
         liquidity_amount,
+        liquidity_decimals,
     )?;
 
     Ok(())
@@ -236,69 +266,83 @@ pub fn borrow_obligation_liquidity_transfer<'a>(
 
 pub fn pay_borrowing_fees_transfer<'a>(
     token_program: AccountInfo<'a>,
+    liquidity_mint: AccountInfo<'a>,
+    liquidity_decimals: u8,
     user_liquidity: AccountInfo<'a>,
     fee_collector: AccountInfo<'a>,
     user_authority: AccountInfo<'a>,
     fee: u64,
 ) -> Result<()> {
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
             token_program,
-            anchor_spl::token::Transfer {
+            token_interface::TransferChecked {
                 from: user_liquidity,
                 to: fee_collector,
                 authority: user_authority,
+                mint: liquidity_mint,
             },
         ),
         fee,
+        liquidity_decimals,
     )?;
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn send_origination_fees_transfer<'a>(
     token_program: AccountInfo<'a>,
+    liquidity_mint: AccountInfo<'a>,
+    liquidity_decimals: u8,
     reserve_liquidity: AccountInfo<'a>,
     fee_receiver: AccountInfo<'a>,
     lending_market_authority: AccountInfo<'a>,
     authority_signer_seeds: &[&[u8]],
     fee: u64,
 ) -> Result<()> {
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             token_program,
-            anchor_spl::token::Transfer {
+            token_interface::TransferChecked {
                 to: fee_receiver,
                 from: reserve_liquidity,
                 authority: lending_market_authority,
+                mint: liquidity_mint,
             },
             &[authority_signer_seeds],
         ),
         fee,
+        liquidity_decimals,
     )?;
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn withdraw_fees_from_reserve<'a>(
     token_program: AccountInfo<'a>,
+    liquidity_mint: AccountInfo<'a>,
+    liquidity_decimals: u8,
     reserve_supply_liquidity: AccountInfo<'a>,
     fee_receiver: AccountInfo<'a>,
     lending_market_authority: AccountInfo<'a>,
     authority_signer_seeds: &[&[u8]],
     withdraw_amount: u64,
 ) -> Result<()> {
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             token_program,
-            anchor_spl::token::Transfer {
+            token_interface::TransferChecked {
                 from: reserve_supply_liquidity,
                 to: fee_receiver,
                 authority: lending_market_authority,
+                mint: liquidity_mint,
             },
             &[authority_signer_seeds],
         ),
         withdraw_amount,
+        liquidity_decimals,
     )?;
 
     Ok(())
@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod helpers;
+use std::rc::Rc;
+
+use anchor_lang::{error::ErrorCode, AnchorDeserialize};
+use cluster_lend::{utils::Fraction, AuditReserveReport, Reserve};
+use reserve::ReserveFixture;
+use solana_program::instruction::Instruction;
+use solana_program_test::*;
+
+use helpers::*;
+use spl::TokenAccountFixture;
+use test::TestFixture;
+
+fn audited_reserve() -> Reserve {
+    let mut reserve = Reserve {
+        obligation_collateral_tracked: 1,
+        ..Default::default()
+    };
+    reserve.liquidity.available_amount = 1_000;
+    reserve.liquidity.borrowed_amount_sf = Fraction::from_num(500).to_bits();
+    reserve.liquidity.accumulated_protocol_fees_sf = Fraction::from_num(10.5).to_bits();
+    reserve.collateral.mint_total_supply = 1_400;
+    reserve.obligation_collateral_amount = 800;
+    reserve
+}
+
+#[test]
+fn consistent_reserve_passes_audit() {
+    let report = audited_reserve().audit(1_000, 800, 1_400, 3, true);
+
+    assert!(report.is_healthy());
+    assert_eq!(report.accumulated_protocol_fees, 11);
+    assert_eq!(report.fee_vault_balance, 3);
+
+    // Donations leave the reserve healthy
+    assert!(audited_reserve()
+        .audit(1_200, 900, 1_400, 0, true)
+        .is_healthy());
+}
+
+#[test]
+fn each_invariant_is_reported() {
+    let report = audited_reserve().audit(999, 800, 1_400, 0, true);
+    assert!(!report.liquidity_vault_ok);
+    assert!(!report.is_healthy());
+
+    let report = audited_reserve().audit(1_000, 799, 1_400, 0, true);
+    assert!(!report.collateral_vault_ok);
+
+    let report = audited_reserve().audit(1_000, 800, 1_401, 0, true);
+    assert!(!report.collateral_supply_ok);
+
+    let mut reserve = audited_reserve();
+    reserve.liquidity.accumulated_protocol_fees_sf = Fraction::from_num(1_501).to_bits();
+    assert!(!reserve.audit(1_000, 800, 1_400, 0, true).protocol_fees_ok);
+}
+
+#[test]
+fn untracked_reserve_skips_collateral_vault_check() {
+    let mut reserve = audited_reserve();
+    reserve.obligation_collateral_tracked = 0;
+
+    assert!(reserve.audit(1_000, 0, 1_400, 0, true).collateral_vault_ok);
+}
+
+#[test]
+fn fee_vault_covers_booked_fees() {
+    let mut reserve = audited_reserve();
+    // 10.5 interest fees unredeemed and 20 redeemed, 5 of them to insurance. Together with the
+    // origination fees the fee vault got 19, then 3 were withdrawn from it
+    reserve.liquidity.fee_ledger.interest_fees_sf = Fraction::from_num(30.5).to_bits();
+    reserve.liquidity.fee_ledger.origination_fees = 4;
+    reserve.liquidity.fee_ledger.redeemed_fees = 20;
+    reserve.liquidity.fee_ledger.insurance_fees = 5;
+    reserve.liquidity.fee_ledger.withdrawn_fees = 3;
+
+    let report = reserve.audit(1_000, 800, 1_400, 16, true);
+    assert!(report.fee_vault_ok);
+    assert_eq!(report.booked_protocol_fees, 34);
+
+    let report = reserve.audit(1_000, 800, 1_400, 15, true);
+    assert!(!report.fee_vault_ok);
+    assert!(!report.is_healthy());
+
+    // Transfer fees make the vault receive less than was booked
+    assert!(reserve.audit(1_000, 800, 1_400, 15, false).fee_vault_ok);
+}
+
+async fn setup(test_f: &TestFixture) -> ReserveFixture {
+    let lending_market_f = test_f.new_lending_market().await;
+    test_f
+        .new_reserve(&lending_market_f, test_f.usdc_mint.key)
+        .await
+}
+
+async fn simulate_audit(test_f: &TestFixture, ix: Instruction) -> AuditReserveReport {
+    let return_data = test_f
+        .simulate_return_data(vec![ix], &[test_f.payer_keypair()])
+        .await
+        .unwrap();
+
+    AuditReserveReport::deserialize(&mut return_data.data.as_slice()).unwrap()
+}
+
+#[tokio::test]
+async fn success_audit_seeded_reserve() {
+    let test_f = TestFixture::new().await;
+    let reserve_f = setup(&test_f).await;
+
+    let report = simulate_audit(&test_f, reserve_f.audit_reserve_ix().unwrap()).await;
+    assert!(report.is_healthy());
+
+    // Donations to the vaults keep the reserve healthy
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    test_f
+        .send_transaction(
+            vec![test_f
+                .usdc_mint
+                .make_mint_to_ix(&reserve.liquidity.supply_vault, 500)],
+            &[test_f.payer_keypair()],
+        )
+        .await
+        .unwrap();
+    let report = simulate_audit(&test_f, reserve_f.audit_reserve_ix().unwrap()).await;
+    assert!(report.is_healthy());
+    assert_eq!(
+        report.liquidity_vault_balance,
+        report.available_amount + 500
+    );
+
+    // A healthy reserve is not paused
+    test_f
+        .send_transaction(
+            vec![reserve_f.audit_reserve_and_pause_ix().unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await
+        .unwrap();
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    assert_eq!(reserve.config.paused_operations, 0);
+}
+
+#[tokio::test]
+async fn failure_audit_with_other_fee_vault() {
+    let test_f = TestFixture::new().await;
+    let reserve_f = setup(&test_f).await;
+
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    let other_vault = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &test_f.usdc_mint.key,
+        &test_f.payer(),
+    )
+    .await;
+
+    let mut ix = reserve_f.audit_reserve_ix().unwrap();
+    for meta in ix.accounts.iter_mut() {
+        if meta.pubkey == reserve.liquidity.fee_vault {
+            meta.pubkey = other_vault.key;
+        }
+    }
+
+    let r = test_f
+        .send_transaction(vec![ix], &[test_f.payer_keypair()])
+        .await;
+    assert_anchor_error!(r.unwrap_err(), ErrorCode::ConstraintAddress);
+}
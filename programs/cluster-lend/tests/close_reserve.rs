@@ -0,0 +1,252 @@
+#[cfg(test)]
+mod helpers;
+use std::rc::Rc;
+
+use anchor_lang::{error::ErrorCode, prelude::Pubkey};
+use cluster_lend::{
+    constants::initial_deposit_amount, errors::LendingError, utils::pda::reserve_registry,
+    utils::Fraction, Reserve, ReserveRegistry, ReserveStatus,
+};
+use reserve::ReserveFixture;
+use solana_program_test::*;
+
+use helpers::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl::{MintFixture, TokenAccountFixture};
+use test::{TestFixture, TEST_RESERVE_CONFIG, USDC_MINT_DECIMALS};
+
+#[test]
+fn reserve_is_empty_only_without_debt_collateral_and_fees() {
+    let mut reserve = Reserve::default();
+    assert!(reserve.is_empty(0).unwrap());
+
+    reserve.liquidity.borrowed_amount_sf = 1;
+    assert!(!reserve.is_empty(0).unwrap());
+    reserve.liquidity.borrowed_amount_sf = 0;
+
+    reserve.collateral.mint_total_supply = 1;
+    assert!(!reserve.is_empty(0).unwrap());
+    // Only the locked seed deposit is left
+    assert!(reserve.is_empty(1).unwrap());
+    reserve.collateral.mint_total_supply = 0;
+
+    reserve.liquidity.available_amount = 10;
+    reserve.liquidity.accumulated_protocol_fees_sf = Fraction::from_num(3).to_bits();
+    assert!(!reserve.is_empty(0).unwrap());
+
+    // Fractional fees cannot be redeemed, the leftover dust is swept on close
+    reserve.liquidity.accumulated_protocol_fees_sf = Fraction::from_num(0.5).to_bits();
+    assert!(reserve.is_empty(0).unwrap());
+}
+
+#[test]
+fn removing_a_reserve_keeps_creation_order() {
+    let mut registry = ReserveRegistry::default();
+    let reserves: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    for (slot, reserve) in reserves.iter().enumerate() {
+        registry
+            .add_reserve(
+                *reserve,
+                Pubkey::new_unique(),
+                ReserveStatus::Active,
+                slot as u64,
+            )
+            .unwrap();
+    }
+
+    registry.remove_reserve(&reserves[1]).unwrap();
+
+    let remaining: Vec<Pubkey> = registry.reserves().iter().map(|e| e.reserve).collect();
+    assert_eq!(remaining, vec![reserves[0], reserves[2]]);
+    assert_eq!(registry.entries[2].reserve, Pubkey::default());
+    assert!(registry.remove_reserve(&reserves[1]).is_err());
+
+    registry.remove_reserve(&reserves[2]).unwrap();
+    registry.remove_reserve(&reserves[0]).unwrap();
+    assert!(registry.reserves().is_empty());
+}
+
+async fn setup(test_f: &TestFixture) -> (ReserveFixture, TokenAccountFixture) {
+    let lending_market_f = test_f.new_lending_market().await;
+    let reserve_f = test_f
+        .new_reserve(&lending_market_f, test_f.usdc_mint.key)
+        .await;
+    let owner_ata = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &test_f.usdc_mint.key,
+        &test_f.payer(),
+    )
+    .await;
+
+    (reserve_f, owner_ata)
+}
+
+#[tokio::test]
+async fn success_close_empty_reserve() {
+    let test_f = TestFixture::new().await;
+    let (reserve_f, owner_ata) = setup(&test_f).await;
+
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f.close_reserve_ix(owner_ata.key, None).unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    // The seed deposit is swept to the owner and the reserve account is gone
+    assert_eq!(
+        owner_ata.balance().await,
+        initial_deposit_amount(USDC_MINT_DECIMALS)
+    );
+    assert!(!test_f.account_exists(&reserve_f.key).await);
+}
+
+#[tokio::test]
+async fn success_close_reserve_with_transfer_fee_mint() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market().await;
+    // 1% transfer fee
+    let mint_f = MintFixture::new_with_transfer_fee(
+        Rc::clone(&test_f.context),
+        USDC_MINT_DECIMALS,
+        100,
+        u64::MAX,
+    )
+    .await;
+    let reserve_f = test_f.new_reserve(&lending_market_f, mint_f.key).await;
+    let owner_ata = TokenAccountFixture::new_token_2022(
+        Rc::clone(&test_f.context),
+        &mint_f.key,
+        &test_f.payer(),
+    )
+    .await;
+
+    // The supply vault withholds the fee on the seed deposit, token-2022 only closes it once
+    // that fee is harvested to the mint
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f.close_reserve_ix(owner_ata.key, None).unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    let seed_amount = initial_deposit_amount(USDC_MINT_DECIMALS);
+    let net_seed_amount = seed_amount - seed_amount / 100;
+    // The sweep pays the fee again, rounded up
+    assert_eq!(
+        owner_ata.balance().await,
+        net_seed_amount - net_seed_amount.div_ceil(100)
+    );
+    assert!(!test_f.account_exists(&reserve_f.key).await);
+}
+
+#[tokio::test]
+async fn success_close_reserve_drops_registry_entry() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market_with_registry().await;
+    let reserve_f = test_f
+        .new_reserve(&lending_market_f, test_f.usdc_mint.key)
+        .await;
+    let owner_ata = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &test_f.usdc_mint.key,
+        &test_f.payer(),
+    )
+    .await;
+
+    test_f
+        .send_transaction(
+            vec![reserve_f.close_reserve_ix(owner_ata.key, None).unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await
+        .unwrap();
+
+    let registry: ReserveRegistry = test_f
+        .load_and_deserialize(&reserve_registry(&lending_market_f.key))
+        .await;
+    assert!(registry.reserves().is_empty());
+}
+
+#[tokio::test]
+async fn failure_close_reserve_by_other_signer() {
+    let test_f = TestFixture::new().await;
+    let (reserve_f, owner_ata) = setup(&test_f).await;
+
+    let other = Keypair::new();
+    let ix = reserve_f.with_signer(
+        reserve_f.close_reserve_ix(owner_ata.key, None).unwrap(),
+        other.pubkey(),
+    );
+
+    let r = test_f
+        .send_transaction(vec![ix], &[test_f.payer_keypair(), other])
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::InvalidMarketOwner);
+}
+
+#[tokio::test]
+async fn failure_close_reserve_with_deposits() {
+    let test_f = TestFixture::new().await;
+    let (reserve_f, owner_ata) = setup(&test_f).await;
+
+    reserve_f
+        .try_update_reserve(test_f.payer_keypair(), TEST_RESERVE_CONFIG)
+        .await
+        .unwrap();
+
+    let depositor = Keypair::new();
+    let depositor_ata_f = test_f
+        .usdc_mint
+        .create_token_account_and_mint_to(&depositor, 10)
+        .await;
+    let user_destination_collateral = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &reserve_f.reserve_collateral_mint,
+        &depositor.pubkey(),
+    )
+    .await;
+    reserve_f
+        .try_deposit(
+            &depositor,
+            depositor_ata_f.key,
+            user_destination_collateral.key,
+            1_000,
+        )
+        .await
+        .unwrap();
+
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f.close_reserve_ix(owner_ata.key, None).unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::ReserveNotEmpty);
+}
+
+#[tokio::test]
+async fn failure_close_reserve_with_foreign_insurance_vault() {
+    let test_f = TestFixture::new().await;
+    let (reserve_f, owner_ata) = setup(&test_f).await;
+
+    // The reserve has no insurance vault, so none may be passed
+    let other_vault = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &test_f.usdc_mint.key,
+        &test_f.payer(),
+    )
+    .await;
+
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .close_reserve_ix(owner_ata.key, Some(other_vault.key))
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert_anchor_error!(r.unwrap_err(), ErrorCode::ConstraintAddress);
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::Pubkey;
+use cluster_lend::{FeeDistribution, FeeShare};
+
+fn share(recipient: Pubkey, share_bps: u16) -> FeeShare {
+    FeeShare {
+        recipient,
+        share_bps,
+    }
+}
+
+#[test]
+fn fee_shares_must_cover_the_full_amount_once() {
+    let mut distribution = FeeDistribution::default();
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert!(distribution.set_recipients(&[]).is_err());
+    assert!(distribution.set_recipients(&[share(a, 5_000)]).is_err());
+    assert!(distribution
+        .set_recipients(&[share(a, 5_000), share(a, 5_000)])
+        .is_err());
+    assert!(distribution
+        .set_recipients(&[share(a, 10_000), share(b, 0)])
+        .is_err());
+    assert!(distribution
+        .set_recipients(&[share(Pubkey::default(), 10_000)])
+        .is_err());
+    let too_many: Vec<FeeShare> = (0..9).map(|_| share(Pubkey::new_unique(), 1)).collect();
+    assert!(distribution.set_recipients(&too_many).is_err());
+    assert!(distribution.recipients().is_empty());
+
+    distribution
+        .set_recipients(&[share(a, 7_000), share(b, 3_000)])
+        .unwrap();
+    assert_eq!(distribution.recipients().len(), 2);
+
+    // Replacing the list clears the previous recipients
+    distribution.set_recipients(&[share(b, 10_000)]).unwrap();
+    assert_eq!(distribution.recipients().len(), 1);
+    assert_eq!(distribution.recipients()[0].recipient, b);
+    assert_eq!(distribution.recipients[1].recipient, Pubkey::default());
+}
+
+#[test]
+fn fee_split_rounds_down_and_leaves_dust() {
+    let mut distribution = FeeDistribution::default();
+    distribution
+        .set_recipients(&[
+            share(Pubkey::new_unique(), 3_333),
+            share(Pubkey::new_unique(), 3_333),
+            share(Pubkey::new_unique(), 3_334),
+        ])
+        .unwrap();
+
+    assert_eq!(distribution.split(10), vec![3, 3, 3]);
+    assert_eq!(distribution.split(10_000), vec![3_333, 3_333, 3_334]);
+    assert_eq!(distribution.split(0), vec![0, 0, 0]);
+}
@@ -0,0 +1,232 @@
+#[cfg(test)]
+mod helpers;
+use std::rc::Rc;
+
+use anchor_lang::AnchorDeserialize;
+use cluster_lend::{
+    constants::ten_pow, utils::pda::init_reserve_pdas_program_id, utils::Fraction,
+    InitObligationArgs, Reserve, ReserveConfig, ReserveFeeLedger, ReserveFees,
+};
+use lending_market::LendingMarketFixture;
+use obligation::ObligationFixture;
+use reserve::ReserveFixture;
+use solana_program_test::*;
+
+use helpers::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use spl::TokenAccountFixture;
+use test::{TestFixture, PYTH_SOL_FEED, SOL_MINT_DECIMALS, TEST_RESERVE_CONFIG};
+
+#[test]
+fn fee_ledger_tracks_sources_and_outflows() {
+    let mut reserve = Reserve::default();
+    let ledger = &mut reserve.liquidity.fee_ledger;
+
+    ledger.record_interest_fees(Fraction::from_num(1.25));
+    ledger.record_interest_fees(Fraction::from_num(0.75));
+    ledger.record_origination_fees(10);
+    ledger.record_flash_loan_fees(3);
+    ledger.record_liquidation_fees(7);
+    ledger.record_withdrawn_fees(12);
+
+    assert_eq!(ledger.interest_fees_sf, Fraction::from_num(2).to_bits());
+    assert_eq!(ledger.origination_fees, 10);
+    assert_eq!(ledger.flash_loan_fees, 3);
+    assert_eq!(ledger.liquidation_fees, 7);
+    assert_eq!(ledger.withdrawn_fees, 12);
+
+    // Redeeming moves accrued interest fees to the fee vault and records it
+    reserve.liquidity.available_amount = 100;
+    reserve.liquidity.accumulated_protocol_fees_sf = Fraction::from_num(2).to_bits();
+    reserve.liquidity.redeem_fees(2).unwrap();
+    assert_eq!(reserve.liquidity.fee_ledger.redeemed_fees, 2);
+    assert_eq!(
+        reserve.liquidity.fee_ledger.interest_fees_sf,
+        Fraction::from_num(2).to_bits()
+    );
+}
+
+#[test]
+fn fee_ledger_counters_saturate() {
+    let mut reserve = Reserve::default();
+    let ledger = &mut reserve.liquidity.fee_ledger;
+
+    ledger.record_origination_fees(u64::MAX);
+    ledger.record_origination_fees(1);
+    assert_eq!(ledger.origination_fees, u64::MAX);
+}
+
+async fn new_active_reserve(
+    test_f: &TestFixture,
+    lending_market_f: &LendingMarketFixture,
+    mint: Pubkey,
+    config: ReserveConfig,
+) -> ReserveFixture {
+    let reserve_f = test_f.new_reserve(lending_market_f, mint).await;
+    reserve_f
+        .try_update_reserve(test_f.payer_keypair(), config)
+        .await
+        .unwrap();
+    reserve_f
+}
+
+/// Deposits `ui_amount` of new tokens of `depositor` into the reserve, returning the collateral.
+async fn deposit(
+    test_f: &TestFixture,
+    reserve_f: &ReserveFixture,
+    depositor: &Keypair,
+    ui_amount: u64,
+) -> TokenAccountFixture {
+    let mint_f = if reserve_f.liquidity_mint == test_f.usdc_mint.key {
+        &test_f.usdc_mint
+    } else {
+        &test_f.sol_mint
+    };
+    let liquidity_f = mint_f
+        .create_token_account_and_mint_to(depositor, ui_amount as f64)
+        .await;
+    let collateral_f = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &reserve_f.reserve_collateral_mint,
+        &depositor.pubkey(),
+    )
+    .await;
+    reserve_f
+        .try_deposit(
+            depositor,
+            liquidity_f.key,
+            collateral_f.key,
+            liquidity_f.balance().await,
+        )
+        .await
+        .unwrap();
+
+    collateral_f
+}
+
+#[tokio::test]
+async fn success_get_reserve_fee_ledger_after_accrual() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market().await;
+    let usdc_reserve_f = new_active_reserve(
+        &test_f,
+        &lending_market_f,
+        test_f.usdc_mint.key,
+        TEST_RESERVE_CONFIG,
+    )
+    .await;
+    // 1/64 borrow fee, half of the interest goes to the protocol
+    let sol_reserve_f = new_active_reserve(
+        &test_f,
+        &lending_market_f,
+        test_f.sol_mint.key,
+        ReserveConfig {
+            protocol_take_rate_pct: 50,
+            fees: ReserveFees {
+                borrow_fee_sf: Fraction::from_num(0.015625).to_bits() as u64,
+                ..TEST_RESERVE_CONFIG.fees
+            },
+            ..TEST_RESERVE_CONFIG
+        },
+    )
+    .await;
+    let usdc_pdas = init_reserve_pdas_program_id(
+        &cluster_lend::ID,
+        &lending_market_f.key,
+        &test_f.usdc_mint.key,
+    );
+    let sol_pdas = init_reserve_pdas_program_id(
+        &cluster_lend::ID,
+        &lending_market_f.key,
+        &test_f.sol_mint.key,
+    );
+
+    let now = test_f.get_clock().await.unix_timestamp;
+    test_f.set_pyth_oracle_timestamp(PYTH_SOL_FEED, now).await;
+
+    deposit(&test_f, &sol_reserve_f, &Keypair::new(), 10).await;
+    let borrower = Keypair::new();
+    let usdc_collateral_f = deposit(&test_f, &usdc_reserve_f, &borrower, 100).await;
+    let obligation_f = ObligationFixture::new(
+        Rc::clone(&test_f.context),
+        lending_market_f.key,
+        InitObligationArgs { id: 0, tag: 0 },
+        &borrower,
+    )
+    .await
+    .unwrap();
+    let sol_ata_f = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &test_f.sol_mint.key,
+        &borrower.pubkey(),
+    )
+    .await;
+
+    let borrow_amount = ten_pow(SOL_MINT_DECIMALS as usize);
+    let r = test_f
+        .send_transaction(
+            vec![
+                usdc_reserve_f
+                    .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                    .unwrap(),
+                obligation_f.refresh_obligation_ix().unwrap(),
+                obligation_f
+                    .deposit_obligation_ix(
+                        usdc_collateral_f.balance().await,
+                        usdc_reserve_f.key,
+                        usdc_pdas.collateral_supply_vault,
+                        usdc_collateral_f.key,
+                    )
+                    .unwrap(),
+                usdc_reserve_f
+                    .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                    .unwrap(),
+                sol_reserve_f
+                    .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                    .unwrap(),
+                obligation_f
+                    .refresh_obligation_with_reserves_ix(&[usdc_reserve_f.key])
+                    .unwrap(),
+                obligation_f
+                    .borrow_obligation_ix(
+                        borrow_amount,
+                        sol_reserve_f.key,
+                        test_f.sol_mint.key,
+                        sol_pdas.liquidity_supply_vault,
+                        sol_pdas.fee_vault,
+                        sol_ata_f.key,
+                    )
+                    .unwrap(),
+            ],
+            &[test_f.payer_keypair(), borrower.insecure_clone()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    // Interest accrues on the next refresh
+    let slot = test_f.get_slot().await;
+    test_f.warp_to_slot(slot + 10_000);
+    let r = test_f
+        .send_transaction(
+            vec![sol_reserve_f
+                .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    let return_data = test_f
+        .simulate_return_data(
+            vec![sol_reserve_f.get_reserve_fee_ledger_ix().unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await
+        .unwrap();
+    let fee_ledger = ReserveFeeLedger::deserialize(&mut return_data.data.as_slice()).unwrap();
+
+    assert_eq!(fee_ledger.origination_fees, borrow_amount / 64);
+    assert!(fee_ledger.interest_fees_sf > 0);
+    let sol_reserve: Reserve = test_f.load_and_deserialize(&sol_reserve_f.key).await;
+    assert_eq!(fee_ledger, sol_reserve.liquidity.fee_ledger);
+}
@@ -0,0 +1,288 @@
+#[cfg(test)]
+mod helpers;
+
+use anchor_lang::error::ErrorCode;
+use cluster_lend::{
+    errors::LendingError, LendingMarket, LendingMarketUpdate, MarketRole, Reserve, ReserveConfig,
+    ReserveConfigUpdate, ReservePauseFlags, ReserveStatus,
+};
+use reserve::ReserveFixture;
+use solana_program_test::*;
+
+use helpers::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use test::{TestFixture, TEST_RESERVE_CONFIG};
+
+#[test]
+fn market_updates_allowed_for_guardian() {
+    let mut market = LendingMarket::default();
+
+    assert!(LendingMarketUpdate::EmergencyMode(true).is_tightening(&market));
+    assert!(LendingMarketUpdate::BorrowingDisabled(true).is_tightening(&market));
+    // Setting a flag to the value it already has is harmless
+    assert!(LendingMarketUpdate::EmergencyMode(false).is_tightening(&market));
+
+    market.emergency_mode = 1;
+    market.borrow_disabled = 1;
+    assert!(!LendingMarketUpdate::EmergencyMode(false).is_tightening(&market));
+    assert!(!LendingMarketUpdate::BorrowingDisabled(false).is_tightening(&market));
+}
+
+#[test]
+fn market_updates_rejected_for_guardian() {
+    let market = LendingMarket::default();
+
+    assert!(!LendingMarketUpdate::GlobalAllowedBorrowValue(0).is_tightening(&market));
+    assert!(!LendingMarketUpdate::LiquidationCloseFactorPct(100).is_tightening(&market));
+    assert!(!LendingMarketUpdate::AutodeleverageEnabled(true).is_tightening(&market));
+    assert!(!LendingMarketUpdate::ConfigTimelockSecs(7_200).is_tightening(&market));
+}
+
+#[test]
+fn reserve_limits_can_only_be_lowered() {
+    let config = ReserveConfig {
+        deposit_limit: 1_000,
+        borrow_limit: 500,
+        ..Default::default()
+    };
+
+    assert!(ReserveConfigUpdate::DepositLimit(999).is_tightening(&config));
+    assert!(ReserveConfigUpdate::DepositLimit(1_000).is_tightening(&config));
+    assert!(!ReserveConfigUpdate::DepositLimit(1_001).is_tightening(&config));
+    assert!(ReserveConfigUpdate::BorrowLimit(0).is_tightening(&config));
+    assert!(!ReserveConfigUpdate::BorrowLimit(501).is_tightening(&config));
+}
+
+#[test]
+fn reserve_status_can_only_wind_down() {
+    let mut config = ReserveConfig {
+        status: ReserveStatus::Active as u8,
+        ..Default::default()
+    };
+
+    assert!(ReserveConfigUpdate::ReserveStatus(ReserveStatus::Obsolete).is_tightening(&config));
+    assert!(ReserveConfigUpdate::ReserveStatus(ReserveStatus::ReduceOnly).is_tightening(&config));
+    assert!(!ReserveConfigUpdate::ReserveStatus(ReserveStatus::Hidden).is_tightening(&config));
+
+    config.status = ReserveStatus::ReduceOnly as u8;
+    assert!(ReserveConfigUpdate::ReserveStatus(ReserveStatus::Obsolete).is_tightening(&config));
+    assert!(!ReserveConfigUpdate::ReserveStatus(ReserveStatus::Active).is_tightening(&config));
+
+    config.status = ReserveStatus::Obsolete as u8;
+    assert!(!ReserveConfigUpdate::ReserveStatus(ReserveStatus::ReduceOnly).is_tightening(&config));
+    assert!(!ReserveConfigUpdate::ReserveStatus(ReserveStatus::Active).is_tightening(&config));
+    assert!(!ReserveConfigUpdate::LoanToValuePct(0).is_tightening(&config));
+}
+
+#[test]
+fn owner_skips_timelock_for_safer_reserve_updates() {
+    let config = ReserveConfig {
+        loan_to_value_pct: 70,
+        liquidation_threshold_pct: 80,
+        ..Default::default()
+    };
+
+    let lower_ltv = ReserveConfigUpdate::LoanToValuePct(60);
+    assert!(lower_ltv.is_timelock_exempt(&config));
+    assert!(!lower_ltv.is_tightening(&config));
+    assert!(!ReserveConfigUpdate::LoanToValuePct(75).is_timelock_exempt(&config));
+    assert!(ReserveConfigUpdate::LiquidationThresholdPct(75).is_timelock_exempt(&config));
+    assert!(!ReserveConfigUpdate::LiquidationThresholdPct(85).is_timelock_exempt(&config));
+    // Fee increases are charged to existing borrowers, they wait like any other change
+    assert!(!ReserveConfigUpdate::BorrowFeeSf(1).is_timelock_exempt(&config));
+    assert!(!ReserveConfigUpdate::FlashLoanFeeSf(1).is_timelock_exempt(&config));
+    // Guardian updates never need the timelock either
+    assert!(ReserveConfigUpdate::DepositLimit(0).is_timelock_exempt(&config));
+}
+
+#[test]
+fn withdrawal_caps_skip_timelock_when_lowered() {
+    let mut config = ReserveConfig::default();
+    let cap = |capacity, interval_length_seconds| ReserveConfigUpdate::DepositWithdrawalCap {
+        capacity,
+        interval_length_seconds,
+    };
+
+    // Enabling a cap only restricts withdrawals
+    assert!(cap(1_000, 3_600).is_timelock_exempt(&config));
+
+    config.deposit_withdrawal_cap.config_capacity = 1_000;
+    config.deposit_withdrawal_cap.config_interval_length_seconds = 3_600;
+    assert!(cap(500, 3_600).is_timelock_exempt(&config));
+    assert!(cap(1_000, 7_200).is_timelock_exempt(&config));
+    assert!(!cap(2_000, 3_600).is_timelock_exempt(&config));
+    assert!(!cap(1_000, 60).is_timelock_exempt(&config));
+    assert!(!cap(0, 0).is_timelock_exempt(&config));
+}
+
+#[test]
+fn owner_skips_timelock_for_safer_market_updates() {
+    let market = LendingMarket {
+        config_timelock_secs: 3_600,
+        global_allowed_borrow_value: 1_000,
+        ..Default::default()
+    };
+
+    assert!(LendingMarketUpdate::ConfigTimelockSecs(7_200).is_timelock_exempt(&market));
+    assert!(!LendingMarketUpdate::ConfigTimelockSecs(0).is_timelock_exempt(&market));
+    assert!(LendingMarketUpdate::GlobalAllowedBorrowValue(500).is_timelock_exempt(&market));
+    assert!(!LendingMarketUpdate::GlobalAllowedBorrowValue(2_000).is_timelock_exempt(&market));
+    assert!(LendingMarketUpdate::EmergencyMode(true).is_timelock_exempt(&market));
+
+    // Lifting an emergency only needs the owner
+    let market = LendingMarket {
+        emergency_mode: true as u8,
+        borrow_disabled: true as u8,
+        ..market
+    };
+    assert!(!LendingMarketUpdate::EmergencyMode(false).is_tightening(&market));
+    assert!(LendingMarketUpdate::EmergencyMode(false).is_timelock_exempt(&market));
+    assert!(LendingMarketUpdate::BorrowingDisabled(false).is_timelock_exempt(&market));
+}
+
+/// Market with an active reserve and `guardian` holding the guardian role.
+async fn setup(test_f: &TestFixture, guardian: &Keypair) -> ReserveFixture {
+    let lending_market_f = test_f.new_lending_market().await;
+
+    let owner = test_f.payer_keypair();
+    lending_market_f
+        .try_propose_market_role(&owner, MarketRole::Guardian, guardian.pubkey())
+        .await
+        .unwrap();
+    lending_market_f
+        .try_accept_market_role(guardian, MarketRole::Guardian)
+        .await
+        .unwrap();
+
+    let reserve_f = test_f
+        .new_reserve(&lending_market_f, test_f.usdc_mint.key)
+        .await;
+    reserve_f
+        .try_update_reserve(owner, TEST_RESERVE_CONFIG)
+        .await
+        .unwrap();
+
+    reserve_f
+}
+
+#[tokio::test]
+async fn success_guardian_lowers_deposit_limit() {
+    let test_f = TestFixture::new().await;
+    let guardian = Keypair::new();
+    let reserve_f = setup(&test_f, &guardian).await;
+
+    let ix = reserve_f
+        .guardian_update_reserve_ix(guardian.pubkey(), ReserveConfigUpdate::DepositLimit(1_000))
+        .unwrap();
+    let r = test_f
+        .send_transaction(vec![ix], &[test_f.payer_keypair(), guardian])
+        .await;
+    assert!(r.is_ok());
+
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    assert_eq!(reserve.config.deposit_limit, 1_000);
+}
+
+#[tokio::test]
+async fn failure_guardian_raises_deposit_limit() {
+    let test_f = TestFixture::new().await;
+    let guardian = Keypair::new();
+    let reserve_f = setup(&test_f, &guardian).await;
+
+    let ix = reserve_f
+        .guardian_update_reserve_ix(
+            guardian.pubkey(),
+            ReserveConfigUpdate::DepositLimit(TEST_RESERVE_CONFIG.deposit_limit + 1),
+        )
+        .unwrap();
+    let r = test_f
+        .send_transaction(vec![ix], &[test_f.payer_keypair(), guardian])
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::GuardianUpdateNotAllowed);
+}
+
+#[tokio::test]
+async fn failure_guardian_update_by_other_signer() {
+    let test_f = TestFixture::new().await;
+    let reserve_f = setup(&test_f, &Keypair::new()).await;
+
+    let other = Keypair::new();
+    let ix = reserve_f
+        .guardian_update_reserve_ix(other.pubkey(), ReserveConfigUpdate::DepositLimit(1_000))
+        .unwrap();
+    let r = test_f
+        .send_transaction(vec![ix], &[test_f.payer_keypair(), other])
+        .await;
+    assert_anchor_error!(r.unwrap_err(), ErrorCode::ConstraintHasOne);
+}
+
+#[tokio::test]
+async fn success_owner_updates_reserve_config() {
+    let test_f = TestFixture::new().await;
+    let reserve_f = setup(&test_f, &Keypair::new()).await;
+
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .update_reserve_config_ix(ReserveConfigUpdate::LoanToValuePct(60))
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    assert_eq!(reserve.config.loan_to_value_pct, 60);
+}
+
+#[tokio::test]
+async fn failure_reserve_config_update_by_guardian() {
+    let test_f = TestFixture::new().await;
+    let guardian = Keypair::new();
+    let reserve_f = setup(&test_f, &guardian).await;
+
+    // Tightening or not, the owner instruction stays with the owner
+    let ix = reserve_f.with_signer(
+        reserve_f
+            .update_reserve_config_ix(ReserveConfigUpdate::DepositLimit(1_000))
+            .unwrap(),
+        guardian.pubkey(),
+    );
+    let r = test_f
+        .send_transaction(vec![ix], &[test_f.payer_keypair(), guardian])
+        .await;
+    assert_anchor_error!(r.unwrap_err(), ErrorCode::ConstraintHasOne);
+}
+
+#[test]
+fn reserve_operations_can_only_be_paused() {
+    let paused = ReservePauseFlags::BORROW | ReservePauseFlags::FLASH_LOAN;
+    let config = ReserveConfig {
+        paused_operations: paused.bits(),
+        ..Default::default()
+    };
+
+    let more = paused | ReservePauseFlags::DEPOSIT;
+    assert!(ReserveConfigUpdate::PausedOperations(more.bits()).is_tightening(&config));
+    assert!(ReserveConfigUpdate::PausedOperations(paused.bits()).is_tightening(&config));
+    assert!(
+        !ReserveConfigUpdate::PausedOperations(ReservePauseFlags::BORROW.bits())
+            .is_tightening(&config)
+    );
+
+    // The owner resumes operations without waiting for the timelock
+    assert!(!ReserveConfigUpdate::PausedOperations(0).is_tightening(&config));
+    assert!(ReserveConfigUpdate::PausedOperations(0).is_timelock_exempt(&config));
+    assert!(
+        ReserveConfigUpdate::PausedOperations(ReservePauseFlags::BORROW.bits())
+            .is_timelock_exempt(&config)
+    );
+
+    // Repaying and liquidating stay open whatever the guardian pauses
+    for deleveraging in [ReservePauseFlags::REPAY, ReservePauseFlags::LIQUIDATION] {
+        assert!(
+            !ReserveConfigUpdate::PausedOperations((paused | deleveraging).bits())
+                .is_tightening(&config)
+        );
+    }
+}
@@ -1,21 +1,112 @@
 use anchor_lang::{prelude::*, system_program, InstructionData, ToAccountMetas};
-use anchor_spl::token;
 use anyhow::Result;
-use cluster_lend::utils::pda::lending_market_auth;
-use solana_program::{instruction::Instruction, sysvar};
-use solana_program_test::{BanksClientError, ProgramTestContext};
-use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction, signature::Keypair, signer::Signer,
-    transaction::Transaction,
+use cluster_lend::{
+    utils::pda::{lending_market_auth, reserve_registry},
+    LendingMarketUpdate, MarketRole,
 };
-use std::{cell::RefCell, mem, rc::Rc};
+use solana_program::instruction::Instruction;
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use std::{cell::RefCell, rc::Rc};
 
 pub struct LendingMarketFixture {
+    ctx: Rc<RefCell<ProgramTestContext>>,
     pub key: Pubkey,
     pub owner: Pubkey,
 }
 
 impl LendingMarketFixture {
+    pub async fn new(
+        ctx: Rc<RefCell<ProgramTestContext>>,
+        quote_currency: [u8; 32],
+        lending_market_key: &Keypair,
+    ) -> Result<LendingMarketFixture, BanksClientError> {
+        let owner = ctx.borrow().payer.pubkey();
+        let lending_market_f = LendingMarketFixture {
+            ctx: Rc::clone(&ctx),
+            key: lending_market_key.pubkey(),
+            owner,
+        };
+
+        let ix = lending_market_f.init_market_ix(quote_currency).unwrap();
+        lending_market_f
+            .send_transaction(vec![ix], &[lending_market_key])
+            .await?;
+
+        Ok(lending_market_f)
+    }
+
+    async fn send_transaction(
+        &self,
+        ixs: Vec<Instruction>,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let tx = {
+            let ctx = self.ctx.borrow();
+
+            let mut all_signers = vec![&ctx.payer];
+            all_signers.extend_from_slice(signers);
+
+            Transaction::new_signed_with_payer(
+                &ixs,
+                Some(&ctx.payer.pubkey()),
+                &all_signers,
+                ctx.last_blockhash,
+            )
+        };
+
+        self.ctx
+            .borrow_mut()
+            .banks_client
+            .process_transaction(tx)
+            .await
+    }
+
+    pub async fn try_update_market(
+        &self,
+        owner: Keypair,
+        mode: u64,
+        value: [u8; 72],
+    ) -> Result<(), BanksClientError> {
+        let mut ix = self.update_market_ix(mode, value).unwrap();
+        ix.accounts[0].pubkey = owner.pubkey();
+
+        self.send_transaction(vec![ix], &[&owner]).await
+    }
+
+    pub async fn try_propose_market_role(
+        &self,
+        owner: &Keypair,
+        role: MarketRole,
+        new_holder: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let mut ix = self.propose_role_ix(role, new_holder).unwrap();
+        ix.accounts[0].pubkey = owner.pubkey();
+
+        self.send_transaction(vec![ix], &[owner]).await
+    }
+
+    pub async fn try_accept_market_role(
+        &self,
+        pending_holder: &Keypair,
+        role: MarketRole,
+    ) -> Result<(), BanksClientError> {
+        let ix = self.accept_role_ix(role, pending_holder.pubkey()).unwrap();
+
+        self.send_transaction(vec![ix], &[pending_holder]).await
+    }
+
+    pub async fn try_cancel_market_role(
+        &self,
+        owner: &Keypair,
+        role: MarketRole,
+    ) -> Result<(), BanksClientError> {
+        let mut ix = self.cancel_role_ix(role).unwrap();
+        ix.accounts[0].pubkey = owner.pubkey();
+
+        self.send_transaction(vec![ix], &[owner]).await
+    }
+
     pub fn init_market_ix(&self, quote_currency: [u8; 32]) -> Result<Instruction> {
         let lending_market_authority = lending_market_auth(&self.key);
 
@@ -34,6 +125,22 @@ impl LendingMarketFixture {
         Ok(ix)
     }
 
+    pub fn init_reserve_registry_ix(&self) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::InitializeReserveRegistryCtx {
+            owner: self.owner,
+            lending_market: self.key,
+            reserve_registry: reserve_registry(&self.key),
+            system_program: system_program::ID,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::InitializeReserveRegistry {}.data(),
+        };
+
+        Ok(ix)
+    }
+
     pub fn update_market_ix(&self, mode: u64, value: [u8; 72]) -> Result<Instruction> {
         let accounts = cluster_lend::accounts::UpdateMarketCtx {
             owner: self.owner,
@@ -49,17 +156,103 @@ impl LendingMarketFixture {
         Ok(ix)
     }
 
-    pub fn update_owner_ix(&self, new_owner: Pubkey) -> Result<Instruction> {
-        let accounts = cluster_lend::accounts::UpdateMarketOwnerCtx {
+    pub fn update_market_config_ix(&self, update: LendingMarketUpdate) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::UpdateMarketCtx {
             owner: self.owner,
             lending_market: self.key,
-            new_owner,
             system_program: system_program::ID,
         };
         let ix = Instruction {
             program_id: cluster_lend::id(),
             accounts: accounts.to_account_metas(Some(true)),
-            data: cluster_lend::instruction::UpdateMarketOwner {}.data(),
+            data: cluster_lend::instruction::UpdateMarketConfig { update }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn guardian_update_market_ix(
+        &self,
+        guardian: Pubkey,
+        update: LendingMarketUpdate,
+    ) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::GuardianUpdateMarketCtx {
+            guardian,
+            lending_market: self.key,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::GuardianUpdateMarket { update }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn propose_role_ix(&self, role: MarketRole, new_holder: Pubkey) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::UpdateMarketCtx {
+            owner: self.owner,
+            lending_market: self.key,
+            system_program: system_program::ID,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::ProposeMarketRole { role, new_holder }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn accept_role_ix(&self, role: MarketRole, pending_holder: Pubkey) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::AcceptMarketRoleCtx {
+            pending_holder,
+            lending_market: self.key,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::AcceptMarketRole { role }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn cancel_role_ix(&self, role: MarketRole) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::UpdateMarketCtx {
+            owner: self.owner,
+            lending_market: self.key,
+            system_program: system_program::ID,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::CancelMarketRole { role }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    /// Refreshes every `(reserve, pyth_oracle)` pair in one instruction.
+    pub fn refresh_reserves_batch_ix(
+        &self,
+        reserves: &[(Pubkey, Option<Pubkey>)],
+    ) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::RefreshReservesBatchCtx {
+            lending_market: self.key,
+        };
+        let mut account_metas = accounts.to_account_metas(Some(true));
+        for (reserve, pyth_oracle) in reserves {
+            account_metas.push(AccountMeta::new(*reserve, false));
+            account_metas.push(AccountMeta::new_readonly(
+                pyth_oracle.unwrap_or(cluster_lend::ID),
+                false,
+            ));
+        }
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: account_metas,
+            data: cluster_lend::instruction::RefreshReservesBatch {}.data(),
         };
 
         Ok(ix)
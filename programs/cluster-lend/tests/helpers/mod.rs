@@ -1,3 +1,10 @@
+// Each test crate only uses part of the shared fixtures
+#![allow(
+    dead_code,
+    clippy::await_holding_refcell_ref,
+    clippy::too_many_arguments
+)]
+
 pub mod lending_market;
 pub mod obligation;
 pub mod reserve;
@@ -2,12 +2,16 @@ use anchor_lang::{prelude::*, system_program, InstructionData, ToAccountMetas};
 use anchor_spl::token;
 use anyhow::Result;
 use cluster_lend::{
-    utils::pda::{init_reserve_pdas_program_id, lending_market_auth},
+    utils::pda::{init_reserve_pdas_program_id, lending_market_auth, user_wsol},
     InitObligationArgs,
 };
 use solana_program::{instruction::Instruction, rent::Rent, sysvar::SysvarId};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use std::{cell::RefCell, rc::Rc};
 
 pub struct ObligationFixture {
+    ctx: Rc<RefCell<ProgramTestContext>>,
     pub key: Pubkey,
     pub owner: Pubkey,
     pub payer: Pubkey,
@@ -15,6 +19,70 @@ pub struct ObligationFixture {
 }
 
 impl ObligationFixture {
+    /// Initializes an obligation of `owner` in `lending_market`, paid for by the payer.
+    pub async fn new(
+        ctx: Rc<RefCell<ProgramTestContext>>,
+        lending_market: Pubkey,
+        args: InitObligationArgs,
+        owner: &Keypair,
+    ) -> Result<ObligationFixture, BanksClientError> {
+        let payer = ctx.borrow().payer.pubkey();
+        let (key, _) = Pubkey::find_program_address(
+            &[
+                &[args.tag],
+                &[args.id],
+                owner.pubkey().as_ref(),
+                lending_market.as_ref(),
+                Pubkey::default().as_ref(),
+                Pubkey::default().as_ref(),
+            ],
+            &cluster_lend::ID,
+        );
+        let obligation_f = ObligationFixture {
+            ctx: Rc::clone(&ctx),
+            key,
+            owner: owner.pubkey(),
+            payer,
+            lending_market,
+        };
+
+        let ix = obligation_f.initialize_obligation_ix(args).unwrap();
+        obligation_f.send_transaction(vec![ix], &[owner]).await?;
+
+        Ok(obligation_f)
+    }
+
+    async fn send_transaction(
+        &self,
+        ixs: Vec<Instruction>,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let tx = {
+            let ctx = self.ctx.borrow();
+
+            let mut all_signers = vec![&ctx.payer];
+            all_signers.extend_from_slice(signers);
+
+            Transaction::new_signed_with_payer(
+                &ixs,
+                Some(&ctx.payer.pubkey()),
+                &all_signers,
+                ctx.last_blockhash,
+            )
+        };
+
+        self.ctx
+            .borrow_mut()
+            .banks_client
+            .process_transaction(tx)
+            .await
+    }
+
+    pub async fn try_refresh_obligation(&self) -> Result<(), BanksClientError> {
+        let ix = self.refresh_obligation_ix().unwrap();
+        self.send_transaction(vec![ix], &[]).await
+    }
+
     pub fn initialize_obligation_ix(&self, args: InitObligationArgs) -> Result<Instruction> {
         let accounts = cluster_lend::accounts::InitializeObligationCtx {
             owner: self.owner,
@@ -37,13 +105,24 @@ impl ObligationFixture {
     }
 
     pub fn refresh_obligation_ix(&self) -> Result<Instruction> {
+        self.refresh_obligation_with_reserves_ix(&[])
+    }
+
+    /// `reserves` are the deposit reserves of the obligation followed by its borrow reserves.
+    pub fn refresh_obligation_with_reserves_ix(&self, reserves: &[Pubkey]) -> Result<Instruction> {
         let accounts = cluster_lend::accounts::RefreshObligationCtx {
             lending_market: self.lending_market,
             obligation: self.key,
         };
+        let mut account_metas = accounts.to_account_metas(Some(true));
+        account_metas.extend(
+            reserves
+                .iter()
+                .map(|reserve| AccountMeta::new_readonly(*reserve, false)),
+        );
         let ix = Instruction {
             program_id: cluster_lend::id(),
-            accounts: accounts.to_account_metas(Some(true)),
+            accounts: account_metas,
             data: cluster_lend::instruction::RefreshObligation {}.data(),
         };
 
@@ -113,6 +192,7 @@ impl ObligationFixture {
         &self,
         liquidity_amount: u64,
         borrow_reserve: Pubkey,
+        reserve_liquidity_mint: Pubkey,
         reserve_source_liquidity: Pubkey,
         borrow_reserve_liquidity_fee_receiver: Pubkey,
         user_destination_liquidity: Pubkey,
@@ -125,10 +205,13 @@ impl ObligationFixture {
             lending_market_authority,
             obligation: self.key,
             borrow_reserve,
+            reserve_liquidity_mint,
             reserve_source_liquidity,
             borrow_reserve_liquidity_fee_receiver,
-            user_destination_liquidity,
+            user_destination_liquidity: Some(user_destination_liquidity),
+            user_wsol: None,
             token_program: token::ID,
+            system_program: None,
             instruction_sysvar_account: Instructions::id(),
         };
         let ix = Instruction {
@@ -144,6 +227,7 @@ impl ObligationFixture {
         &self,
         liquidity_amount: u64,
         repay_reserve: Pubkey,
+        reserve_liquidity_mint: Pubkey,
         reserve_destination_liquidity: Pubkey,
         user_source_liquidity: Pubkey,
     ) -> Result<Instruction> {
@@ -152,9 +236,42 @@ impl ObligationFixture {
             lending_market: self.lending_market,
             obligation: self.key,
             repay_reserve,
+            reserve_liquidity_mint,
             reserve_destination_liquidity,
-            user_source_liquidity,
+            user_source_liquidity: Some(user_source_liquidity),
+            user_wsol: None,
+            token_program: token::ID,
+            system_program: None,
+            instruction_sysvar_account: Instructions::id(),
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::RepayObligationLiquidity { liquidity_amount }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    /// Like [`Self::repay_obligation_ix`], paid in lamports of the owner into a wrapped SOL
+    /// reserve.
+    pub fn repay_obligation_native_ix(
+        &self,
+        liquidity_amount: u64,
+        repay_reserve: Pubkey,
+        reserve_destination_liquidity: Pubkey,
+    ) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::RepayObligationLiquidityCtx {
+            owner: self.owner,
+            lending_market: self.lending_market,
+            obligation: self.key,
+            repay_reserve,
+            reserve_liquidity_mint: token::spl_token::native_mint::ID,
+            reserve_destination_liquidity,
+            user_source_liquidity: None,
+            user_wsol: Some(user_wsol(&self.owner)),
             token_program: token::ID,
+            system_program: Some(system_program::ID),
             instruction_sysvar_account: Instructions::id(),
         };
         let ix = Instruction {
@@ -173,6 +290,7 @@ impl ObligationFixture {
         min_acceptable_received_collateral_amount: u64,
         liquidator: Pubkey,
         repay_reserve: Pubkey,
+        repay_reserve_liquidity_mint: Pubkey,
         repay_reserve_liquidity_supply: Pubkey,
         withdraw_reserve: Pubkey,
         withdraw_liquidity_mint: Pubkey,
@@ -194,16 +312,21 @@ impl ObligationFixture {
             lending_market_authority,
             obligation: self.key,
             repay_reserve,
+            repay_reserve_liquidity_mint,
             repay_reserve_liquidity_supply,
             withdraw_reserve,
             withdraw_reserve_collateral_mint: pdas.collateral_ctoken_mint,
             withdraw_reserve_collateral_supply: pdas.collateral_supply_vault,
+            withdraw_reserve_liquidity_mint: withdraw_liquidity_mint,
             withdraw_reserve_liquidity_fee_receiver: pdas.fee_vault,
+            withdraw_reserve_insurance_vault: None,
             withdraw_reserve_liquidity_supply: pdas.liquidity_supply_vault,
             user_source_liquidity,
             user_destination_collateral,
             user_destination_liquidity,
-            token_program: token::ID,
+            collateral_token_program: token::ID,
+            repay_liquidity_token_program: token::ID,
+            withdraw_liquidity_token_program: token::ID,
             instruction_sysvar_account: Instructions::id(),
         };
         let ix = Instruction {
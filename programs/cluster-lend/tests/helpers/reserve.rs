@@ -1,10 +1,15 @@
 use anchor_lang::{prelude::*, system_program, InstructionData, ToAccountMetas};
-use anchor_spl::token::{self, spl_token::instruction, Token};
+use anchor_spl::{
+    token::{self, spl_token, Mint, Token},
+    token_2022::spl_token_2022,
+};
 use anyhow::Result;
 use cluster_lend::{
-    constants::VALUE_BYTE_ARRAY_LEN_RESERVE,
-    utils::pda::{init_reserve_pdas_program_id, lending_market_auth, InitReservePdas},
-    ReserveConfig,
+    constants::{initial_deposit_amount, VALUE_BYTE_ARRAY_LEN_RESERVE},
+    utils::pda::{
+        init_reserve_pdas_program_id, lending_market_auth, reserve_insurance_vault, user_wsol,
+    },
+    LendingMarket, ReserveConfig, ReserveConfigUpdate,
 };
 use solana_program::instruction::Instruction;
 use solana_program_test::{BanksClientError, ProgramTestContext};
@@ -13,23 +18,194 @@ use solana_sdk::{
     rent,
     signature::Keypair,
     signer::Signer,
-    sysvar::{id, instructions, SysvarId},
+    system_instruction,
+    sysvar::{instructions, SysvarId},
     transaction::Transaction,
 };
-use std::{cell::RefCell, mem, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
-use crate::spl::MintFixture;
+use crate::spl::TokenAccountFixture;
 
 pub struct ReserveFixture {
+    ctx: Rc<RefCell<ProgramTestContext>>,
     pub key: Pubkey,
     pub owner: Pubkey,
     pub payer: Pubkey,
     pub lending_market: Pubkey,
     pub liquidity_mint: Pubkey,
+    pub liquidity_token_program: Pubkey,
+    pub reserve_collateral_mint: Pubkey,
+    /// `None` for markets without a reserve registry
+    pub reserve_registry: Option<Pubkey>,
 }
 
 impl ReserveFixture {
-    pub fn initialize_reserve_ix(&self) -> Result<Instruction> {
+    /// Initializes the reserve, seeding it from a token account of the payer. The payer has to
+    /// be both the market owner and the mint authority.
+    pub async fn new(
+        ctx: Rc<RefCell<ProgramTestContext>>,
+        lending_market: Pubkey,
+        liquidity_mint: Pubkey,
+        reserve_key: &Keypair,
+    ) -> Result<ReserveFixture, BanksClientError> {
+        let payer = ctx.borrow().payer.pubkey();
+        let (liquidity_token_program, mint_decimals, reserve_registry) = {
+            let mut ctx = ctx.borrow_mut();
+            let mint_account = ctx.banks_client.get_account(liquidity_mint).await?.unwrap();
+            let mint = Mint::try_deserialize(&mut &mint_account.data[..Mint::LEN]).unwrap();
+            let market_account = ctx.banks_client.get_account(lending_market).await?.unwrap();
+            let market =
+                LendingMarket::try_deserialize(&mut market_account.data.as_slice()).unwrap();
+            (
+                mint_account.owner,
+                mint.decimals,
+                market
+                    .has_reserve_registry()
+                    .then_some(market.reserve_registry),
+            )
+        };
+
+        let reserve_f = ReserveFixture {
+            ctx: Rc::clone(&ctx),
+            key: reserve_key.pubkey(),
+            owner: payer,
+            payer,
+            lending_market,
+            liquidity_mint,
+            liquidity_token_program,
+            reserve_collateral_mint: init_reserve_pdas_program_id(
+                &cluster_lend::ID,
+                &lending_market,
+                &liquidity_mint,
+            )
+            .collateral_ctoken_mint,
+            reserve_registry,
+        };
+
+        let seed_source = if liquidity_token_program == spl_token_2022::id() {
+            TokenAccountFixture::new_token_2022(Rc::clone(&ctx), &liquidity_mint, &payer).await
+        } else {
+            TokenAccountFixture::new(Rc::clone(&ctx), &liquidity_mint, &payer).await
+        };
+        let seed_amount = initial_deposit_amount(mint_decimals);
+        // Nobody can mint wrapped SOL, the seed is wrapped from the payer's lamports instead
+        let seed_ixs = if liquidity_mint == spl_token::native_mint::ID {
+            vec![
+                system_instruction::transfer(&payer, &seed_source.key, seed_amount),
+                spl_token::instruction::sync_native(&token::ID, &seed_source.key).unwrap(),
+            ]
+        } else {
+            vec![spl_token_2022::instruction::mint_to(
+                &liquidity_token_program,
+                &liquidity_mint,
+                &seed_source.key,
+                &payer,
+                &[],
+                seed_amount,
+            )
+            .unwrap()]
+        };
+
+        let mut ixs = vec![ComputeBudgetInstruction::set_compute_unit_limit(400_000)];
+        ixs.extend(seed_ixs);
+        ixs.push(reserve_f.initialize_reserve_ix(seed_source.key).unwrap());
+        reserve_f.send_transaction(ixs, &[reserve_key]).await?;
+
+        Ok(reserve_f)
+    }
+
+    async fn send_transaction(
+        &self,
+        ixs: Vec<Instruction>,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let tx = {
+            let ctx = self.ctx.borrow();
+
+            let mut all_signers = vec![&ctx.payer];
+            all_signers.extend_from_slice(signers);
+
+            Transaction::new_signed_with_payer(
+                &ixs,
+                Some(&ctx.payer.pubkey()),
+                &all_signers,
+                ctx.last_blockhash,
+            )
+        };
+
+        self.ctx
+            .borrow_mut()
+            .banks_client
+            .process_transaction(tx)
+            .await
+    }
+
+    /// Replaces the fixture's owner as signer of `ix`.
+    pub fn with_signer(&self, mut ix: Instruction, signer: Pubkey) -> Instruction {
+        for meta in ix.accounts.iter_mut() {
+            if meta.pubkey == self.owner && meta.is_signer {
+                meta.pubkey = signer;
+            }
+        }
+        ix
+    }
+
+    async fn send_as(&self, ix: Instruction, signer: &Keypair) -> Result<(), BanksClientError> {
+        let ix = self.with_signer(ix, signer.pubkey());
+        self.send_transaction(vec![ix], &[signer]).await
+    }
+
+    pub async fn try_update_reserve(
+        &self,
+        owner: Keypair,
+        config: ReserveConfig,
+    ) -> Result<(), BanksClientError> {
+        let ix = self.update_reserve_ix(config).unwrap();
+        self.send_as(ix, &owner).await
+    }
+
+    pub async fn try_update_reserve_mode(
+        &self,
+        owner: Keypair,
+        mode: u64,
+        value: [u8; 32],
+    ) -> Result<(), BanksClientError> {
+        let ix = self.update_reserve_mode_ix(mode, value).unwrap();
+        self.send_as(ix, &owner).await
+    }
+
+    pub async fn try_update_reserve_config(
+        &self,
+        owner: &Keypair,
+        update: ReserveConfigUpdate,
+    ) -> Result<(), BanksClientError> {
+        let ix = self.update_reserve_config_ix(update).unwrap();
+        self.send_as(ix, owner).await
+    }
+
+    pub async fn try_refresh_reserve(&self, pyth_oracle: Pubkey) -> Result<(), BanksClientError> {
+        let ix = self.refresh_reserve_ix(Some(pyth_oracle)).unwrap();
+        self.send_transaction(vec![ix], &[]).await
+    }
+
+    pub async fn try_deposit(
+        &self,
+        depositor: &Keypair,
+        user_source_liquidity: Pubkey,
+        user_destination_collateral: Pubkey,
+        liquidity_amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix = self
+            .deposit_reserve_ix(
+                liquidity_amount,
+                user_source_liquidity,
+                user_destination_collateral,
+            )
+            .unwrap();
+        self.send_as(ix, depositor).await
+    }
+
+    pub fn initialize_reserve_ix(&self, initial_liquidity_source: Pubkey) -> Result<Instruction> {
         let lending_market_authority = lending_market_auth(&self.lending_market);
         let pdas = init_reserve_pdas_program_id(
             &cluster_lend::ID,
@@ -42,13 +218,17 @@ impl ReserveFixture {
             lending_market: self.lending_market,
             lending_market_authority,
             reserve: self.key,
+            reserve_registry: self.reserve_registry,
             reserve_liquidity_mint: self.liquidity_mint,
             reserve_collateral_mint: pdas.collateral_ctoken_mint,
             reserve_liquidity_supply: pdas.liquidity_supply_vault,
             reserve_collateral_supply: pdas.collateral_supply_vault,
             fee_receiver: pdas.fee_vault,
+            reserve_locked_collateral: pdas.locked_collateral_vault,
+            initial_liquidity_source,
             rent: rent::Rent::id(),
-            token_program: token::ID,
+            collateral_token_program: token::ID,
+            liquidity_token_program: self.liquidity_token_program,
             system_program: system_program::ID,
         };
         let ix = Instruction {
@@ -69,6 +249,7 @@ impl ReserveFixture {
             reserve: self.key,
             lending_market: self.lending_market,
             owner: self.owner,
+            reserve_registry: self.reserve_registry,
         };
         let ix = Instruction {
             program_id: cluster_lend::id(),
@@ -84,6 +265,7 @@ impl ReserveFixture {
             reserve: self.key,
             lending_market: self.lending_market,
             owner: self.owner,
+            reserve_registry: self.reserve_registry,
         };
         let ix = Instruction {
             program_id: cluster_lend::id(),
@@ -94,6 +276,293 @@ impl ReserveFixture {
         Ok(ix)
     }
 
+    pub fn update_reserve_config_ix(&self, update: ReserveConfigUpdate) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::UpdateReserveCtx {
+            reserve: self.key,
+            lending_market: self.lending_market,
+            owner: self.owner,
+            reserve_registry: self.reserve_registry,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::UpdateReserveConfig { update }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn guardian_update_reserve_ix(
+        &self,
+        guardian: Pubkey,
+        update: ReserveConfigUpdate,
+    ) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::GuardianUpdateReserveCtx {
+            guardian,
+            lending_market: self.lending_market,
+            reserve: self.key,
+            reserve_registry: self.reserve_registry,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::GuardianUpdateReserve { update }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn close_reserve_ix(
+        &self,
+        lending_market_owner_ata: Pubkey,
+        reserve_insurance_vault: Option<Pubkey>,
+    ) -> Result<Instruction> {
+        let pdas = init_reserve_pdas_program_id(
+            &cluster_lend::ID,
+            &self.lending_market,
+            &self.liquidity_mint,
+        );
+
+        let accounts = cluster_lend::accounts::CloseReserveCtx {
+            owner: self.owner,
+            lending_market: self.lending_market,
+            lending_market_authority: lending_market_auth(&self.lending_market),
+            reserve: self.key,
+            reserve_registry: self.reserve_registry,
+            reserve_liquidity_mint: self.liquidity_mint,
+            reserve_liquidity_supply: pdas.liquidity_supply_vault,
+            fee_receiver: pdas.fee_vault,
+            reserve_collateral_mint: pdas.collateral_ctoken_mint,
+            reserve_collateral_supply: pdas.collateral_supply_vault,
+            reserve_locked_collateral: Some(pdas.locked_collateral_vault),
+            reserve_insurance_vault,
+            lending_market_owner_ata,
+            collateral_token_program: token::ID,
+            liquidity_token_program: self.liquidity_token_program,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::CloseReserve {}.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn skim_reserve_excess_ix(
+        &self,
+        liquidity_destination: Pubkey,
+        collateral_destination: Pubkey,
+    ) -> Result<Instruction> {
+        let pdas = init_reserve_pdas_program_id(
+            &cluster_lend::ID,
+            &self.lending_market,
+            &self.liquidity_mint,
+        );
+
+        let accounts = cluster_lend::accounts::SkimReserveExcessCtx {
+            owner: self.owner,
+            lending_market: self.lending_market,
+            lending_market_authority: lending_market_auth(&self.lending_market),
+            reserve: self.key,
+            reserve_liquidity_mint: self.liquidity_mint,
+            reserve_liquidity_supply: pdas.liquidity_supply_vault,
+            reserve_collateral_supply: pdas.collateral_supply_vault,
+            liquidity_destination,
+            collateral_destination,
+            collateral_token_program: token::ID,
+            liquidity_token_program: self.liquidity_token_program,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::SkimReserveExcess {}.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn initialize_reserve_insurance_ix(&self) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::InitializeReserveInsuranceCtx {
+            owner: self.owner,
+            lending_market: self.lending_market,
+            lending_market_authority: lending_market_auth(&self.lending_market),
+            reserve: self.key,
+            reserve_liquidity_mint: self.liquidity_mint,
+            insurance_vault: self.insurance_vault(),
+            token_program: self.liquidity_token_program,
+            system_program: system_program::ID,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::InitializeReserveInsurance {}.data(),
+        };
+
+        Ok(ix)
+    }
+
+    fn reserve_insurance_accounts(
+        &self,
+        owner_token_account: Pubkey,
+    ) -> cluster_lend::accounts::ReserveInsuranceCtx {
+        cluster_lend::accounts::ReserveInsuranceCtx {
+            owner: self.owner,
+            lending_market: self.lending_market,
+            lending_market_authority: lending_market_auth(&self.lending_market),
+            reserve: self.key,
+            reserve_liquidity_mint: self.liquidity_mint,
+            insurance_vault: self.insurance_vault(),
+            owner_token_account,
+            token_program: self.liquidity_token_program,
+        }
+    }
+
+    pub fn deposit_reserve_insurance_ix(
+        &self,
+        owner_token_account: Pubkey,
+        amount: u64,
+    ) -> Result<Instruction> {
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: self
+                .reserve_insurance_accounts(owner_token_account)
+                .to_account_metas(Some(true)),
+            data: cluster_lend::instruction::DepositReserveInsurance { amount }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn withdraw_reserve_insurance_ix(
+        &self,
+        owner_token_account: Pubkey,
+        amount: u64,
+    ) -> Result<Instruction> {
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: self
+                .reserve_insurance_accounts(owner_token_account)
+                .to_account_metas(Some(true)),
+            data: cluster_lend::instruction::WithdrawReserveInsurance { amount }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn queue_insurance_withdrawal_ix(
+        &self,
+        destination_token_account: Pubkey,
+        queued_update: Pubkey,
+        amount: u64,
+    ) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::QueueInsuranceWithdrawalCtx {
+            owner: self.owner,
+            lending_market: self.lending_market,
+            reserve: self.key,
+            destination_token_account,
+            queued_update,
+            system_program: system_program::ID,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::QueueInsuranceWithdrawal { amount }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn execute_insurance_withdrawal_ix(
+        &self,
+        destination_token_account: Pubkey,
+        queued_update: Pubkey,
+    ) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::ExecuteInsuranceWithdrawalCtx {
+            payer: self.owner,
+            lending_market: self.lending_market,
+            lending_market_authority: lending_market_auth(&self.lending_market),
+            reserve: self.key,
+            reserve_liquidity_mint: self.liquidity_mint,
+            insurance_vault: self.insurance_vault(),
+            destination_token_account,
+            queued_update,
+            token_program: self.liquidity_token_program,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::ExecuteInsuranceWithdrawal {}.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn insurance_vault(&self) -> Pubkey {
+        reserve_insurance_vault(&self.lending_market, &self.liquidity_mint)
+    }
+
+    pub fn get_reserve_fee_ledger_ix(&self) -> Result<Instruction> {
+        let accounts = cluster_lend::accounts::GetReserveFeeLedgerCtx { reserve: self.key };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::GetReserveFeeLedger {}.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn audit_reserve_ix(&self) -> Result<Instruction> {
+        let pdas = init_reserve_pdas_program_id(
+            &cluster_lend::ID,
+            &self.lending_market,
+            &self.liquidity_mint,
+        );
+
+        let accounts = cluster_lend::accounts::AuditReserveCtx {
+            lending_market: self.lending_market,
+            reserve: self.key,
+            reserve_liquidity_mint: self.liquidity_mint,
+            reserve_liquidity_supply: pdas.liquidity_supply_vault,
+            fee_receiver: pdas.fee_vault,
+            reserve_collateral_mint: pdas.collateral_ctoken_mint,
+            reserve_collateral_supply: pdas.collateral_supply_vault,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::AuditReserve {}.data(),
+        };
+
+        Ok(ix)
+    }
+
+    pub fn audit_reserve_and_pause_ix(&self) -> Result<Instruction> {
+        let pdas = init_reserve_pdas_program_id(
+            &cluster_lend::ID,
+            &self.lending_market,
+            &self.liquidity_mint,
+        );
+
+        let accounts = cluster_lend::accounts::AuditReserveAndPauseCtx {
+            lending_market: self.lending_market,
+            reserve: self.key,
+            reserve_liquidity_mint: self.liquidity_mint,
+            reserve_liquidity_supply: pdas.liquidity_supply_vault,
+            fee_receiver: pdas.fee_vault,
+            reserve_collateral_mint: pdas.collateral_ctoken_mint,
+            reserve_collateral_supply: pdas.collateral_supply_vault,
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::AuditReserveAndPause {}.data(),
+        };
+
+        Ok(ix)
+    }
+
     pub fn refresh_reserve_ix(&self, pyth_oracle: Option<Pubkey>) -> Result<Instruction> {
         let accounts = cluster_lend::accounts::RefreshReserveCtx {
             reserve: self.key,
@@ -128,11 +597,53 @@ impl ReserveFixture {
             lending_market: self.lending_market,
             owner: self.owner,
             lending_market_authority,
+            reserve_liquidity_mint: self.liquidity_mint,
+            reserve_collateral_mint: pdas.collateral_ctoken_mint,
+            reserve_liquidity_supply: pdas.liquidity_supply_vault,
+            user_source_liquidity: Some(user_source_liquidity),
+            user_wsol: None,
+            user_destination_collateral,
+            collateral_token_program: Token::id(),
+            liquidity_token_program: self.liquidity_token_program,
+            system_program: None,
+            instruction_sysvar_account: instructions::id(),
+        };
+        let ix = Instruction {
+            program_id: cluster_lend::id(),
+            accounts: accounts.to_account_metas(Some(true)),
+            data: cluster_lend::instruction::DepositReserveLiquidity { liquidity_amount }.data(),
+        };
+
+        Ok(ix)
+    }
+
+    /// Like [`Self::deposit_reserve_ix`], paid in lamports of `owner` into a wrapped SOL reserve.
+    pub fn deposit_reserve_native_ix(
+        &self,
+        owner: Pubkey,
+        liquidity_amount: u64,
+        user_destination_collateral: Pubkey,
+    ) -> Result<Instruction> {
+        let pdas = init_reserve_pdas_program_id(
+            &cluster_lend::ID,
+            &self.lending_market,
+            &self.liquidity_mint,
+        );
+
+        let accounts = cluster_lend::accounts::DepositReserveLiquidityCtx {
+            reserve: self.key,
+            lending_market: self.lending_market,
+            owner,
+            lending_market_authority: lending_market_auth(&self.lending_market),
+            reserve_liquidity_mint: self.liquidity_mint,
             reserve_collateral_mint: pdas.collateral_ctoken_mint,
             reserve_liquidity_supply: pdas.liquidity_supply_vault,
-            user_source_liquidity,
+            user_source_liquidity: None,
+            user_wsol: Some(user_wsol(&owner)),
             user_destination_collateral,
-            token_program: Token::id(),
+            collateral_token_program: Token::id(),
+            liquidity_token_program: self.liquidity_token_program,
+            system_program: Some(system_program::ID),
             instruction_sysvar_account: instructions::id(),
         };
         let ix = Instruction {
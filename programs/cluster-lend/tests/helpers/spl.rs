@@ -1,10 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{
-    spl_token::{
+use anchor_spl::{
+    token::{
+        spl_token::{self, instruction::initialize_mint},
+        Mint, TokenAccount,
+    },
+    token_2022::spl_token_2022::{
         self,
-        instruction::{initialize_mint, mint_to},
+        extension::{transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType},
+        instruction::{initialize_account3, initialize_mint2, mint_to},
     },
-    Mint, TokenAccount,
 };
 use solana_program_test::ProgramTestContext;
 use solana_sdk::{
@@ -20,6 +24,7 @@ pub struct MintFixture {
     pub ctx: Rc<RefCell<ProgramTestContext>>,
     pub key: Pubkey,
     pub mint: Mint,
+    pub token_program: Pubkey,
 }
 
 impl MintFixture {
@@ -74,6 +79,78 @@ impl MintFixture {
             ctx: ctx_ref,
             key: keypair.pubkey(),
             mint,
+            token_program: spl_token::id(),
+        }
+    }
+
+    /// Token-2022 mint with a transfer fee of `transfer_fee_bps`, capped at `maximum_fee`.
+    pub async fn new_with_transfer_fee(
+        ctx: Rc<RefCell<ProgramTestContext>>,
+        mint_decimals: u8,
+        transfer_fee_bps: u16,
+        maximum_fee: u64,
+    ) -> MintFixture {
+        let ctx_ref = Rc::clone(&ctx);
+        let keypair = Keypair::new();
+        let mint = {
+            let mut ctx = ctx.borrow_mut();
+
+            let rent = ctx.banks_client.get_rent().await.unwrap();
+            let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+                ExtensionType::TransferFeeConfig,
+            ])
+            .unwrap();
+
+            let init_account_ix = create_account(
+                &ctx.payer.pubkey(),
+                &keypair.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &spl_token_2022::id(),
+            );
+            let init_transfer_fee_ix = initialize_transfer_fee_config(
+                &spl_token_2022::id(),
+                &keypair.pubkey(),
+                None,
+                None,
+                transfer_fee_bps,
+                maximum_fee,
+            )
+            .unwrap();
+            let init_mint_ix = initialize_mint2(
+                &spl_token_2022::id(),
+                &keypair.pubkey(),
+                &ctx.payer.pubkey(),
+                None,
+                mint_decimals,
+            )
+            .unwrap();
+
+            let tx = Transaction::new_signed_with_payer(
+                &[init_account_ix, init_transfer_fee_ix, init_mint_ix],
+                Some(&ctx.payer.pubkey()),
+                &[&ctx.payer, &keypair],
+                ctx.last_blockhash,
+            );
+
+            ctx.banks_client.process_transaction(tx).await.unwrap();
+
+            let mint_account = ctx
+                .banks_client
+                .get_account(keypair.pubkey())
+                .await
+                .unwrap()
+                .unwrap();
+
+            // The base mint layout is shared by both token programs, extensions follow it
+            Mint::try_deserialize(&mut &mint_account.data[..Mint::LEN]).unwrap()
+        };
+
+        MintFixture {
+            ctx: ctx_ref,
+            key: keypair.pubkey(),
+            mint,
+            token_program: spl_token_2022::id(),
         }
     }
 
@@ -87,7 +164,7 @@ impl MintFixture {
             .await
             .unwrap()
             .unwrap();
-        self.mint = Mint::try_deserialize(&mut mint_account.data.as_slice()).unwrap();
+        self.mint = Mint::try_deserialize(&mut &mint_account.data[..Mint::LEN]).unwrap();
     }
 
     pub async fn mint_to<T: Into<f64>>(&mut self, dest: &Pubkey, ui_amount: T) {
@@ -116,7 +193,7 @@ impl MintFixture {
     pub fn make_mint_to_ix(&self, dest: &Pubkey, amount: u64) -> Instruction {
         let ctx = self.ctx.borrow();
         mint_to(
-            &spl_token::id(),
+            &self.token_program,
             &self.key,
             dest,
             &ctx.payer.pubkey(),
@@ -131,8 +208,11 @@ impl MintFixture {
         owner: &Keypair,
         ui_amount: T,
     ) -> TokenAccountFixture {
-        let token_account_f =
-            TokenAccountFixture::new(self.ctx.clone(), &self.key, &owner.pubkey()).await;
+        let token_account_f = if self.token_program == spl_token_2022::id() {
+            TokenAccountFixture::new_token_2022(self.ctx.clone(), &self.key, &owner.pubkey()).await
+        } else {
+            TokenAccountFixture::new(self.ctx.clone(), &self.key, &owner.pubkey()).await
+        };
 
         let mint_to_ix = self.make_mint_to_ix(
             &token_account_f.key,
@@ -241,7 +321,7 @@ impl TokenAccountFixture {
         Self {
             ctx: ctx_ref.clone(),
             key: keypair.pubkey(),
-            token: get_and_deserialize(ctx_ref.clone(), keypair.pubkey()).await,
+            token: get_token_account(ctx_ref.clone(), keypair.pubkey()).await,
         }
     }
 
@@ -254,11 +334,58 @@ impl TokenAccountFixture {
         TokenAccountFixture::new_with_keypair(ctx, mint_pk, owner_pk, &keypair).await
     }
 
+    /// Token-2022 account with room for the transfer fee withheld on it.
+    pub async fn new_token_2022(
+        ctx: Rc<RefCell<ProgramTestContext>>,
+        mint_pk: &Pubkey,
+        owner_pk: &Pubkey,
+    ) -> TokenAccountFixture {
+        let ctx_ref = ctx.clone();
+        let keypair = Keypair::new();
+
+        {
+            let mut ctx = ctx.borrow_mut();
+
+            let rent = ctx.banks_client.get_rent().await.unwrap();
+            let space =
+                ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&[
+                    ExtensionType::TransferFeeAmount,
+                ])
+                .unwrap();
+
+            let init_account_ix = create_account(
+                &ctx.payer.pubkey(),
+                &keypair.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &spl_token_2022::id(),
+            );
+            let init_token_ix =
+                initialize_account3(&spl_token_2022::id(), &keypair.pubkey(), mint_pk, owner_pk)
+                    .unwrap();
+
+            let tx = Transaction::new_signed_with_payer(
+                &[init_account_ix, init_token_ix],
+                Some(&ctx.payer.pubkey()),
+                &[&ctx.payer, &keypair],
+                ctx.last_blockhash,
+            );
+
+            ctx.banks_client.process_transaction(tx).await.unwrap();
+        }
+
+        Self {
+            ctx: ctx_ref.clone(),
+            key: keypair.pubkey(),
+            token: get_token_account(ctx_ref, keypair.pubkey()).await,
+        }
+    }
+
     pub async fn fetch(
         ctx: Rc<RefCell<ProgramTestContext>>,
         address: Pubkey,
     ) -> TokenAccountFixture {
-        let token = get_and_deserialize(ctx.clone(), address).await;
+        let token = get_token_account(ctx.clone(), address).await;
 
         Self {
             ctx: ctx.clone(),
@@ -268,9 +395,7 @@ impl TokenAccountFixture {
     }
 
     pub async fn balance(&self) -> u64 {
-        let token_account: TokenAccount = get_and_deserialize(self.ctx.clone(), self.key).await;
-
-        token_account.amount
+        balance_of(self.ctx.clone(), self.key).await
     }
 }
 
@@ -283,8 +408,18 @@ pub async fn get_and_deserialize<T: AccountDeserialize>(
     T::try_deserialize(&mut account.data.as_slice()).unwrap()
 }
 
+/// Reads the base token account state, which Token-2022 accounts extend.
+pub async fn get_token_account(
+    ctx: Rc<RefCell<ProgramTestContext>>,
+    pubkey: Pubkey,
+) -> TokenAccount {
+    let mut ctx = ctx.borrow_mut();
+    let account = ctx.banks_client.get_account(pubkey).await.unwrap().unwrap();
+    TokenAccount::try_deserialize(&mut &account.data[..TokenAccount::LEN]).unwrap()
+}
+
 pub async fn balance_of(ctx: Rc<RefCell<ProgramTestContext>>, pubkey: Pubkey) -> u64 {
-    let token_account: TokenAccount = get_and_deserialize(ctx, pubkey).await;
+    let token_account = get_token_account(ctx, pubkey).await;
 
     token_account.amount
 }
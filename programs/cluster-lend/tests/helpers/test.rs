@@ -6,18 +6,19 @@ use anyhow::Result;
 use bincode::deserialize;
 use cluster_lend::{
     utils::{BorrowRateCurve, CurvePoint},
-    AssetTier, PythConfiguration, ReserveConfig, ReserveFees, ReserveStatus, TokenInfo,
-    WithdrawalCaps,
+    ParameterRamp, PythConfiguration, ReserveConfig, ReserveFees, TokenInfo, WithdrawalCaps,
 };
 use pyth_sdk_solana::state::SolanaPriceAccount;
 use solana_program::{hash::Hash, sysvar};
 use solana_program_test::*;
 use solana_sdk::{
     account::AccountSharedData, instruction::Instruction, pubkey, signature::Keypair,
-    signer::Signer, transaction::Transaction,
+    signer::Signer, transaction::Transaction, transaction_context::TransactionReturnData,
 };
 
 use crate::{
+    lending_market::LendingMarketFixture,
+    reserve::ReserveFixture,
     spl::MintFixture,
     utils::{clone_keypair, create_pyth_price_account},
 };
@@ -46,6 +47,7 @@ pub const TEST_RESERVE_CONFIG: ReserveConfig = ReserveConfig {
     min_liquidation_bonus_bps: 200,
     max_liquidation_bonus_bps: 500,
     bad_debt_liquidation_bonus_bps: 10,
+    insurance_fee_pct: 0,
 
     deleveraging_margin_call_period_secs: 259200, // 3 days
     deleveraging_threshold_slots_per_bps: 7200,   // 0.01% per hour
@@ -133,10 +135,22 @@ pub const TEST_RESERVE_CONFIG: ReserveConfig = ReserveConfig {
         config_interval_length_seconds: 0,
     },
 
-    padding_0: [0; 4],
+    padding_0: [0; 3],
     padding_1: 0,
     padding_2: [0; 7],
-    reserved: [0; 32],
+    loan_to_value_ramp: INACTIVE_RAMP,
+    liquidation_threshold_ramp: INACTIVE_RAMP,
+    paused_operations: 0,
+    padding_3: [0; 6],
+    reserved: [0; 25],
+};
+
+const INACTIVE_RAMP: ParameterRamp = ParameterRamp {
+    start_ts: 0,
+    duration_secs: 0,
+    start_value: 0,
+    target_value: 0,
+    padding: [0; 6],
 };
 
 pub struct TestFixture {
@@ -198,6 +212,46 @@ impl TestFixture {
         }
     }
 
+    /// USDC quoted market owned by the payer.
+    pub async fn new_lending_market(&self) -> LendingMarketFixture {
+        LendingMarketFixture::new(
+            Rc::clone(&self.context),
+            USDC_QUOTE_CURRENCY,
+            &Keypair::new(),
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Like [`Self::new_lending_market`], with the reserve registry initialized.
+    pub async fn new_lending_market_with_registry(&self) -> LendingMarketFixture {
+        let lending_market_f = self.new_lending_market().await;
+        self.send_transaction(
+            vec![lending_market_f.init_reserve_registry_ix().unwrap()],
+            &[self.payer_keypair()],
+        )
+        .await
+        .unwrap();
+
+        lending_market_f
+    }
+
+    /// Hidden reserve of `mint`, listed by the payer.
+    pub async fn new_reserve(
+        &self,
+        lending_market_f: &LendingMarketFixture,
+        mint: Pubkey,
+    ) -> ReserveFixture {
+        ReserveFixture::new(
+            Rc::clone(&self.context),
+            lending_market_f.key,
+            mint,
+            &Keypair::new(),
+        )
+        .await
+        .unwrap()
+    }
+
     pub async fn load_and_deserialize<T: anchor_lang::AccountDeserialize>(
         &self,
         address: &Pubkey,
@@ -214,6 +268,25 @@ impl TestFixture {
         T::try_deserialize(&mut ai.data.as_slice()).unwrap()
     }
 
+    pub async fn account_exists(&self, address: &Pubkey) -> bool {
+        self.context
+            .borrow_mut()
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    pub async fn get_lamports(&self, address: &Pubkey) -> u64 {
+        self.context
+            .borrow_mut()
+            .banks_client
+            .get_balance(*address)
+            .await
+            .unwrap()
+    }
+
     pub fn payer(&self) -> Pubkey {
         self.context.borrow().payer.pubkey()
     }
@@ -255,6 +328,10 @@ impl TestFixture {
         self.context.borrow_mut().set_sysvar(&clock);
     }
 
+    pub fn warp_to_slot(&self, slot: u64) {
+        self.context.borrow_mut().warp_to_slot(slot).unwrap();
+    }
+
     pub async fn get_minimum_rent_for_size(&self, size: usize) -> u64 {
         self.context
             .borrow_mut()
@@ -274,6 +351,15 @@ impl TestFixture {
             .unwrap()
     }
 
+    /// Waits for a new blockhash, so that a transaction that failed before can be sent again.
+    pub async fn refresh_blockhash(&self) {
+        self.context
+            .borrow_mut()
+            .get_new_latest_blockhash()
+            .await
+            .unwrap();
+    }
+
     pub async fn get_slot(&self) -> u64 {
         self.context
             .borrow_mut()
@@ -303,17 +389,45 @@ impl TestFixture {
         ixs: Vec<Instruction>,
         signers: &[Keypair],
     ) -> Result<(), BanksClientError> {
-        let ctx = self.context.borrow();
-
+        let signers: Vec<&Keypair> = signers.iter().collect();
         let tx = Transaction::new_signed_with_payer(
             &ixs,
             Some(&self.payer()),
-            signers,
-            ctx.last_blockhash,
+            &signers,
+            self.context.borrow().last_blockhash,
         );
 
-        ctx.banks_client.process_transaction(tx).await?;
+        self.context
+            .borrow_mut()
+            .banks_client
+            .process_transaction(tx)
+            .await?;
 
         Ok(())
     }
+
+    /// Simulates the transaction and returns what its last instruction set as return data.
+    pub async fn simulate_return_data(
+        &self,
+        ixs: Vec<Instruction>,
+        signers: &[Keypair],
+    ) -> Option<TransactionReturnData> {
+        let signers: Vec<&Keypair> = signers.iter().collect();
+        let tx = Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&self.payer()),
+            &signers,
+            self.context.borrow().last_blockhash,
+        );
+
+        self.context
+            .borrow_mut()
+            .banks_client
+            .simulate_transaction(tx)
+            .await
+            .unwrap()
+            .simulation_details
+            .unwrap()
+            .return_data
+    }
 }
@@ -1,6 +1,5 @@
-use cluster_lend::utils::pyth;
 use pyth_sdk_solana::state::{
-    AccountType, PriceAccount, PriceInfo, PriceStatus, Rational, MAGIC, VERSION_2,
+    AccountType, PriceInfo, PriceStatus, Rational, SolanaPriceAccount, MAGIC, VERSION_2,
 };
 
 use solana_program::pubkey;
@@ -38,7 +37,7 @@ pub fn create_pyth_price_account(
     let native_price = ui_price * 10_i64.pow(mint_decimals as u32);
     Account {
         lamports: 1_000_000,
-        data: bytemuck::bytes_of(&PriceAccount {
+        data: bytemuck::bytes_of(&SolanaPriceAccount {
             prod: mint,
             agg: PriceInfo {
                 conf: 0,
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::Pubkey;
+use cluster_lend::{
+    lending_market::lending_operations,
+    utils::{BigFraction, Fraction},
+    AssetTier, Obligation, ObligationCollateral, Reserve,
+};
+
+const SLOT: u64 = 100;
+
+fn reserve_with_debt(borrowed: u64) -> Reserve {
+    let mut reserve = Reserve::default();
+    reserve.liquidity.available_amount = 1_000;
+    reserve.liquidity.borrowed_amount_sf = Fraction::from(borrowed).to_bits();
+    reserve.last_update.update_slot(SLOT, None);
+    reserve
+}
+
+fn obligation_with_debt(reserve: Pubkey, borrowed: u64) -> Obligation {
+    let mut obligation = Obligation::default();
+    let (liquidity, _) = obligation
+        .find_or_add_liquidity_to_borrows(
+            reserve,
+            BigFraction::from(Fraction::ONE),
+            AssetTier::Regular,
+        )
+        .unwrap();
+    liquidity.borrow(Fraction::from(borrowed));
+    obligation
+}
+
+#[test]
+fn insurance_fee_needs_a_vault() {
+    let mut reserve = Reserve::default();
+    reserve.config.insurance_fee_pct = 25;
+    assert_eq!(reserve.insurance_fee(100), 0);
+
+    reserve.liquidity.insurance_vault = Pubkey::new_unique();
+    assert_eq!(reserve.insurance_fee(100), 25);
+    assert_eq!(reserve.insurance_fee(3), 0);
+}
+
+#[test]
+fn insurance_covers_bad_debt_before_depositors() {
+    let reserve_pk = Pubkey::new_unique();
+    let mut reserve = reserve_with_debt(500);
+    let mut obligation = obligation_with_debt(reserve_pk, 500);
+    let total_supply = reserve.liquidity.total_supply().unwrap();
+
+    let result =
+        lending_operations::socialize_loss(&mut reserve, &mut obligation, reserve_pk, 200, SLOT)
+            .unwrap();
+
+    assert_eq!(result.insurance_amount, 200);
+    assert_eq!(result.socialized_amount_f, Fraction::from_num(300));
+    assert!(obligation.borrows_empty());
+    assert_eq!(reserve.liquidity.borrowed_amount_sf, 0);
+    assert_eq!(reserve.liquidity.available_amount, 1_200);
+    // Depositors only lose what the insurance vault could not cover
+    assert_eq!(
+        reserve.liquidity.total_supply().unwrap(),
+        total_supply - Fraction::from_num(300)
+    );
+}
+
+#[test]
+fn fully_insured_bad_debt_is_not_socialized() {
+    let reserve_pk = Pubkey::new_unique();
+    let mut reserve = reserve_with_debt(500);
+    let mut obligation = obligation_with_debt(reserve_pk, 500);
+    let total_supply = reserve.liquidity.total_supply().unwrap();
+
+    let result =
+        lending_operations::socialize_loss(&mut reserve, &mut obligation, reserve_pk, 10_000, SLOT)
+            .unwrap();
+
+    assert_eq!(result.insurance_amount, 500);
+    assert_eq!(result.socialized_amount_f, Fraction::ZERO);
+    assert_eq!(reserve.liquidity.total_supply().unwrap(), total_supply);
+}
+
+#[test]
+fn cannot_socialize_an_obligation_with_collateral() {
+    let reserve_pk = Pubkey::new_unique();
+    let mut reserve = reserve_with_debt(500);
+    let mut obligation = obligation_with_debt(reserve_pk, 500);
+    obligation.deposits[0] = ObligationCollateral::new(Pubkey::new_unique());
+
+    assert!(
+        lending_operations::socialize_loss(&mut reserve, &mut obligation, reserve_pk, 0, SLOT)
+            .is_err()
+    );
+}
@@ -2,15 +2,8 @@
 mod helpers;
 use std::rc::Rc;
 
-use anchor_lang::context;
-use anchor_spl::{
-    associated_token::{create, get_associated_token_address, AssociatedToken},
-    token::{self, spl_token::state::Account, TokenAccount},
-};
-use cluster_lend::{
-    constants::ten_pow, errors::LendingError, InitObligationArgs, LendingMarket, Reserve,
-    ReserveStatus, UpdateLendingMarketMode,
-};
+use anchor_spl::token::TokenAccount;
+use cluster_lend::{constants::ten_pow, InitObligationArgs};
 use lending_market::LendingMarketFixture;
 
 use obligation::ObligationFixture;
@@ -18,21 +11,16 @@ use reserve::ReserveFixture;
 use solana_program_test::*;
 
 use helpers::*;
-use solana_sdk::{
-    clock::{self, Clock},
-    signature::Keypair,
-    signer::Signer,
-};
+use solana_sdk::{signature::Keypair, signer::Signer};
 use spl::TokenAccountFixture;
 use test::{
-    TestFixture, PYTH_SOL_FEED, SOL_MINT_DECIMALS, SOL_QUOTE_CURRENCY, TEST_RESERVE_CONFIG,
-    USDC_MINT_DECIMALS, USDC_QUOTE_CURRENCY,
+    TestFixture, PYTH_SOL_FEED, TEST_RESERVE_CONFIG, USDC_MINT_DECIMALS, USDC_QUOTE_CURRENCY,
 };
 
 #[tokio::test]
 async fn success_lending() {
     // Create market & reserve
-    let mut test_f = TestFixture::new().await;
+    let test_f = TestFixture::new().await;
 
     let lending_market_key = Keypair::new();
     let lending_market_f = LendingMarketFixture::new(
@@ -43,7 +31,7 @@ async fn success_lending() {
     .await
     .unwrap();
 
-    let mut reserve_f = ReserveFixture::new(
+    let reserve_f = ReserveFixture::new(
         Rc::clone(&test_f.context),
         lending_market_f.key,
         test_f.usdc_mint.key,
@@ -98,8 +86,8 @@ async fn success_lending() {
     let borrower = Keypair::new();
     let obligation_f = ObligationFixture::new(
         test_f.context.clone(),
-        reserve_f,
-        InitObligationArgs { id: 1, tag: 0 },
+        lending_market_f.key,
+        InitObligationArgs { id: 0, tag: 0 },
         &borrower,
     )
     .await
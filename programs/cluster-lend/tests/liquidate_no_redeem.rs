@@ -0,0 +1,37 @@
+use cluster_lend::{utils::Fraction, Reserve};
+
+/// Fully utilized reserve where one cToken is worth two tokens
+fn utilized_reserve() -> Reserve {
+    let mut reserve = Reserve::default();
+    reserve.liquidity.available_amount = 0;
+    reserve.liquidity.borrowed_amount_sf = Fraction::from_num(2_000).to_bits();
+    reserve.collateral.mint_total_supply = 1_000;
+    reserve
+}
+
+#[test]
+fn collateral_fee_keeps_exchange_rate() {
+    let mut reserve = utilized_reserve();
+    let exchange_rate = reserve.collateral_exchange_rate().unwrap();
+
+    let protocol_fee = reserve.burn_collateral_as_protocol_fees(100).unwrap();
+
+    assert_eq!(protocol_fee, 200);
+    assert_eq!(reserve.collateral.mint_total_supply, 900);
+    assert_eq!(
+        reserve.liquidity.accumulated_protocol_fees_sf,
+        Fraction::from_num(200).to_bits()
+    );
+    assert_eq!(reserve.liquidity.fee_ledger.liquidation_fees, 200);
+    assert_eq!(reserve.collateral_exchange_rate().unwrap(), exchange_rate);
+}
+
+#[test]
+fn collateral_fee_is_redeemed_once_liquidity_returns() {
+    let mut reserve = utilized_reserve();
+    reserve.burn_collateral_as_protocol_fees(100).unwrap();
+    assert_eq!(reserve.calculate_redeem_fees().unwrap(), 0);
+
+    reserve.liquidity.available_amount = 150;
+    assert_eq!(reserve.calculate_redeem_fees().unwrap(), 150);
+}
@@ -0,0 +1,102 @@
+use cluster_lend::{
+    lending_market::liquidation_operations,
+    utils::{Fraction, FractionExtra},
+    LendingMarket, Obligation, Reserve,
+};
+
+const UNHEALTHY_SLOT: u64 = 1_000;
+
+fn reserve() -> Reserve {
+    let mut reserve = Reserve::default();
+    reserve.config.min_liquidation_bonus_bps = 200;
+    reserve.config.max_liquidation_bonus_bps = 1_000;
+    reserve.config.bad_debt_liquidation_bonus_bps = 100;
+    reserve
+}
+
+fn market(ramp_slots: u64) -> LendingMarket {
+    LendingMarket {
+        liquidation_bonus_ramp_slots: ramp_slots,
+        ..Default::default()
+    }
+}
+
+/// 90% LTV against an 80% liquidation threshold
+fn unhealthy_obligation() -> Obligation {
+    let mut obligation = Obligation {
+        deposited_value_sf: Fraction::from_num(1_000).to_bits(),
+        unhealthy_borrow_value_sf: Fraction::from_num(800).to_bits(),
+        borrow_factor_adjusted_debt_value_sf: Fraction::from_num(900).to_bits(),
+        ..Default::default()
+    };
+    obligation.update_unhealthy_since_slot(UNHEALTHY_SLOT);
+    obligation
+}
+
+fn bonus_bps(market: &LendingMarket, obligation: &Obligation, slot: u64) -> u64 {
+    liquidation_operations::check_liquidate_obligation(
+        market,
+        &reserve(),
+        &reserve(),
+        obligation,
+        slot,
+        None,
+    )
+    .unwrap()
+    .liquidation_bonus_rate
+    .to_bps()
+    .unwrap()
+}
+
+#[test]
+fn unhealthy_since_slot_tracks_the_first_unhealthy_refresh() {
+    let mut obligation = unhealthy_obligation();
+    assert_eq!(obligation.unhealthy_since_slot, UNHEALTHY_SLOT);
+
+    // Later refreshes keep the auction start
+    obligation.update_unhealthy_since_slot(UNHEALTHY_SLOT + 50);
+    assert_eq!(obligation.unhealthy_since_slot, UNHEALTHY_SLOT);
+
+    obligation.borrow_factor_adjusted_debt_value_sf = Fraction::from_num(700).to_bits();
+    obligation.update_unhealthy_since_slot(UNHEALTHY_SLOT + 100);
+    assert_eq!(obligation.unhealthy_since_slot, 0);
+
+    let mut obligation = Obligation::default();
+    obligation.update_unhealthy_since_slot(UNHEALTHY_SLOT);
+    assert_eq!(obligation.unhealthy_since_slot, 0);
+}
+
+#[test]
+fn bonus_ramps_from_min_to_max() {
+    let market = market(100);
+    let obligation = unhealthy_obligation();
+
+    assert_eq!(bonus_bps(&market, &obligation, UNHEALTHY_SLOT), 200);
+    assert_eq!(bonus_bps(&market, &obligation, UNHEALTHY_SLOT + 50), 600);
+    assert_eq!(bonus_bps(&market, &obligation, UNHEALTHY_SLOT + 100), 1_000);
+    assert_eq!(
+        bonus_bps(&market, &obligation, UNHEALTHY_SLOT + 10_000),
+        1_000
+    );
+}
+
+#[test]
+fn bonus_follows_ltv_without_ramp() {
+    let obligation = unhealthy_obligation();
+
+    assert_eq!(bonus_bps(&market(0), &obligation, UNHEALTHY_SLOT), 1_000);
+    assert_eq!(
+        bonus_bps(&market(0), &obligation, UNHEALTHY_SLOT + 50),
+        1_000
+    );
+}
+
+#[test]
+fn ramped_bonus_stays_below_bad_debt() {
+    let market = market(100);
+    let mut obligation = unhealthy_obligation();
+    // 95% LTV leaves 5% of collateral value above the debt
+    obligation.borrow_factor_adjusted_debt_value_sf = Fraction::from_num(950).to_bits();
+
+    assert_eq!(bonus_bps(&market, &obligation, UNHEALTHY_SLOT + 100), 500);
+}
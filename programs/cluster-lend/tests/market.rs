@@ -2,13 +2,13 @@
 mod helpers;
 use std::rc::Rc;
 
-use cluster_lend::{LendingMarket, UpdateLendingMarketMode};
+use cluster_lend::{LendingMarket, MarketRole, UpdateLendingMarketMode};
 use lending_market::LendingMarketFixture;
 
 use solana_program_test::*;
 
 use helpers::*;
-use solana_sdk::{signature::Keypair, signer::Signer};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
 use test::{TestFixture, SOL_QUOTE_CURRENCY, USDC_QUOTE_CURRENCY};
 
 #[tokio::test]
@@ -99,7 +99,17 @@ async fn success_update_lending_market_owner() {
     let owner = test_f.payer_keypair();
     let new_owner = Keypair::new();
     let r = lending_market_f
-        .try_update_market_owner(owner, new_owner.pubkey())
+        .try_propose_market_role(&owner, MarketRole::Owner, new_owner.pubkey())
+        .await;
+    assert!(r.is_ok());
+
+    // Ownership does not move until the proposed owner accepts
+    let lending_market: LendingMarket = test_f.load_and_deserialize(&lending_market_f.key).await;
+    assert_eq!(lending_market.owner, owner.pubkey());
+    assert_eq!(lending_market.pending_owner, new_owner.pubkey());
+
+    let r = lending_market_f
+        .try_accept_market_role(&new_owner, MarketRole::Owner)
         .await;
     assert!(r.is_ok());
 
@@ -109,6 +119,46 @@ async fn success_update_lending_market_owner() {
     // Check properties
     assert_eq!(lending_market.quote_currency, USDC_QUOTE_CURRENCY);
     assert_eq!(lending_market.owner, new_owner.pubkey());
+    assert_eq!(lending_market.pending_owner, Pubkey::default());
+}
+
+#[tokio::test]
+async fn failure_accept_lending_market_owner_by_other_signer() {
+    let test_f = TestFixture::new().await;
+
+    let lending_market_f = LendingMarketFixture::new(
+        Rc::clone(&test_f.context),
+        USDC_QUOTE_CURRENCY,
+        &Keypair::new(),
+    )
+    .await
+    .unwrap();
+
+    let owner = test_f.payer_keypair();
+    let new_owner = Keypair::new();
+    lending_market_f
+        .try_propose_market_role(&owner, MarketRole::Owner, new_owner.pubkey())
+        .await
+        .unwrap();
+
+    let r = lending_market_f
+        .try_accept_market_role(&Keypair::new(), MarketRole::Owner)
+        .await;
+    assert!(r.is_err());
+
+    // A cancelled proposal can no longer be accepted
+    lending_market_f
+        .try_cancel_market_role(&owner, MarketRole::Owner)
+        .await
+        .unwrap();
+    let r = lending_market_f
+        .try_accept_market_role(&new_owner, MarketRole::Owner)
+        .await;
+    assert!(r.is_err());
+
+    let lending_market: LendingMarket = test_f.load_and_deserialize(&lending_market_f.key).await;
+    assert_eq!(lending_market.owner, owner.pubkey());
+    assert_eq!(lending_market.pending_owner, Pubkey::default());
 }
 
 #[tokio::test]
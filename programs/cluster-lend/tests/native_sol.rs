@@ -0,0 +1,285 @@
+#[cfg(test)]
+mod helpers;
+use std::rc::Rc;
+
+use anchor_spl::token::{spl_token::native_mint, TokenAccount};
+use cluster_lend::{
+    utils::pda::{init_reserve_pdas_program_id, user_wsol},
+    InitObligationArgs, Reserve,
+};
+use lending_market::LendingMarketFixture;
+use obligation::ObligationFixture;
+use reserve::ReserveFixture;
+use solana_program_test::*;
+
+use helpers::*;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    system_instruction,
+};
+use spl::TokenAccountFixture;
+use test::{TestFixture, PYTH_SOL_FEED, TEST_RESERVE_CONFIG};
+
+async fn new_active_reserve(
+    test_f: &TestFixture,
+    lending_market_f: &LendingMarketFixture,
+    mint: Pubkey,
+) -> ReserveFixture {
+    let reserve_f = test_f.new_reserve(lending_market_f, mint).await;
+    reserve_f
+        .try_update_reserve(test_f.payer_keypair(), TEST_RESERVE_CONFIG)
+        .await
+        .unwrap();
+    reserve_f
+}
+
+async fn fund(test_f: &TestFixture, to: &Pubkey, lamports: u64) {
+    test_f
+        .send_transaction(
+            vec![system_instruction::transfer(&test_f.payer(), to, lamports)],
+            &[test_f.payer_keypair()],
+        )
+        .await
+        .unwrap();
+}
+
+/// Deposits `amount` lamports of a new funded user into the wrapped SOL reserve, returning the
+/// user and their collateral account.
+async fn deposit_native(
+    test_f: &TestFixture,
+    reserve_f: &ReserveFixture,
+    amount: u64,
+) -> (Keypair, TokenAccountFixture) {
+    let depositor = Keypair::new();
+    fund(test_f, &depositor.pubkey(), 2 * amount).await;
+    let collateral_f = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &reserve_f.reserve_collateral_mint,
+        &depositor.pubkey(),
+    )
+    .await;
+
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .deposit_reserve_native_ix(depositor.pubkey(), amount, collateral_f.key)
+                .unwrap()],
+            &[test_f.payer_keypair(), depositor.insecure_clone()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    (depositor, collateral_f)
+}
+
+#[tokio::test]
+async fn success_deposit_native_sol() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market().await;
+    let reserve_f = new_active_reserve(&test_f, &lending_market_f, native_mint::ID).await;
+
+    let amount = LAMPORTS_PER_SOL;
+    let (depositor, collateral_f) = deposit_native(&test_f, &reserve_f, amount).await;
+
+    // The temporary wSOL account is closed and its rent returned, the payer covers the fees
+    assert_eq!(test_f.get_lamports(&depositor.pubkey()).await, amount);
+    assert!(!test_f.account_exists(&user_wsol(&depositor.pubkey())).await);
+    assert_eq!(collateral_f.balance().await, amount);
+
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    let seed_amount = reserve.liquidity.available_amount - amount;
+    assert_eq!(
+        test_f
+            .get_lamports(
+                &init_reserve_pdas_program_id(
+                    &cluster_lend::ID,
+                    &lending_market_f.key,
+                    &native_mint::ID
+                )
+                .liquidity_supply_vault
+            )
+            .await,
+        test_f.get_minimum_rent_for_size(TokenAccount::LEN).await + seed_amount + amount
+    );
+}
+
+#[tokio::test]
+async fn success_deposit_native_sol_with_prefunded_user_wsol() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market().await;
+    let reserve_f = new_active_reserve(&test_f, &lending_market_f, native_mint::ID).await;
+
+    let depositor = Keypair::new();
+    let amount = LAMPORTS_PER_SOL;
+    fund(&test_f, &depositor.pubkey(), 2 * amount).await;
+    // Anyone can send lamports to the predictable PDA ahead of the deposit
+    let prefunded = 1_000_000;
+    fund(&test_f, &user_wsol(&depositor.pubkey()), prefunded).await;
+    let collateral_f = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &reserve_f.reserve_collateral_mint,
+        &depositor.pubkey(),
+    )
+    .await;
+
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .deposit_reserve_native_ix(depositor.pubkey(), amount, collateral_f.key)
+                .unwrap()],
+            &[test_f.payer_keypair(), depositor.insecure_clone()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    // The prefunded lamports go towards the rent and end up with the depositor on close
+    assert_eq!(
+        test_f.get_lamports(&depositor.pubkey()).await,
+        amount + prefunded
+    );
+    assert!(!test_f.account_exists(&user_wsol(&depositor.pubkey())).await);
+    assert_eq!(collateral_f.balance().await, amount);
+}
+
+#[tokio::test]
+async fn success_repay_native_sol() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market().await;
+    let usdc_reserve_f = new_active_reserve(&test_f, &lending_market_f, test_f.usdc_mint.key).await;
+    let sol_reserve_f = new_active_reserve(&test_f, &lending_market_f, native_mint::ID).await;
+    let usdc_pdas = init_reserve_pdas_program_id(
+        &cluster_lend::ID,
+        &lending_market_f.key,
+        &test_f.usdc_mint.key,
+    );
+    let sol_pdas =
+        init_reserve_pdas_program_id(&cluster_lend::ID, &lending_market_f.key, &native_mint::ID);
+
+    // Borrowing needs a fresh price
+    let now = test_f.get_clock().await.unix_timestamp;
+    test_f.set_pyth_oracle_timestamp(PYTH_SOL_FEED, now).await;
+
+    // Liquidity to borrow from
+    deposit_native(&test_f, &sol_reserve_f, 10 * LAMPORTS_PER_SOL).await;
+
+    // The borrower posts 100 USDC as collateral
+    let borrower = Keypair::new();
+    fund(&test_f, &borrower.pubkey(), 10 * LAMPORTS_PER_SOL).await;
+    let usdc_ata_f = test_f
+        .usdc_mint
+        .create_token_account_and_mint_to(&borrower, 100)
+        .await;
+    let usdc_collateral_f = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &usdc_reserve_f.reserve_collateral_mint,
+        &borrower.pubkey(),
+    )
+    .await;
+    usdc_reserve_f
+        .try_deposit(
+            &borrower,
+            usdc_ata_f.key,
+            usdc_collateral_f.key,
+            usdc_ata_f.balance().await,
+        )
+        .await
+        .unwrap();
+    let obligation_f = ObligationFixture::new(
+        Rc::clone(&test_f.context),
+        lending_market_f.key,
+        InitObligationArgs { id: 0, tag: 0 },
+        &borrower,
+    )
+    .await
+    .unwrap();
+
+    let r = test_f
+        .send_transaction(
+            vec![
+                usdc_reserve_f
+                    .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                    .unwrap(),
+                obligation_f.refresh_obligation_ix().unwrap(),
+                obligation_f
+                    .deposit_obligation_ix(
+                        usdc_collateral_f.balance().await,
+                        usdc_reserve_f.key,
+                        usdc_pdas.collateral_supply_vault,
+                        usdc_collateral_f.key,
+                    )
+                    .unwrap(),
+            ],
+            &[test_f.payer_keypair(), borrower.insecure_clone()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    let wsol_ata_f = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &native_mint::ID,
+        &borrower.pubkey(),
+    )
+    .await;
+    let r = test_f
+        .send_transaction(
+            vec![
+                usdc_reserve_f
+                    .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                    .unwrap(),
+                sol_reserve_f
+                    .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                    .unwrap(),
+                obligation_f
+                    .refresh_obligation_with_reserves_ix(&[usdc_reserve_f.key])
+                    .unwrap(),
+                obligation_f
+                    .borrow_obligation_ix(
+                        LAMPORTS_PER_SOL,
+                        sol_reserve_f.key,
+                        native_mint::ID,
+                        sol_pdas.liquidity_supply_vault,
+                        sol_pdas.fee_vault,
+                        wsol_ata_f.key,
+                    )
+                    .unwrap(),
+            ],
+            &[test_f.payer_keypair(), borrower.insecure_clone()],
+        )
+        .await;
+    assert!(r.is_ok());
+    assert_eq!(wsol_ata_f.balance().await, LAMPORTS_PER_SOL);
+
+    // Repaid out of the borrower's lamports, not the wSOL token account
+    let lamports_before = test_f.get_lamports(&borrower.pubkey()).await;
+    let r = test_f
+        .send_transaction(
+            vec![
+                usdc_reserve_f
+                    .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                    .unwrap(),
+                sol_reserve_f
+                    .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                    .unwrap(),
+                obligation_f
+                    .refresh_obligation_with_reserves_ix(&[usdc_reserve_f.key, sol_reserve_f.key])
+                    .unwrap(),
+                obligation_f
+                    .repay_obligation_native_ix(
+                        u64::MAX,
+                        sol_reserve_f.key,
+                        sol_pdas.liquidity_supply_vault,
+                    )
+                    .unwrap(),
+            ],
+            &[test_f.payer_keypair(), borrower.insecure_clone()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    let sol_reserve: Reserve = test_f.load_and_deserialize(&sol_reserve_f.key).await;
+    assert_eq!(sol_reserve.liquidity.borrowed_amount_sf, 0);
+    assert!(!test_f.account_exists(&user_wsol(&borrower.pubkey())).await);
+    assert_eq!(wsol_ata_f.balance().await, LAMPORTS_PER_SOL);
+    let repaid = lamports_before - test_f.get_lamports(&borrower.pubkey()).await;
+    assert!(repaid >= LAMPORTS_PER_SOL);
+}
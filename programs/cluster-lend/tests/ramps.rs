@@ -0,0 +1,50 @@
+use cluster_lend::{ParameterRamp, ReserveConfig};
+
+fn config(ltv_pct: u8, liquidation_threshold_pct: u8) -> ReserveConfig {
+    ReserveConfig {
+        loan_to_value_pct: ltv_pct,
+        liquidation_threshold_pct,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn ramp_interpolates_linearly() {
+    let ramp = ParameterRamp::new(80, 40, 1_000, 400);
+
+    assert_eq!(ramp.value_at(0), 80);
+    assert_eq!(ramp.value_at(1_000), 80);
+    assert_eq!(ramp.value_at(1_100), 70);
+    assert_eq!(ramp.value_at(1_200), 60);
+    assert_eq!(ramp.value_at(1_400), 40);
+    assert_eq!(ramp.value_at(10_000), 40);
+}
+
+#[test]
+fn ramp_deactivates_at_target() {
+    let mut config = config(70, 80);
+    config.loan_to_value_ramp = ParameterRamp::new(70, 0, 0, 100);
+
+    config.apply_ramps(50);
+    assert_eq!(config.loan_to_value_pct, 35);
+    assert!(config.loan_to_value_ramp.is_active());
+
+    config.apply_ramps(100);
+    assert_eq!(config.loan_to_value_pct, 0);
+    assert!(!config.loan_to_value_ramp.is_active());
+
+    // Nothing moves once the ramp is done
+    config.loan_to_value_pct = 10;
+    config.apply_ramps(200);
+    assert_eq!(config.loan_to_value_pct, 10);
+}
+
+#[test]
+fn ltv_stays_below_ramping_liquidation_threshold() {
+    let mut config = config(70, 80);
+    config.liquidation_threshold_ramp = ParameterRamp::new(80, 20, 0, 60);
+
+    config.apply_ramps(30);
+    assert_eq!(config.liquidation_threshold_pct, 50);
+    assert_eq!(config.loan_to_value_pct, 50);
+}
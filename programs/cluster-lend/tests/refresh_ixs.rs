@@ -0,0 +1,176 @@
+use anchor_lang::{
+    prelude::{AccountMeta, ProgramError, Pubkey},
+    solana_program::instruction::Instruction,
+    InstructionData,
+};
+use cluster_lend::{
+    instruction, lending_market::ix_utils::InstructionLoader, utils::check_refresh_with_loader,
+};
+
+struct TxInstructions {
+    ixs: Vec<Instruction>,
+    current_idx: u16,
+}
+
+impl InstructionLoader for TxInstructions {
+    fn load_instruction_at(&self, index: usize) -> Result<Instruction, ProgramError> {
+        self.ixs
+            .get(index)
+            .cloned()
+            .ok_or(ProgramError::InvalidArgument)
+    }
+
+    fn load_current_index(&self) -> Result<u16, ProgramError> {
+        Ok(self.current_idx)
+    }
+}
+
+fn tx(ixs: Vec<Instruction>) -> TxInstructions {
+    TxInstructions {
+        current_idx: ixs.len() as u16,
+        ixs,
+    }
+}
+
+fn refresh_reserve(reserve: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cluster_lend::ID,
+        accounts: vec![
+            AccountMeta::new(reserve, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ],
+        data: instruction::RefreshReserve {}.data(),
+    }
+}
+
+fn refresh_reserves_batch(reserves: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)];
+    for reserve in reserves {
+        accounts.push(AccountMeta::new(*reserve, false));
+        accounts.push(AccountMeta::new_readonly(cluster_lend::ID, false));
+    }
+    Instruction {
+        program_id: cluster_lend::ID,
+        accounts,
+        data: instruction::RefreshReservesBatch {}.data(),
+    }
+}
+
+fn refresh_obligation(obligation: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cluster_lend::ID,
+        accounts: vec![
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new(obligation, false),
+        ],
+        data: instruction::RefreshObligation {}.data(),
+    }
+}
+
+fn other_program_ix(accounts: Vec<AccountMeta>) -> Instruction {
+    Instruction {
+        program_id: Pubkey::new_unique(),
+        accounts,
+        data: vec![],
+    }
+}
+
+#[test]
+fn success_adjacent_refreshes() {
+    let reserve = Pubkey::new_unique();
+    let obligation = Pubkey::new_unique();
+    let ixs = tx(vec![
+        refresh_reserve(reserve),
+        refresh_obligation(obligation),
+    ]);
+
+    check_refresh_with_loader(&ixs, &[reserve], &obligation).unwrap();
+}
+
+#[test]
+fn success_unrelated_ixs_in_between() {
+    let reserve = Pubkey::new_unique();
+    let obligation = Pubkey::new_unique();
+    let ixs = tx(vec![
+        refresh_reserve(reserve),
+        other_program_ix(vec![AccountMeta::new(Pubkey::new_unique(), true)]),
+        refresh_obligation(obligation),
+        other_program_ix(vec![AccountMeta::new_readonly(reserve, false)]),
+    ]);
+
+    check_refresh_with_loader(&ixs, &[reserve], &obligation).unwrap();
+}
+
+#[test]
+fn success_reserves_refreshed_in_any_order() {
+    let withdraw_reserve = Pubkey::new_unique();
+    let repay_reserve = Pubkey::new_unique();
+    let obligation = Pubkey::new_unique();
+    let ixs = tx(vec![
+        refresh_reserve(repay_reserve),
+        refresh_reserve(withdraw_reserve),
+        refresh_obligation(obligation),
+    ]);
+
+    check_refresh_with_loader(&ixs, &[withdraw_reserve, repay_reserve], &obligation).unwrap();
+}
+
+#[test]
+fn success_batch_refresh() {
+    let withdraw_reserve = Pubkey::new_unique();
+    let repay_reserve = Pubkey::new_unique();
+    let obligation = Pubkey::new_unique();
+    let ixs = tx(vec![
+        refresh_reserves_batch(&[withdraw_reserve, repay_reserve]),
+        refresh_obligation(obligation),
+    ]);
+
+    check_refresh_with_loader(&ixs, &[withdraw_reserve, repay_reserve], &obligation).unwrap();
+}
+
+#[test]
+fn failure_reserve_written_after_refresh() {
+    let reserve = Pubkey::new_unique();
+    let obligation = Pubkey::new_unique();
+    let ixs = tx(vec![
+        refresh_reserve(reserve),
+        other_program_ix(vec![AccountMeta::new(reserve, false)]),
+        refresh_obligation(obligation),
+    ]);
+
+    assert!(check_refresh_with_loader(&ixs, &[reserve], &obligation).is_err());
+}
+
+#[test]
+fn failure_obligation_written_after_refresh() {
+    let reserve = Pubkey::new_unique();
+    let obligation = Pubkey::new_unique();
+    let ixs = tx(vec![
+        refresh_reserve(reserve),
+        refresh_obligation(obligation),
+        other_program_ix(vec![AccountMeta::new(obligation, false)]),
+    ]);
+
+    assert!(check_refresh_with_loader(&ixs, &[reserve], &obligation).is_err());
+}
+
+#[test]
+fn failure_reserve_refreshed_after_obligation_only() {
+    let reserve = Pubkey::new_unique();
+    let obligation = Pubkey::new_unique();
+    let ixs = tx(vec![
+        refresh_obligation(obligation),
+        refresh_reserve(reserve),
+    ]);
+
+    assert!(check_refresh_with_loader(&ixs, &[reserve], &obligation).is_err());
+}
+
+#[test]
+fn failure_missing_obligation_refresh() {
+    let reserve = Pubkey::new_unique();
+    let obligation = Pubkey::new_unique();
+    let ixs = tx(vec![refresh_reserve(reserve)]);
+
+    assert!(check_refresh_with_loader(&ixs, &[reserve], &obligation).is_err());
+}
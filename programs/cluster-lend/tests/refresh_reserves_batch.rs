@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod helpers;
+use std::rc::Rc;
+
+use cluster_lend::{
+    constants::ten_pow, errors::LendingError, utils::pda::init_reserve_pdas_program_id,
+    InitObligationArgs,
+};
+use lending_market::LendingMarketFixture;
+use obligation::ObligationFixture;
+use reserve::ReserveFixture;
+use solana_program_test::*;
+
+use helpers::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use spl::TokenAccountFixture;
+use test::{TestFixture, PYTH_SOL_FEED, SOL_MINT_DECIMALS, TEST_RESERVE_CONFIG};
+
+async fn new_active_reserve(
+    test_f: &TestFixture,
+    lending_market_f: &LendingMarketFixture,
+    mint: Pubkey,
+) -> ReserveFixture {
+    let reserve_f = test_f.new_reserve(lending_market_f, mint).await;
+    reserve_f
+        .try_update_reserve(test_f.payer_keypair(), TEST_RESERVE_CONFIG)
+        .await
+        .unwrap();
+    reserve_f
+}
+
+/// Deposits `ui_amount` of new tokens of `depositor` into the reserve, returning the collateral.
+async fn deposit(
+    test_f: &TestFixture,
+    reserve_f: &ReserveFixture,
+    depositor: &Keypair,
+    ui_amount: u64,
+) -> TokenAccountFixture {
+    let mint_f = if reserve_f.liquidity_mint == test_f.usdc_mint.key {
+        &test_f.usdc_mint
+    } else {
+        &test_f.sol_mint
+    };
+    let liquidity_f = mint_f
+        .create_token_account_and_mint_to(depositor, ui_amount as f64)
+        .await;
+    let collateral_f = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &reserve_f.reserve_collateral_mint,
+        &depositor.pubkey(),
+    )
+    .await;
+    reserve_f
+        .try_deposit(
+            depositor,
+            liquidity_f.key,
+            collateral_f.key,
+            liquidity_f.balance().await,
+        )
+        .await
+        .unwrap();
+
+    collateral_f
+}
+
+#[tokio::test]
+async fn success_borrow_after_batch_refresh() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market().await;
+    let usdc_reserve_f = new_active_reserve(&test_f, &lending_market_f, test_f.usdc_mint.key).await;
+    let sol_reserve_f = new_active_reserve(&test_f, &lending_market_f, test_f.sol_mint.key).await;
+    let usdc_pdas = init_reserve_pdas_program_id(
+        &cluster_lend::ID,
+        &lending_market_f.key,
+        &test_f.usdc_mint.key,
+    );
+    let sol_pdas = init_reserve_pdas_program_id(
+        &cluster_lend::ID,
+        &lending_market_f.key,
+        &test_f.sol_mint.key,
+    );
+
+    // Borrowing needs a fresh price
+    let now = test_f.get_clock().await.unix_timestamp;
+    test_f.set_pyth_oracle_timestamp(PYTH_SOL_FEED, now).await;
+
+    deposit(&test_f, &sol_reserve_f, &Keypair::new(), 10).await;
+
+    // The borrower posts 100 USDC as collateral
+    let borrower = Keypair::new();
+    let usdc_collateral_f = deposit(&test_f, &usdc_reserve_f, &borrower, 100).await;
+    let obligation_f = ObligationFixture::new(
+        Rc::clone(&test_f.context),
+        lending_market_f.key,
+        InitObligationArgs { id: 0, tag: 0 },
+        &borrower,
+    )
+    .await
+    .unwrap();
+    let r = test_f
+        .send_transaction(
+            vec![
+                usdc_reserve_f
+                    .refresh_reserve_ix(Some(PYTH_SOL_FEED))
+                    .unwrap(),
+                obligation_f.refresh_obligation_ix().unwrap(),
+                obligation_f
+                    .deposit_obligation_ix(
+                        usdc_collateral_f.balance().await,
+                        usdc_reserve_f.key,
+                        usdc_pdas.collateral_supply_vault,
+                        usdc_collateral_f.key,
+                    )
+                    .unwrap(),
+            ],
+            &[test_f.payer_keypair(), borrower.insecure_clone()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    let borrow_amount = ten_pow(SOL_MINT_DECIMALS as usize);
+    let sol_ata_f = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &test_f.sol_mint.key,
+        &borrower.pubkey(),
+    )
+    .await;
+    let borrow_ix = obligation_f
+        .borrow_obligation_ix(
+            borrow_amount,
+            sol_reserve_f.key,
+            test_f.sol_mint.key,
+            sol_pdas.liquidity_supply_vault,
+            sol_pdas.fee_vault,
+            sol_ata_f.key,
+        )
+        .unwrap();
+    let refresh_obligation_ix = obligation_f
+        .refresh_obligation_with_reserves_ix(&[usdc_reserve_f.key])
+        .unwrap();
+
+    // The borrow reserve has to be part of the batch
+    let r = test_f
+        .send_transaction(
+            vec![
+                lending_market_f
+                    .refresh_reserves_batch_ix(&[(usdc_reserve_f.key, Some(PYTH_SOL_FEED))])
+                    .unwrap(),
+                refresh_obligation_ix.clone(),
+                borrow_ix.clone(),
+            ],
+            &[test_f.payer_keypair(), borrower.insecure_clone()],
+        )
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::IncorrectInstructionInPosition);
+
+    let r = test_f
+        .send_transaction(
+            vec![
+                lending_market_f
+                    .refresh_reserves_batch_ix(&[
+                        (usdc_reserve_f.key, Some(PYTH_SOL_FEED)),
+                        (sol_reserve_f.key, Some(PYTH_SOL_FEED)),
+                    ])
+                    .unwrap(),
+                refresh_obligation_ix,
+                borrow_ix,
+            ],
+            &[test_f.payer_keypair(), borrower.insecure_clone()],
+        )
+        .await;
+    assert!(r.is_ok());
+    assert_eq!(sol_ata_f.balance().await, borrow_amount);
+}
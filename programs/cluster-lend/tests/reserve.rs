@@ -2,27 +2,15 @@
 mod helpers;
 use std::rc::Rc;
 
-use anchor_lang::AnchorSerialize;
-use cluster_lend::{
-    errors::LendingError, LendingMarket, Reserve, ReserveStatus, UpdateConfigMode,
-    UpdateLendingMarketMode,
-};
+use cluster_lend::{errors::LendingError, Reserve, ReserveStatus, UpdateConfigMode};
 use lending_market::LendingMarketFixture;
 
 use reserve::ReserveFixture;
 use solana_program_test::*;
 
 use helpers::*;
-use solana_sdk::{
-    clock::{self, Clock},
-    pubkey::Pubkey,
-    signature::Keypair,
-    signer::Signer,
-};
-use test::{
-    TestFixture, PYTH_SOL_FEED, SOL_MINT_DECIMALS, SOL_QUOTE_CURRENCY, TEST_RESERVE_CONFIG,
-    USDC_QUOTE_CURRENCY,
-};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use test::{TestFixture, PYTH_SOL_FEED, TEST_RESERVE_CONFIG, USDC_QUOTE_CURRENCY};
 
 #[tokio::test]
 async fn success_init_update_reserve() {
@@ -38,7 +26,7 @@ async fn success_init_update_reserve() {
     .unwrap();
 
     let reserve_key = Keypair::new();
-    let mut reserve_f = ReserveFixture::new(
+    let reserve_f = ReserveFixture::new(
         Rc::clone(&test_f.context),
         lending_market_f.key,
         test_f.usdc_mint.key,
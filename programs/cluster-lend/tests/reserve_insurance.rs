@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod helpers;
+
+use cluster_lend::{errors::LendingError, LendingMarketUpdate};
+use lending_market::LendingMarketFixture;
+use reserve::ReserveFixture;
+use solana_program_test::*;
+
+use helpers::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl::{balance_of, TokenAccountFixture};
+use test::TestFixture;
+
+const TIMELOCK_SECS: u64 = 3_600;
+const START_TS: i64 = 1_700_000_000;
+
+/// A reserve with 100 tokens in its insurance vault, and the owner's token account it pays from.
+async fn setup(
+    test_f: &TestFixture,
+) -> (LendingMarketFixture, ReserveFixture, TokenAccountFixture) {
+    let lending_market_f = test_f.new_lending_market().await;
+    let reserve_f = test_f
+        .new_reserve(&lending_market_f, test_f.usdc_mint.key)
+        .await;
+    let owner_ata = test_f
+        .usdc_mint
+        .create_token_account_and_mint_to(&test_f.payer_keypair(), 100)
+        .await;
+    let amount = owner_ata.balance().await;
+
+    let r = test_f
+        .send_transaction(
+            vec![
+                reserve_f.initialize_reserve_insurance_ix().unwrap(),
+                reserve_f
+                    .deposit_reserve_insurance_ix(owner_ata.key, amount)
+                    .unwrap(),
+            ],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    (lending_market_f, reserve_f, owner_ata)
+}
+
+#[tokio::test]
+async fn success_withdraw_insurance_without_timelock() {
+    let test_f = TestFixture::new().await;
+    let (_lending_market_f, reserve_f, owner_ata) = setup(&test_f).await;
+
+    // Capped at the vault balance
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .withdraw_reserve_insurance_ix(owner_ata.key, u64::MAX)
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    assert_eq!(
+        balance_of(test_f.context.clone(), reserve_f.insurance_vault()).await,
+        0
+    );
+    assert!(owner_ata.balance().await > 0);
+}
+
+#[tokio::test]
+async fn success_withdraw_insurance_after_timelock() {
+    let test_f = TestFixture::new().await;
+    let (lending_market_f, reserve_f, owner_ata) = setup(&test_f).await;
+    let insurance_amount = balance_of(test_f.context.clone(), reserve_f.insurance_vault()).await;
+
+    test_f.set_time(START_TS);
+    let r = test_f
+        .send_transaction(
+            vec![lending_market_f
+                .update_market_config_ix(LendingMarketUpdate::ConfigTimelockSecs(TIMELOCK_SECS))
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    // The owner can no longer drain the vault at once
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .withdraw_reserve_insurance_ix(owner_ata.key, insurance_amount)
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::UpdateRequiresTimelock);
+
+    let queued_update = Keypair::new();
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .queue_insurance_withdrawal_ix(
+                    owner_ata.key,
+                    queued_update.pubkey(),
+                    insurance_amount,
+                )
+                .unwrap()],
+            &[test_f.payer_keypair(), queued_update.insecure_clone()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .execute_insurance_withdrawal_ix(owner_ata.key, queued_update.pubkey())
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::QueuedUpdateNotReady);
+
+    test_f.set_time(START_TS + TIMELOCK_SECS as i64);
+    // The destination is fixed when the withdrawal is queued
+    let other_ata = test_f
+        .usdc_mint
+        .create_token_account_and_mint_to(&Keypair::new(), 0)
+        .await;
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .execute_insurance_withdrawal_ix(other_ata.key, queued_update.pubkey())
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::QueuedUpdateMismatch);
+
+    test_f.refresh_blockhash().await;
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .execute_insurance_withdrawal_ix(owner_ata.key, queued_update.pubkey())
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    assert_eq!(owner_ata.balance().await, insurance_amount);
+    assert_eq!(
+        balance_of(test_f.context.clone(), reserve_f.insurance_vault()).await,
+        0
+    );
+    assert!(!test_f.account_exists(&queued_update.pubkey()).await);
+}
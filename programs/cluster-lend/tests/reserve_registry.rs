@@ -0,0 +1,192 @@
+#[cfg(test)]
+mod helpers;
+
+use anchor_lang::{error::ErrorCode, prelude::Pubkey};
+use cluster_lend::{
+    constants::MAX_RESERVES_PER_MARKET, errors::LendingError, utils::pda::reserve_registry,
+    LendingMarket, ReserveConfigUpdate, ReserveRegistry, ReserveStatus,
+};
+use solana_program_test::*;
+
+use helpers::*;
+use solana_sdk::{signature::Keypair, signer::Signer, system_instruction};
+use test::{TestFixture, TEST_RESERVE_CONFIG};
+
+fn registry_with(count: usize) -> (ReserveRegistry, Vec<Pubkey>) {
+    let mut registry = ReserveRegistry::default();
+    let reserves: Vec<Pubkey> = (0..count).map(|_| Pubkey::new_unique()).collect();
+    for (slot, reserve) in reserves.iter().enumerate() {
+        registry
+            .add_reserve(
+                *reserve,
+                Pubkey::new_unique(),
+                ReserveStatus::Hidden,
+                slot as u64,
+            )
+            .unwrap();
+    }
+    (registry, reserves)
+}
+
+#[test]
+fn add_reserve_appends_in_creation_order() {
+    let (mut registry, reserves) = registry_with(2);
+
+    let entries = registry.reserves();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].reserve, reserves[0]);
+    assert_eq!(entries[1].reserve, reserves[1]);
+    assert_eq!(entries[1].creation_slot, 1);
+    assert_eq!(entries[1].status(), ReserveStatus::Hidden);
+
+    // The same reserve cannot be registered twice
+    assert!(registry
+        .add_reserve(reserves[0], Pubkey::new_unique(), ReserveStatus::Active, 2)
+        .is_err());
+    assert_eq!(registry.reserves_count, 2);
+}
+
+#[test]
+fn add_reserve_fails_when_full() {
+    let (mut registry, _) = registry_with(MAX_RESERVES_PER_MARKET);
+
+    assert!(registry
+        .add_reserve(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            ReserveStatus::Hidden,
+            0
+        )
+        .is_err());
+    assert_eq!(registry.reserves_count, MAX_RESERVES_PER_MARKET as u64);
+}
+
+#[test]
+fn set_status_updates_only_the_given_reserve() {
+    let (mut registry, reserves) = registry_with(2);
+
+    registry
+        .set_status(&reserves[1], ReserveStatus::Active)
+        .unwrap();
+
+    assert_eq!(registry.reserves()[0].status(), ReserveStatus::Hidden);
+    assert_eq!(registry.reserves()[1].status(), ReserveStatus::Active);
+    assert!(registry
+        .set_status(&Pubkey::new_unique(), ReserveStatus::Active)
+        .is_err());
+}
+
+#[tokio::test]
+async fn success_registry_follows_reserve_status() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market_with_registry().await;
+
+    let registry_key = reserve_registry(&lending_market_f.key);
+    let lending_market: LendingMarket = test_f.load_and_deserialize(&lending_market_f.key).await;
+    assert_eq!(lending_market.reserve_registry, registry_key);
+
+    // Reserves created afterwards are registered
+    let reserve_f = test_f
+        .new_reserve(&lending_market_f, test_f.usdc_mint.key)
+        .await;
+    assert_eq!(reserve_f.reserve_registry, Some(registry_key));
+
+    let registry: ReserveRegistry = test_f.load_and_deserialize(&registry_key).await;
+    assert_eq!(registry.reserves().len(), 1);
+    assert_eq!(registry.reserves()[0].reserve, reserve_f.key);
+    assert_eq!(registry.reserves()[0].mint, test_f.usdc_mint.key);
+    assert_eq!(registry.reserves()[0].status(), ReserveStatus::Hidden);
+
+    // Status changes are mirrored, whether the entire config or only the status is updated
+    reserve_f
+        .try_update_reserve(test_f.payer_keypair(), TEST_RESERVE_CONFIG)
+        .await
+        .unwrap();
+    let registry: ReserveRegistry = test_f.load_and_deserialize(&registry_key).await;
+    assert_eq!(registry.reserves()[0].status(), ReserveStatus::Active);
+
+    reserve_f
+        .try_update_reserve_config(
+            &test_f.payer_keypair(),
+            ReserveConfigUpdate::ReserveStatus(ReserveStatus::Obsolete),
+        )
+        .await
+        .unwrap();
+    let registry: ReserveRegistry = test_f.load_and_deserialize(&registry_key).await;
+    assert_eq!(registry.reserves()[0].status(), ReserveStatus::Obsolete);
+}
+
+#[tokio::test]
+async fn success_market_without_registry() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market().await;
+
+    let reserve_f = test_f
+        .new_reserve(&lending_market_f, test_f.usdc_mint.key)
+        .await;
+    assert_eq!(reserve_f.reserve_registry, None);
+
+    reserve_f
+        .try_update_reserve(test_f.payer_keypair(), TEST_RESERVE_CONFIG)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn failure_init_reserve_registry_outside_its_pda() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market().await;
+
+    let registry_key = reserve_registry(&lending_market_f.key);
+    let mut ix = lending_market_f.init_reserve_registry_ix().unwrap();
+    for meta in ix.accounts.iter_mut() {
+        if meta.pubkey == registry_key {
+            meta.pubkey = Pubkey::new_unique();
+        }
+    }
+
+    let r = test_f
+        .send_transaction(vec![ix], &[test_f.payer_keypair()])
+        .await;
+    assert_anchor_error!(r.unwrap_err(), ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn failure_init_reserve_registry_by_other_signer() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market().await;
+
+    // Funded, so that only the owner check can fail
+    let other = Keypair::new();
+    let fund_ix = system_instruction::transfer(&test_f.payer(), &other.pubkey(), 1_000_000_000);
+    let mut ix = lending_market_f.init_reserve_registry_ix().unwrap();
+    ix.accounts[0].pubkey = other.pubkey();
+
+    let r = test_f
+        .send_transaction(vec![fund_ix, ix], &[test_f.payer_keypair(), other])
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::InvalidMarketOwner);
+}
+
+#[tokio::test]
+async fn failure_status_change_without_registry() {
+    let test_f = TestFixture::new().await;
+    let lending_market_f = test_f.new_lending_market_with_registry().await;
+
+    let mut reserve_f = test_f
+        .new_reserve(&lending_market_f, test_f.usdc_mint.key)
+        .await;
+    reserve_f
+        .try_update_reserve(test_f.payer_keypair(), TEST_RESERVE_CONFIG)
+        .await
+        .unwrap();
+
+    reserve_f.reserve_registry = None;
+    let r = reserve_f
+        .try_update_reserve_config(
+            &test_f.payer_keypair(),
+            ReserveConfigUpdate::ReserveStatus(ReserveStatus::Obsolete),
+        )
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::ReserveRegistryMissing);
+}
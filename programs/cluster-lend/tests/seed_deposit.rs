@@ -0,0 +1,34 @@
+use cluster_lend::{
+    constants::{initial_deposit_amount, MIN_INITIAL_DEPOSIT_AMOUNT},
+    Reserve,
+};
+
+#[test]
+fn seed_deposit_mints_at_the_initial_rate() {
+    let mut reserve = Reserve::default();
+
+    let locked = reserve
+        .deposit_liquidity(MIN_INITIAL_DEPOSIT_AMOUNT)
+        .unwrap();
+    assert_eq!(locked, MIN_INITIAL_DEPOSIT_AMOUNT);
+    assert_eq!(reserve.collateral.mint_total_supply, locked);
+    assert_eq!(
+        reserve.liquidity.available_amount,
+        MIN_INITIAL_DEPOSIT_AMOUNT
+    );
+
+    // The first real depositor gets the same rate, and the seed can never be redeemed by them
+    let minted = reserve.deposit_liquidity(5_000).unwrap();
+    assert_eq!(minted, 5_000);
+    assert_eq!(reserve.redeem_collateral(minted).unwrap(), 5_000);
+    assert_eq!(reserve.collateral.mint_total_supply, locked);
+    assert!(reserve.is_empty(locked).unwrap());
+}
+
+#[test]
+fn seed_deposit_scales_with_decimals() {
+    assert_eq!(initial_deposit_amount(0), MIN_INITIAL_DEPOSIT_AMOUNT);
+    assert_eq!(initial_deposit_amount(6), 1_000);
+    assert_eq!(initial_deposit_amount(9), 1_000_000);
+    assert_eq!(initial_deposit_amount(255), 10_000_000_000_000_000);
+}
@@ -0,0 +1,164 @@
+#[cfg(test)]
+mod helpers;
+use std::rc::Rc;
+
+use cluster_lend::{constants::initial_deposit_amount, errors::LendingError, Reserve};
+use reserve::ReserveFixture;
+use solana_program_test::*;
+
+use helpers::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl::{balance_of, TokenAccountFixture};
+use test::{TestFixture, USDC_MINT_DECIMALS};
+
+#[test]
+fn vault_excess_ignores_accounted_tokens() {
+    let mut reserve = Reserve {
+        obligation_collateral_tracked: 1,
+        ..Default::default()
+    };
+    reserve.liquidity.available_amount = 1_000;
+    reserve.deposit_obligation_collateral(400).unwrap();
+
+    assert_eq!(reserve.vault_excess(1_000, 400), (0, 0));
+    assert_eq!(reserve.vault_excess(1_250, 410), (250, 10));
+
+    reserve.withdraw_obligation_collateral(400).unwrap();
+    assert_eq!(reserve.obligation_collateral_amount, 0);
+    assert!(reserve.withdraw_obligation_collateral(1).is_err());
+}
+
+#[test]
+fn untracked_reserves_never_report_collateral_excess() {
+    let mut reserve = Reserve::default();
+    reserve.liquidity.available_amount = 1_000;
+
+    // Older reserves hold obligation deposits the counter never saw
+    reserve.deposit_obligation_collateral(400).unwrap();
+    assert_eq!(reserve.obligation_collateral_amount, 0);
+    reserve.withdraw_obligation_collateral(400).unwrap();
+
+    assert_eq!(reserve.vault_excess(1_100, 5_000), (100, 0));
+}
+
+struct SkimAccounts {
+    reserve_f: ReserveFixture,
+    liquidity_destination: TokenAccountFixture,
+    collateral_destination: TokenAccountFixture,
+}
+
+async fn setup(test_f: &TestFixture) -> SkimAccounts {
+    let lending_market_f = test_f.new_lending_market().await;
+    let reserve_f = test_f
+        .new_reserve(&lending_market_f, test_f.usdc_mint.key)
+        .await;
+
+    let liquidity_destination = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &test_f.usdc_mint.key,
+        &test_f.payer(),
+    )
+    .await;
+    let collateral_destination = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &reserve_f.reserve_collateral_mint,
+        &test_f.payer(),
+    )
+    .await;
+
+    SkimAccounts {
+        reserve_f,
+        liquidity_destination,
+        collateral_destination,
+    }
+}
+
+/// Sends `amount` liquidity straight into the reserve supply vault.
+async fn donate_liquidity(test_f: &TestFixture, reserve_f: &ReserveFixture, amount: u64) {
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    test_f
+        .send_transaction(
+            vec![test_f
+                .usdc_mint
+                .make_mint_to_ix(&reserve.liquidity.supply_vault, amount)],
+            &[test_f.payer_keypair()],
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn success_skim_donated_liquidity() {
+    let test_f = TestFixture::new().await;
+    let accounts = setup(&test_f).await;
+    let reserve_f = &accounts.reserve_f;
+    donate_liquidity(&test_f, reserve_f, 500).await;
+
+    let r = test_f
+        .send_transaction(
+            vec![reserve_f
+                .skim_reserve_excess_ix(
+                    accounts.liquidity_destination.key,
+                    accounts.collateral_destination.key,
+                )
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert!(r.is_ok());
+
+    // Only the donation leaves, the seed deposit stays with the reserve
+    assert_eq!(accounts.liquidity_destination.balance().await, 500);
+    assert_eq!(accounts.collateral_destination.balance().await, 0);
+    let seed_amount = initial_deposit_amount(USDC_MINT_DECIMALS);
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    assert_eq!(
+        balance_of(Rc::clone(&test_f.context), reserve.liquidity.supply_vault).await,
+        seed_amount
+    );
+    assert_eq!(reserve.liquidity.available_amount, seed_amount);
+}
+
+#[tokio::test]
+async fn failure_skim_by_other_signer() {
+    let test_f = TestFixture::new().await;
+    let accounts = setup(&test_f).await;
+    let reserve_f = &accounts.reserve_f;
+    donate_liquidity(&test_f, reserve_f, 500).await;
+
+    let other = Keypair::new();
+    let ix = reserve_f.with_signer(
+        reserve_f
+            .skim_reserve_excess_ix(
+                accounts.liquidity_destination.key,
+                accounts.collateral_destination.key,
+            )
+            .unwrap(),
+        other.pubkey(),
+    );
+
+    let r = test_f
+        .send_transaction(vec![ix], &[test_f.payer_keypair(), other])
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::InvalidMarketOwner);
+}
+
+#[tokio::test]
+async fn failure_skim_without_excess() {
+    let test_f = TestFixture::new().await;
+    let accounts = setup(&test_f).await;
+
+    let r = test_f
+        .send_transaction(
+            vec![accounts
+                .reserve_f
+                .skim_reserve_excess_ix(
+                    accounts.liquidity_destination.key,
+                    accounts.collateral_destination.key,
+                )
+                .unwrap()],
+            &[test_f.payer_keypair()],
+        )
+        .await;
+    assert_custom_error!(r.unwrap_err(), LendingError::InvalidAmount);
+}
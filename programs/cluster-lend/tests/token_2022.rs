@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod helpers;
+use std::rc::Rc;
+
+use anchor_lang::AnchorDeserialize;
+use cluster_lend::{constants::initial_deposit_amount, Reserve};
+use lending_market::LendingMarketFixture;
+
+use reserve::ReserveFixture;
+use solana_program_test::*;
+
+use helpers::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use spl::{balance_of, MintFixture, TokenAccountFixture};
+use test::{TestFixture, TEST_RESERVE_CONFIG, USDC_MINT_DECIMALS, USDC_QUOTE_CURRENCY};
+use utils::clone_keypair;
+
+/// 1% transfer fee
+const TRANSFER_FEE_BPS: u16 = 100;
+
+#[tokio::test]
+async fn success_deposit_credits_amount_net_of_transfer_fee() {
+    let test_f = TestFixture::new().await;
+
+    let lending_market_f = LendingMarketFixture::new(
+        Rc::clone(&test_f.context),
+        USDC_QUOTE_CURRENCY,
+        &Keypair::new(),
+    )
+    .await
+    .unwrap();
+
+    let mint_f = MintFixture::new_with_transfer_fee(
+        Rc::clone(&test_f.context),
+        USDC_MINT_DECIMALS,
+        TRANSFER_FEE_BPS,
+        u64::MAX,
+    )
+    .await;
+
+    let reserve_f = ReserveFixture::new(
+        Rc::clone(&test_f.context),
+        lending_market_f.key,
+        mint_f.key,
+        &Keypair::new(),
+    )
+    .await
+    .unwrap();
+
+    // The seed deposit is credited net of the fee withheld on its transfer
+    let seed_amount = initial_deposit_amount(USDC_MINT_DECIMALS);
+    let net_seed_amount = seed_amount - seed_amount / 100;
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    assert_eq!(reserve.liquidity.available_amount, net_seed_amount);
+    assert_eq!(reserve.collateral.mint_total_supply, net_seed_amount);
+
+    reserve_f
+        .try_update_reserve(test_f.payer_keypair(), TEST_RESERVE_CONFIG)
+        .await
+        .unwrap();
+
+    let depositor = Keypair::new();
+    let depositor_ata_f = mint_f
+        .create_token_account_and_mint_to(&depositor, 100)
+        .await;
+    let user_destination_collateral = TokenAccountFixture::new(
+        Rc::clone(&test_f.context),
+        &reserve_f.reserve_collateral_mint,
+        &depositor.pubkey(),
+    )
+    .await
+    .key;
+
+    let liquidity_amount = 1_000_000;
+    let net_liquidity_amount = liquidity_amount - liquidity_amount / 100;
+    let ix = reserve_f
+        .deposit_reserve_ix(
+            liquidity_amount,
+            depositor_ata_f.key,
+            user_destination_collateral,
+        )
+        .unwrap();
+    let ix = reserve_f.with_signer(ix, depositor.pubkey());
+
+    // The minted collateral is returned to the caller
+    let return_data = test_f
+        .simulate_return_data(
+            vec![ix.clone()],
+            &[test_f.payer_keypair(), clone_keypair(&depositor)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(return_data.program_id, cluster_lend::id());
+    assert_eq!(
+        u64::deserialize(&mut return_data.data.as_slice()).unwrap(),
+        net_liquidity_amount
+    );
+
+    test_f
+        .send_transaction(vec![ix], &[test_f.payer_keypair(), depositor])
+        .await
+        .unwrap();
+
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_f.key).await;
+    assert_eq!(
+        reserve.liquidity.available_amount,
+        net_seed_amount + net_liquidity_amount
+    );
+    assert_eq!(
+        balance_of(Rc::clone(&test_f.context), reserve.liquidity.supply_vault).await,
+        net_seed_amount + net_liquidity_amount
+    );
+    assert_eq!(
+        balance_of(Rc::clone(&test_f.context), user_destination_collateral).await,
+        net_liquidity_amount
+    );
+    assert_eq!(
+        depositor_ata_f.balance().await,
+        100_000_000 - liquidity_amount
+    );
+}